@@ -0,0 +1,72 @@
+use core::fmt;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use serde::de::{SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
+
+const SAMPLES: usize = 1024;
+
+#[derive(Serialize, Deserialize)]
+struct Bulk {
+    #[serde(with = "postcard::fixint::le_array")]
+    samples: [f32; SAMPLES],
+}
+
+/// The same wire format, decoding one `f32` at a time like serde's own arrays do
+struct PerElement {
+    samples: [f32; SAMPLES],
+}
+
+impl<'de> Deserialize<'de> for PerElement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ElementVisitor;
+
+        impl<'de> Visitor<'de> for ElementVisitor {
+            type Value = PerElement;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "{} f32s", SAMPLES)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<PerElement, A::Error> {
+                let mut samples = [0.0; SAMPLES];
+                for (i, sample) in samples.iter_mut().enumerate() {
+                    *sample = seq
+                        .next_element()?
+                        .ok_or_else(|| serde::de::Error::invalid_length(i, &self))?;
+                }
+                Ok(PerElement { samples })
+            }
+        }
+
+        deserializer.deserialize_tuple(SAMPLES, ElementVisitor)
+    }
+}
+
+fn le_array(c: &mut Criterion) {
+    let input = Bulk {
+        samples: core::array::from_fn(|i| (i as f32 * 0.1).sin()),
+    };
+    let mut buf = vec![0u8; SAMPLES * 4];
+    let used = postcard::to_slice(&input, &mut buf).unwrap();
+
+    let mut group = c.benchmark_group("le_array");
+
+    group.bench_function("bulk", |b| {
+        b.iter(|| {
+            let out: Bulk = postcard::from_bytes(black_box(used)).unwrap();
+            out.samples.iter().sum::<f32>()
+        })
+    });
+
+    group.bench_function("per_element", |b| {
+        b.iter(|| {
+            let out: PerElement = postcard::from_bytes(black_box(used)).unwrap();
+            out.samples.iter().sum::<f32>()
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, le_array);
+criterion_main!(benches);
@@ -0,0 +1,54 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use postcard::fixint::LeSlice;
+
+const SAMPLES: usize = 4096;
+
+fn le_slice(c: &mut Criterion) {
+    let data: Vec<u32> = (0..SAMPLES as u32)
+        .map(|i| i.wrapping_mul(0x9E37_79B9))
+        .collect();
+
+    // Place the message so that the elements (after the length prefix) are 4-byte aligned
+    let mut storage = vec![0u32; SAMPLES + 4];
+    let buf: &mut [u8] = unsafe {
+        core::slice::from_raw_parts_mut(storage.as_mut_ptr().cast::<u8>(), storage.len() * 4)
+    };
+    let prefix = postcard::to_slice(&LeSlice::new(&data), buf).unwrap().len() - SAMPLES * 4;
+    let offset = (4 - prefix % 4) % 4;
+    let len = postcard::to_slice(&LeSlice::new(&data), &mut buf[offset..])
+        .unwrap()
+        .len();
+    let aligned = &buf[offset..][..len];
+
+    let mut group = c.benchmark_group("le_slice");
+
+    group.bench_function("borrow", |b| {
+        b.iter(|| {
+            let out: LeSlice<'_, u32> = postcard::from_bytes(black_box(aligned)).unwrap();
+            out.as_slice()
+                .unwrap()
+                .iter()
+                .fold(0u32, |a, b| a.wrapping_add(*b))
+        })
+    });
+
+    group.bench_function("iter", |b| {
+        b.iter(|| {
+            let out: LeSlice<'_, u32> = postcard::from_bytes(black_box(aligned)).unwrap();
+            out.iter().fold(0u32, |a, b| a.wrapping_add(b))
+        })
+    });
+
+    let varint = postcard::to_allocvec(&data).unwrap();
+    group.bench_function("varint_vec", |b| {
+        b.iter(|| {
+            let out: Vec<u32> = postcard::from_bytes(black_box(&varint)).unwrap();
+            out.iter().fold(0u32, |a, b| a.wrapping_add(*b))
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, le_slice);
+criterion_main!(benches);
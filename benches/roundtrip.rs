@@ -0,0 +1,121 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Telemetry {
+    id: u32,
+    seq: u64,
+    temp: i16,
+    humidity: u8,
+    pressure: f32,
+    flags: u16,
+    position: (i32, i32, i32),
+    valid: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+struct Record<'a> {
+    name: &'a str,
+    unit: &'a str,
+    tags: Vec<&'a str>,
+    description: &'a str,
+}
+
+const SAMPLES: usize = 4096;
+
+fn telemetry(i: u32) -> Telemetry {
+    Telemetry {
+        id: i,
+        seq: u64::from(i) * 1_000_003,
+        temp: (i % 400) as i16 - 200,
+        humidity: (i % 100) as u8,
+        pressure: 1013.25 + i as f32,
+        flags: (i % 7) as u16,
+        position: (i as i32 * 3, -(i as i32), 42),
+        valid: i & 1 == 0,
+    }
+}
+
+fn structs(c: &mut Criterion) {
+    let data: Vec<Telemetry> = (0..SAMPLES as u32).map(telemetry).collect();
+    let ser = postcard::to_allocvec(&data).unwrap();
+    let mut buf = vec![0u8; ser.len()];
+
+    let mut group = c.benchmark_group("struct");
+    group.throughput(Throughput::Bytes(ser.len() as u64));
+    group.bench_function("serialize", |b| {
+        b.iter(|| {
+            postcard::to_slice(black_box(&data), &mut buf)
+                .unwrap()
+                .len()
+        })
+    });
+    group.bench_function("deserialize", |b| {
+        b.iter(|| postcard::from_bytes::<Vec<Telemetry>>(black_box(&ser)).unwrap())
+    });
+    group.finish();
+}
+
+fn u32_vec(c: &mut Criterion) {
+    // A mix of small and large values, so that varints of every length are present
+    let data: Vec<u32> = (0..SAMPLES as u32)
+        .map(|i| {
+            if i & 3 == 0 {
+                i.wrapping_mul(0x9E37_79B9)
+            } else {
+                i % 100
+            }
+        })
+        .collect();
+    let ser = postcard::to_allocvec(&data).unwrap();
+    let mut buf = vec![0u8; ser.len()];
+
+    let mut group = c.benchmark_group("u32_vec");
+    group.throughput(Throughput::Bytes(ser.len() as u64));
+    group.bench_function("serialize", |b| {
+        b.iter(|| {
+            postcard::to_slice(black_box(&data), &mut buf)
+                .unwrap()
+                .len()
+        })
+    });
+    group.bench_function("serialize_allocvec", |b| {
+        b.iter(|| postcard::to_allocvec(black_box(&data)).unwrap())
+    });
+    group.bench_function("deserialize", |b| {
+        b.iter(|| postcard::from_bytes::<Vec<u32>>(black_box(&ser)).unwrap())
+    });
+    group.finish();
+}
+
+fn strings(c: &mut Criterion) {
+    let names: Vec<String> = (0..SAMPLES).map(|i| format!("sensor-{}", i)).collect();
+    let data: Vec<Record<'_>> = names
+        .iter()
+        .map(|name| Record {
+            name,
+            unit: "degrees celsius",
+            tags: vec!["outdoor", "north", "rooftop"],
+            description: "A temperature sensor, mounted on the north side of the roof",
+        })
+        .collect();
+    let ser = postcard::to_allocvec(&data).unwrap();
+    let mut buf = vec![0u8; ser.len()];
+
+    let mut group = c.benchmark_group("strings");
+    group.throughput(Throughput::Bytes(ser.len() as u64));
+    group.bench_function("serialize", |b| {
+        b.iter(|| {
+            postcard::to_slice(black_box(&data), &mut buf)
+                .unwrap()
+                .len()
+        })
+    });
+    group.bench_function("deserialize", |b| {
+        b.iter(|| postcard::from_bytes::<Vec<Record<'_>>>(black_box(&ser)).unwrap())
+    });
+    group.finish();
+}
+
+criterion_group!(benches, structs, u32_vec, strings);
+criterion_main!(benches);
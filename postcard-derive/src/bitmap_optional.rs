@@ -0,0 +1,162 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    parse_macro_input, spanned::Spanned, Data, DeriveInput, Fields, GenericArgument, PathArguments,
+    Type,
+};
+
+pub fn do_derive_bitmap_optional(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream, syn::Error> {
+    let span = input.span();
+    let name = input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "generics are not supported by `postcard::BitmapOptional`",
+        ));
+    }
+
+    let fields = match input.data {
+        Data::Struct(data) => match data.fields {
+            Fields::Named(fields) => fields.named,
+            _ => return Err(unsupported(span)),
+        },
+        _ => return Err(unsupported(span)),
+    };
+
+    let option_count = fields
+        .iter()
+        .filter(|f| option_inner(&f.ty).is_some())
+        .count();
+    let max_len = 1 + fields.len();
+    let expecting = format!("struct {} with a presence bitmap", name);
+
+    let mut ser_fields = Vec::new();
+    let mut de_fields = Vec::new();
+    let mut bit = 0usize;
+    for (idx, field) in fields.iter().enumerate() {
+        let ident = &field.ident;
+        let local = format_ident!("__field{}", idx);
+        match option_inner(&field.ty) {
+            Some(inner) => {
+                ser_fields.push(quote! {
+                    if let ::core::option::Option::Some(value) = &self.#ident {
+                        tup.serialize_element(value)?;
+                    }
+                });
+                de_fields.push(quote! {
+                    let #local: ::core::option::Option<#inner> = if present.0[#bit] {
+                        ::core::option::Option::Some(
+                            seq.next_element()?
+                                .ok_or_else(|| ::serde::de::Error::invalid_length(#idx + 1, &self))?,
+                        )
+                    } else {
+                        ::core::option::Option::None
+                    };
+                });
+                bit += 1;
+            }
+            None => {
+                ser_fields.push(quote! {
+                    tup.serialize_element(&self.#ident)?;
+                });
+                de_fields.push(quote! {
+                    let #local = seq
+                        .next_element()?
+                        .ok_or_else(|| ::serde::de::Error::invalid_length(#idx + 1, &self))?;
+                });
+            }
+        }
+    }
+    let option_names = fields
+        .iter()
+        .filter(|f| option_inner(&f.ty).is_some())
+        .map(|f| &f.ident);
+    let idents = fields.iter().map(|f| &f.ident);
+    let locals = (0..fields.len()).map(|i| format_ident!("__field{}", i));
+
+    Ok(quote! {
+        impl ::serde::Serialize for #name {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                use ::serde::ser::SerializeTuple;
+
+                let present = ::postcard::packed::PackedBools([#(self.#option_names.is_some()),*]);
+                let len = #max_len - present.iter().filter(|p| !**p).count();
+                let mut tup = serializer.serialize_tuple(len)?;
+                tup.serialize_element(&present)?;
+                #(#ser_fields)*
+                tup.end()
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for #name {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: ::serde::Deserializer<'de>,
+            {
+                struct BitmapVisitor;
+
+                impl<'de> ::serde::de::Visitor<'de> for BitmapVisitor {
+                    type Value = #name;
+
+                    fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                        formatter.write_str(#expecting)
+                    }
+
+                    fn visit_seq<A>(self, mut seq: A) -> ::core::result::Result<#name, A::Error>
+                    where
+                        A: ::serde::de::SeqAccess<'de>,
+                    {
+                        let present: ::postcard::packed::PackedBools<#option_count> = seq
+                            .next_element()?
+                            .ok_or_else(|| ::serde::de::Error::invalid_length(0, &self))?;
+                        #(#de_fields)*
+                        ::core::result::Result::Ok(#name {
+                            #(#idents: #locals,)*
+                        })
+                    }
+                }
+
+                // Absent fields are not on the wire, so this is only an upper bound
+                deserializer.deserialize_tuple(#max_len, BitmapVisitor)
+            }
+        }
+    })
+}
+
+fn unsupported(span: Span) -> syn::Error {
+    syn::Error::new(
+        span,
+        "only structs with named fields are supported by `postcard::BitmapOptional`",
+    )
+}
+
+/// The `T` of an `Option<T>` field
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return None,
+    };
+    let last = path.segments.last()?;
+    if last.ident != "Option" {
+        return None;
+    }
+    match &last.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,132 @@
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use syn::{parse_macro_input, spanned::Spanned, Attribute, Data, DeriveInput, Fields};
+
+pub fn do_derive_c_layout(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream, syn::Error> {
+    let span = input.span();
+    let name = input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "generics are not supported by `postcard::CLayout`",
+        ));
+    }
+    if !is_repr_c(&input.attrs) {
+        return Err(syn::Error::new(
+            span,
+            "`postcard::CLayout` requires the struct to be `#[repr(C)]`",
+        ));
+    }
+
+    let fields = match input.data {
+        Data::Struct(data) => match data.fields {
+            Fields::Named(fields) => fields.named,
+            _ => return Err(unsupported(span)),
+        },
+        _ => return Err(unsupported(span)),
+    };
+
+    let names: Vec<_> = fields.iter().map(|f| &f.ident).collect();
+    let types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+    let expecting = format!("struct {} in its C layout", name);
+
+    Ok(quote! {
+        impl ::postcard::experimental::c_layout::CLayout for #name {
+            fn serialize_c<S>(&self, tup: &mut S) -> ::core::result::Result<(), S::Error>
+            where
+                S: ::serde::ser::SerializeTuple,
+            {
+                let mut cursor = 0usize;
+                #(
+                    let offset = ::core::mem::offset_of!(#name, #names);
+                    ::postcard::experimental::c_layout::__pad(tup, offset - cursor)?;
+                    ::postcard::experimental::c_layout::CLayout::serialize_c(&self.#names, tup)?;
+                    cursor = offset + ::core::mem::size_of::<#types>();
+                )*
+                ::postcard::experimental::c_layout::__pad(tup, ::core::mem::size_of::<Self>() - cursor)
+            }
+
+            fn deserialize_c<'de, A>(seq: &mut A) -> ::core::result::Result<Self, A::Error>
+            where
+                A: ::serde::de::SeqAccess<'de>,
+            {
+                let mut cursor = 0usize;
+                #(
+                    let offset = ::core::mem::offset_of!(#name, #names);
+                    ::postcard::experimental::c_layout::__skip(seq, offset - cursor)?;
+                    let #names = <#types as ::postcard::experimental::c_layout::CLayout>::deserialize_c(seq)?;
+                    cursor = offset + ::core::mem::size_of::<#types>();
+                )*
+                ::postcard::experimental::c_layout::__skip(seq, ::core::mem::size_of::<Self>() - cursor)?;
+                ::core::result::Result::Ok(#name { #(#names),* })
+            }
+        }
+
+        impl ::serde::Serialize for #name {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                use ::serde::ser::SerializeTuple;
+
+                let mut tup = serializer.serialize_tuple(::core::mem::size_of::<Self>())?;
+                ::postcard::experimental::c_layout::CLayout::serialize_c(self, &mut tup)?;
+                tup.end()
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for #name {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: ::serde::Deserializer<'de>,
+            {
+                struct CLayoutVisitor;
+
+                impl<'de> ::serde::de::Visitor<'de> for CLayoutVisitor {
+                    type Value = #name;
+
+                    fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                        formatter.write_str(#expecting)
+                    }
+
+                    fn visit_seq<A>(self, mut seq: A) -> ::core::result::Result<#name, A::Error>
+                    where
+                        A: ::serde::de::SeqAccess<'de>,
+                    {
+                        <#name as ::postcard::experimental::c_layout::CLayout>::deserialize_c(&mut seq)
+                    }
+                }
+
+                deserializer.deserialize_tuple(::core::mem::size_of::<Self>(), CLayoutVisitor)
+            }
+        }
+    })
+}
+
+fn unsupported(span: Span) -> syn::Error {
+    syn::Error::new(
+        span,
+        "only structs with named fields are supported by `postcard::CLayout`",
+    )
+}
+
+fn is_repr_c(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path.is_ident("repr")
+            && attr
+                .parse_args_with(
+                    syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
+                )
+                .map(|reprs| reprs.iter().any(|r| r.is_ident("C")))
+                .unwrap_or(false)
+    })
+}
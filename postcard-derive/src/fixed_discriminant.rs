@@ -0,0 +1,152 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Fields};
+
+pub fn do_derive_fixed_discriminant(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream, syn::Error> {
+    let span = input.span();
+    let name = input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "generics are not supported by `postcard::FixedDiscriminant`",
+        ));
+    }
+
+    let variants = match input.data {
+        Data::Enum(data) => data.variants,
+        _ => return Err(unsupported(span)),
+    };
+    if variants.is_empty() || variants.len() > 1 << 16 {
+        return Err(syn::Error::new(
+            span,
+            "`postcard::FixedDiscriminant` requires between 1 and 65536 variants",
+        ));
+    }
+
+    // The discriminant is a single byte where possible, and two little endian bytes otherwise
+    let wide = variants.len() > 1 << 8;
+    let (tag_ty, ser_tag, de_tag) = if wide {
+        (
+            quote!([u8; 2]),
+            quote!(&(tag as u16).to_le_bytes()),
+            quote!(u16::from_le_bytes(tag) as usize),
+        )
+    } else {
+        (quote!(u8), quote!(&(tag as u8)), quote!(tag as usize))
+    };
+    let max_len = 1 + variants.iter().map(|v| v.fields.len()).max().unwrap_or(0);
+    let expecting = format!("enum {} with a fixed width discriminant", name);
+
+    let mut ser_arms = Vec::new();
+    let mut de_arms = Vec::new();
+    for (tag, variant) in variants.iter().enumerate() {
+        let ident = &variant.ident;
+        let len = 1 + variant.fields.len();
+        let locals: Vec<_> = (0..variant.fields.len())
+            .map(|i| format_ident!("__field{}", i))
+            .collect();
+        let types = variant.fields.iter().map(|f| &f.ty);
+        let indices = 1..len;
+        let (pattern, construct) = match &variant.fields {
+            Fields::Named(fields) => {
+                let idents: Vec<_> = fields.named.iter().map(|f| &f.ident).collect();
+                (
+                    quote!(#name::#ident { #(#idents: #locals),* }),
+                    quote!(#name::#ident { #(#idents: #locals),* }),
+                )
+            }
+            Fields::Unnamed(_) => (
+                quote!(#name::#ident(#(#locals),*)),
+                quote!(#name::#ident(#(#locals),*)),
+            ),
+            Fields::Unit => (quote!(#name::#ident), quote!(#name::#ident)),
+        };
+
+        ser_arms.push(quote! {
+            #pattern => {
+                let tag = #tag;
+                let mut tup = serializer.serialize_tuple(#len)?;
+                tup.serialize_element(#ser_tag)?;
+                #(tup.serialize_element(#locals)?;)*
+                tup.end()
+            }
+        });
+        de_arms.push(quote! {
+            #tag => {
+                #(
+                    let #locals: #types = seq
+                        .next_element()?
+                        .ok_or_else(|| ::serde::de::Error::invalid_length(#indices, &self))?;
+                )*
+                ::core::result::Result::Ok(#construct)
+            }
+        });
+    }
+
+    Ok(quote! {
+        impl ::serde::Serialize for #name {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                use ::serde::ser::SerializeTuple;
+
+                match self {
+                    #(#ser_arms)*
+                }
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for #name {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: ::serde::Deserializer<'de>,
+            {
+                struct FixedDiscriminantVisitor;
+
+                impl<'de> ::serde::de::Visitor<'de> for FixedDiscriminantVisitor {
+                    type Value = #name;
+
+                    fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                        formatter.write_str(#expecting)
+                    }
+
+                    fn visit_seq<A>(self, mut seq: A) -> ::core::result::Result<#name, A::Error>
+                    where
+                        A: ::serde::de::SeqAccess<'de>,
+                    {
+                        let tag: #tag_ty = seq
+                            .next_element()?
+                            .ok_or_else(|| ::serde::de::Error::invalid_length(0, &self))?;
+                        match #de_tag {
+                            #(#de_arms)*
+                            other => ::core::result::Result::Err(::serde::de::Error::invalid_value(
+                                ::serde::de::Unexpected::Unsigned(other as u64),
+                                &self,
+                            )),
+                        }
+                    }
+                }
+
+                // Variants without fields are shorter, so this is only an upper bound
+                deserializer.deserialize_tuple(#max_len, FixedDiscriminantVisitor)
+            }
+        }
+    })
+}
+
+fn unsupported(span: Span) -> syn::Error {
+    syn::Error::new(
+        span,
+        "only enums are supported by `postcard::FixedDiscriminant`",
+    )
+}
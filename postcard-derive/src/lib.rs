@@ -1,12 +1,51 @@
+mod bitmap_optional;
+mod c_layout;
+mod fixed_discriminant;
 mod max_size;
+mod no_maps;
+mod packed;
 mod schema;
 
+/// Derive `serde::Serialize` and `serde::Deserialize` for a struct, collecting the
+/// presence of all of its `Option` fields into a leading bitmap.
+#[proc_macro_derive(BitmapOptional)]
+pub fn derive_bitmap_optional(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    bitmap_optional::do_derive_bitmap_optional(item)
+}
+
+/// Derive the `postcard::CLayout` trait for a `#[repr(C)]` struct, along with
+/// `serde::Serialize` and `serde::Deserialize` using its C memory layout.
+#[proc_macro_derive(CLayout)]
+pub fn derive_c_layout(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    c_layout::do_derive_c_layout(item)
+}
+
+/// Derive `serde::Serialize` and `serde::Deserialize` for an enum, using a fixed width
+/// discriminant chosen from its number of variants.
+#[proc_macro_derive(FixedDiscriminant)]
+pub fn derive_fixed_discriminant(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    fixed_discriminant::do_derive_fixed_discriminant(item)
+}
+
 /// Derive the `postcard::MaxSize` trait for a struct or enum.
 #[proc_macro_derive(MaxSize)]
 pub fn derive_max_size(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
     max_size::do_derive_max_size(item)
 }
 
+/// Derive the `postcard::NoMaps` marker trait for a struct or enum.
+#[proc_macro_derive(NoMaps)]
+pub fn derive_no_maps(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    no_maps::do_derive_no_maps(item)
+}
+
+/// Derive `serde::Serialize` and `serde::Deserialize` for a struct, packing all of its
+/// `bool` fields into a leading bitmap.
+#[proc_macro_derive(PackedSerialize)]
+pub fn derive_packed_serialize(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    packed::do_derive_packed_serialize(item)
+}
+
 /// Derive the `postcard::Schema` trait for a struct or enum.
 #[proc_macro_derive(Schema)]
 pub fn derive_schema(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
@@ -0,0 +1,68 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Data, DeriveInput, Fields, GenericParam,
+    Generics,
+};
+
+pub fn do_derive_no_maps(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+
+    let span = input.span();
+    let name = input.ident;
+
+    // Add a bound `T: NoMaps` to every type parameter T.
+    let generics = add_trait_bounds(input.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let bounds = field_bounds(&input.data, span).unwrap_or_else(syn::Error::into_compile_error);
+    let where_clause = match where_clause {
+        Some(clause) => {
+            let predicates = &clause.predicates;
+            quote!(where #predicates, #bounds)
+        }
+        None => quote!(where #bounds),
+    };
+
+    let expanded = quote! {
+        impl #impl_generics ::postcard::experimental::no_maps::NoMaps for #name #ty_generics #where_clause {}
+    };
+
+    expanded.into()
+}
+
+/// Add a bound `T: NoMaps` to every type parameter T.
+fn add_trait_bounds(mut generics: Generics) -> Generics {
+    for param in &mut generics.params {
+        if let GenericParam::Type(ref mut type_param) = *param {
+            type_param
+                .bounds
+                .push(parse_quote!(::postcard::experimental::no_maps::NoMaps));
+        }
+    }
+    generics
+}
+
+/// Generate a `FieldType: NoMaps` bound for every field of the type.
+fn field_bounds(data: &Data, span: Span) -> Result<TokenStream, syn::Error> {
+    let fields: Vec<&Fields> = match data {
+        Data::Struct(data) => vec![&data.fields],
+        Data::Enum(data) => data.variants.iter().map(|v| &v.fields).collect(),
+        Data::Union(_) => {
+            return Err(syn::Error::new(
+                span,
+                "unions are not supported by `postcard::NoMaps`",
+            ))
+        }
+    };
+
+    let bounds = fields
+        .into_iter()
+        .flat_map(|fields| fields.iter())
+        .map(|f| {
+            let ty = &f.ty;
+            quote_spanned! { f.span() => #ty: ::postcard::experimental::no_maps::NoMaps }
+        });
+
+    Ok(quote! { #(#bounds,)* })
+}
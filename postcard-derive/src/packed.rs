@@ -0,0 +1,112 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Fields, Type};
+
+pub fn do_derive_packed_serialize(item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream, syn::Error> {
+    let span = input.span();
+    let name = input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            input.generics.span(),
+            "generics are not supported by `postcard::PackedSerialize`",
+        ));
+    }
+
+    let fields = match input.data {
+        Data::Struct(data) => match data.fields {
+            Fields::Named(fields) => fields.named,
+            _ => return Err(unsupported(span)),
+        },
+        _ => return Err(unsupported(span)),
+    };
+
+    let (bools, others): (Vec<_>, Vec<_>) = fields.iter().partition(|f| is_bool(&f.ty));
+    let bool_names: Vec<_> = bools.iter().map(|f| &f.ident).collect();
+    let bool_count = bools.len();
+    let bool_idx = 0..bool_count;
+    let other_names: Vec<_> = others.iter().map(|f| &f.ident).collect();
+    let other_locals: Vec<_> = (0..others.len())
+        .map(|i| format_ident!("__field{}", i))
+        .collect();
+    let other_idx = 1..=others.len();
+    let tuple_len = 1 + others.len();
+    let expecting = format!("struct {} with packed bools", name);
+
+    Ok(quote! {
+        impl ::serde::Serialize for #name {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: ::serde::Serializer,
+            {
+                use ::serde::ser::SerializeTuple;
+
+                let mut tup = serializer.serialize_tuple(#tuple_len)?;
+                tup.serialize_element(&::postcard::packed::PackedBools([#(self.#bool_names),*]))?;
+                #(
+                    tup.serialize_element(&self.#other_names)?;
+                )*
+                tup.end()
+            }
+        }
+
+        impl<'de> ::serde::Deserialize<'de> for #name {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: ::serde::Deserializer<'de>,
+            {
+                struct PackedVisitor;
+
+                impl<'de> ::serde::de::Visitor<'de> for PackedVisitor {
+                    type Value = #name;
+
+                    fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                        formatter.write_str(#expecting)
+                    }
+
+                    fn visit_seq<A>(self, mut seq: A) -> ::core::result::Result<#name, A::Error>
+                    where
+                        A: ::serde::de::SeqAccess<'de>,
+                    {
+                        let bools: ::postcard::packed::PackedBools<#bool_count> = seq
+                            .next_element()?
+                            .ok_or_else(|| ::serde::de::Error::invalid_length(0, &self))?;
+                        #(
+                            let #other_locals = seq
+                                .next_element()?
+                                .ok_or_else(|| ::serde::de::Error::invalid_length(#other_idx, &self))?;
+                        )*
+                        ::core::result::Result::Ok(#name {
+                            #(#bool_names: bools.0[#bool_idx],)*
+                            #(#other_names: #other_locals,)*
+                        })
+                    }
+                }
+
+                deserializer.deserialize_tuple(#tuple_len, PackedVisitor)
+            }
+        }
+    })
+}
+
+fn unsupported(span: Span) -> syn::Error {
+    syn::Error::new(
+        span,
+        "only structs with named fields are supported by `postcard::PackedSerialize`",
+    )
+}
+
+fn is_bool(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.qself.is_none() && path.path.is_ident("bool"),
+        _ => false,
+    }
+}
@@ -92,6 +92,7 @@ pub enum FeedResult<'a, T> {
 
 impl<const N: usize> CobsAccumulator<N> {
     /// Create a new accumulator.
+    #[allow(clippy::new_without_default)]
     pub const fn new() -> Self {
         CobsAccumulator {
             buf: [0; N],
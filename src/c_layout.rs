@@ -0,0 +1,90 @@
+use serde::de::{Error as _, SeqAccess};
+use serde::ser::SerializeTuple;
+
+/// A type that can be encoded in its `#[repr(C)]` memory layout, including padding.
+///
+/// Integers and floats are encoded with their full width in little endian order (like
+/// [`fixint::le`](crate::fixint::le)), rather than as varints, and padding bytes are
+/// written as zero. The output matches the memory of the value on a little endian
+/// target, so that it can be reinterpreted in place by C code.
+///
+/// This is implemented for primitive integers, floats, `bool`, arrays, and for
+/// `#[repr(C)]` structs using `#[derive(CLayout)]`. The derive also implements
+/// `Serialize` and `Deserialize` for the struct, using this layout. When deserializing,
+/// the contents of padding bytes are ignored.
+pub trait CLayout: Sized {
+    /// Serialize the bytes of `self`, exactly `size_of::<Self>()` of them
+    fn serialize_c<S: SerializeTuple>(&self, tup: &mut S) -> Result<(), S::Error>;
+
+    /// Deserialize a value from exactly `size_of::<Self>()` bytes
+    fn deserialize_c<'de, A: SeqAccess<'de>>(seq: &mut A) -> Result<Self, A::Error>;
+}
+
+fn next_byte<'de, A: SeqAccess<'de>>(seq: &mut A) -> Result<u8, A::Error> {
+    seq.next_element()?
+        .ok_or_else(|| A::Error::custom("unexpected end of C layout"))
+}
+
+macro_rules! impl_c_layout {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl CLayout for $ty {
+                fn serialize_c<S: SerializeTuple>(&self, tup: &mut S) -> Result<(), S::Error> {
+                    for byte in self.to_le_bytes().iter() {
+                        tup.serialize_element(byte)?;
+                    }
+                    Ok(())
+                }
+
+                fn deserialize_c<'de, A: SeqAccess<'de>>(seq: &mut A) -> Result<Self, A::Error> {
+                    let mut bytes = [0u8; core::mem::size_of::<$ty>()];
+                    for byte in bytes.iter_mut() {
+                        *byte = next_byte(seq)?;
+                    }
+                    Ok(<$ty>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+impl_c_layout!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl CLayout for bool {
+    fn serialize_c<S: SerializeTuple>(&self, tup: &mut S) -> Result<(), S::Error> {
+        tup.serialize_element(&(*self as u8))
+    }
+
+    fn deserialize_c<'de, A: SeqAccess<'de>>(seq: &mut A) -> Result<Self, A::Error> {
+        match next_byte(seq)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(A::Error::custom("invalid bool in C layout")),
+        }
+    }
+}
+
+impl<T: CLayout, const N: usize> CLayout for [T; N] {
+    fn serialize_c<S: SerializeTuple>(&self, tup: &mut S) -> Result<(), S::Error> {
+        self.iter().try_for_each(|t| t.serialize_c(tup))
+    }
+
+    fn deserialize_c<'de, A: SeqAccess<'de>>(seq: &mut A) -> Result<Self, A::Error> {
+        // Deserialize into an array of options, as `T` may not be `Default` or `Copy`
+        let mut out: [Option<T>; N] = core::array::from_fn(|_| None);
+        for slot in out.iter_mut() {
+            *slot = Some(T::deserialize_c(seq)?);
+        }
+        Ok(out.map(|t| t.unwrap()))
+    }
+}
+
+#[doc(hidden)]
+pub fn __pad<S: SerializeTuple>(tup: &mut S, count: usize) -> Result<(), S::Error> {
+    (0..count).try_for_each(|_| tup.serialize_element(&0u8))
+}
+
+#[doc(hidden)]
+pub fn __skip<'de, A: SeqAccess<'de>>(seq: &mut A, count: usize) -> Result<(), A::Error> {
+    (0..count).try_for_each(|_| next_byte(seq).map(drop))
+}
@@ -0,0 +1,191 @@
+//! Conversion of messages to CBOR, using their schema
+
+use core::convert::TryFrom;
+
+use crate::error::Result;
+use crate::schema::Schema;
+use crate::value::{from_bytes_as_value, PostcardValue};
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+const UNSIGNED: u8 = 0;
+const NEGATIVE: u8 = 1;
+const BYTES: u8 = 2;
+const TEXT: u8 = 3;
+const ARRAY: u8 = 4;
+const MAP: u8 = 5;
+const TAG: u8 = 6;
+const SIMPLE: u8 = 7;
+
+const TAG_POSITIVE_BIGNUM: u64 = 2;
+const TAG_NEGATIVE_BIGNUM: u64 = 3;
+
+/// Convert a postcard message of type `T` to CBOR ([RFC 8949]), using the [`Schema`]
+/// of `T` to decode it.
+///
+/// The CBOR output uses the same mapping as serde's derives do with CBOR libraries
+/// like `ciborium`, so it can be deserialized as `T` directly:
+///
+/// | postcard                   | CBOR                                              |
+/// | :------------------------- | :------------------------------------------------ |
+/// | `bool`                     | `true` or `false`                                 |
+/// | integers                   | integer, or a bignum (tag 2 or 3) past 64 bits    |
+/// | `f32`, `f64`               | single or double precision float                  |
+/// | `char`, string             | text string                                       |
+/// | byte array                 | byte string                                       |
+/// | `None`, `()`, unit struct  | `null`                                            |
+/// | `Some(v)`, newtype struct  | `v`                                               |
+/// | seq, tuple, tuple struct   | array                                             |
+/// | map                        | map                                               |
+/// | struct                     | map, keyed by the field names                     |
+/// | unit variant               | text string of the variant name                   |
+/// | other variants             | map of the variant name to its contents           |
+///
+/// Lengths are always definite, and floats keep their width. The message is expected
+/// to use the default [`Config`](crate::Config). The unused portion (if any) of the
+/// byte slice is ignored.
+///
+/// [RFC 8949]: https://www.rfc-editor.org/rfc/rfc8949
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::experimental::schema::{to_cbor_via_postcard, Schema};
+///
+/// #[derive(Schema)]
+/// struct Reading {
+///     id: u8,
+///     value: i16,
+/// }
+///
+/// let cbor = to_cbor_via_postcard::<Reading>(&[0x01, 0x03]).unwrap();
+/// assert_eq!(
+///     cbor,
+///     &[
+///         0xA2, // map of 2 pairs
+///         0x62, b'i', b'd', 0x01, // "id": 1
+///         0x65, b'v', b'a', b'l', b'u', b'e', 0x21, // "value": -2
+///     ]
+/// );
+/// ```
+pub fn to_cbor_via_postcard<T>(s: &[u8]) -> Result<Vec<u8>>
+where
+    T: Schema + ?Sized,
+{
+    let value = from_bytes_as_value::<T>(s)?;
+    let mut out = Vec::new();
+    encode(&mut out, &value);
+    Ok(out)
+}
+
+fn encode(out: &mut Vec<u8>, value: &PostcardValue<'_>) {
+    match value {
+        PostcardValue::Bool(v) => out.push(simple(if *v { 21 } else { 20 })),
+        PostcardValue::I8(v) => encode_signed(out, (*v).into()),
+        PostcardValue::I16(v) => encode_signed(out, (*v).into()),
+        PostcardValue::I32(v) => encode_signed(out, (*v).into()),
+        PostcardValue::I64(v) => encode_signed(out, (*v).into()),
+        PostcardValue::I128(v) => encode_signed(out, *v),
+        PostcardValue::U8(v) => header(out, UNSIGNED, (*v).into()),
+        PostcardValue::U16(v) => header(out, UNSIGNED, (*v).into()),
+        PostcardValue::U32(v) => header(out, UNSIGNED, (*v).into()),
+        PostcardValue::U64(v) => header(out, UNSIGNED, *v),
+        PostcardValue::U128(v) => match u64::try_from(*v) {
+            Ok(v) => header(out, UNSIGNED, v),
+            Err(_) => encode_bignum(out, TAG_POSITIVE_BIGNUM, *v),
+        },
+        PostcardValue::F32(v) => {
+            out.push(simple(26));
+            out.extend_from_slice(&v.to_be_bytes());
+        }
+        PostcardValue::F64(v) => {
+            out.push(simple(27));
+            out.extend_from_slice(&v.to_be_bytes());
+        }
+        PostcardValue::Char(v) => encode_text(out, v.encode_utf8(&mut [0u8; 4])),
+        PostcardValue::String(v) => encode_text(out, v),
+        PostcardValue::Bytes(v) => {
+            header(out, BYTES, v.len() as u64);
+            out.extend_from_slice(v);
+        }
+        PostcardValue::Option(Some(v)) => encode(out, v),
+        PostcardValue::Option(None) | PostcardValue::Unit => out.push(simple(22)),
+        PostcardValue::Seq(items) | PostcardValue::Tuple(items) => {
+            header(out, ARRAY, items.len() as u64);
+            items.iter().for_each(|item| encode(out, item));
+        }
+        PostcardValue::Map(entries) => {
+            header(out, MAP, entries.len() as u64);
+            for (key, val) in entries {
+                encode(out, key);
+                encode(out, val);
+            }
+        }
+        PostcardValue::Struct(fields) => {
+            header(out, MAP, fields.len() as u64);
+            for (name, val) in fields {
+                encode_text(out, name);
+                encode(out, val);
+            }
+        }
+        PostcardValue::Enum { variant, value } => {
+            if **value != PostcardValue::Unit {
+                header(out, MAP, 1);
+            }
+            encode_text(out, variant);
+            if **value != PostcardValue::Unit {
+                encode(out, value);
+            }
+        }
+    }
+}
+
+/// The initial byte, and the following argument (if any), of a data item
+fn header(out: &mut Vec<u8>, major: u8, arg: u64) {
+    let major = major << 5;
+    if arg < 24 {
+        out.push(major | arg as u8);
+    } else if let Ok(arg) = u8::try_from(arg) {
+        out.extend_from_slice(&[major | 24, arg]);
+    } else if let Ok(arg) = u16::try_from(arg) {
+        out.push(major | 25);
+        out.extend_from_slice(&arg.to_be_bytes());
+    } else if let Ok(arg) = u32::try_from(arg) {
+        out.push(major | 26);
+        out.extend_from_slice(&arg.to_be_bytes());
+    } else {
+        out.push(major | 27);
+        out.extend_from_slice(&arg.to_be_bytes());
+    }
+}
+
+const fn simple(value: u8) -> u8 {
+    (SIMPLE << 5) | value
+}
+
+fn encode_signed(out: &mut Vec<u8>, v: i128) {
+    // Negative integers are encoded as `-1 - n`, which is `!n` in two's complement
+    let (major, tag, arg) = if v < 0 {
+        (NEGATIVE, TAG_NEGATIVE_BIGNUM, !v as u128)
+    } else {
+        (UNSIGNED, TAG_POSITIVE_BIGNUM, v as u128)
+    };
+    match u64::try_from(arg) {
+        Ok(arg) => header(out, major, arg),
+        Err(_) => encode_bignum(out, tag, arg),
+    }
+}
+
+fn encode_bignum(out: &mut Vec<u8>, tag: u64, arg: u128) {
+    let bytes = arg.to_be_bytes();
+    let skip = (arg.leading_zeros() / 8) as usize;
+    header(out, TAG, tag);
+    header(out, BYTES, (bytes.len() - skip) as u64);
+    out.extend_from_slice(&bytes[skip..]);
+}
+
+fn encode_text(out: &mut Vec<u8>, v: &str) {
+    header(out, TEXT, v.len() as u64);
+    out.extend_from_slice(v.as_bytes());
+}
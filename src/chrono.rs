@@ -0,0 +1,136 @@
+//! # Compact Timestamps
+//!
+//! Use with the `#[serde(with = "postcard::chrono")]` field attribute, to encode a
+//! `chrono::DateTime<Utc>` or `chrono::NaiveDateTime` as a compact `(i64, u32)` pair of
+//! seconds and nanoseconds since the Unix epoch, in the same way as a tuple of those.
+//!
+//! With its `serde` feature, `chrono` encodes timestamps as RFC 3339 strings, which take
+//! around 30 bytes. As varints, a timestamp with a whole number of seconds takes 6 bytes,
+//! and a timestamp with a fraction of a second takes at most 10 bytes. This module
+//! doesn't require the `serde` feature of the `chrono` crate.
+//!
+//! ```rust
+//! use chrono::{DateTime, TimeZone, Utc};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct LogEntry {
+//!     #[serde(with = "postcard::chrono")]
+//!     at: DateTime<Utc>,
+//!     level: u8,
+//! }
+//!
+//! let entry = LogEntry {
+//!     at: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+//!     level: 3,
+//! };
+//!
+//! let mut buf = [0u8; 32];
+//! let used = postcard::to_slice(&entry, &mut buf).unwrap();
+//! assert_eq!(used.len(), 7);
+//!
+//! let out: LogEntry = postcard::from_bytes(used).unwrap();
+//! assert_eq!(out, entry);
+//! ```
+
+use ::chrono::{DateTime, NaiveDateTime, Utc};
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A timestamp type supported by this module
+pub trait Timestamp: Sized {
+    /// The seconds and nanoseconds since the Unix epoch
+    fn to_parts(&self) -> (i64, u32);
+
+    /// Create a timestamp from the seconds and nanoseconds since the Unix epoch,
+    /// returning `None` if it is out of range
+    fn from_parts(secs: i64, nanos: u32) -> Option<Self>;
+}
+
+impl Timestamp for DateTime<Utc> {
+    fn to_parts(&self) -> (i64, u32) {
+        (self.timestamp(), self.timestamp_subsec_nanos())
+    }
+
+    fn from_parts(secs: i64, nanos: u32) -> Option<Self> {
+        DateTime::from_timestamp(secs, nanos)
+    }
+}
+
+impl Timestamp for NaiveDateTime {
+    fn to_parts(&self) -> (i64, u32) {
+        self.and_utc().to_parts()
+    }
+
+    fn from_parts(secs: i64, nanos: u32) -> Option<Self> {
+        DateTime::<Utc>::from_parts(secs, nanos).map(|dt| dt.naive_utc())
+    }
+}
+
+/// Serialize the timestamp as a `(i64, u32)` pair of seconds and nanoseconds.
+pub fn serialize<T, S>(val: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Timestamp,
+    S: Serializer,
+{
+    val.to_parts().serialize(serializer)
+}
+
+/// Deserialize the timestamp from a `(i64, u32)` pair of seconds and nanoseconds.
+///
+/// Timestamps that are out of range for the type fail with a custom error.
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+    T: Timestamp,
+    D: Deserializer<'de>,
+{
+    let (secs, nanos) = <(i64, u32)>::deserialize(deserializer)?;
+    T::from_parts(secs, nanos).ok_or_else(|| D::Error::custom("timestamp out of range"))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Error;
+    use ::chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Sample {
+        #[serde(with = "crate::chrono")]
+        at: DateTime<Utc>,
+        #[serde(with = "crate::chrono")]
+        local: NaiveDateTime,
+    }
+
+    #[test]
+    fn compact_timestamps() {
+        let mut buf = [0u8; 32];
+        let local = NaiveDate::from_ymd_opt(2024, 2, 29)
+            .unwrap()
+            .and_hms_nano_opt(12, 30, 15, 500_000_000)
+            .unwrap();
+        let input = Sample {
+            at: DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            local,
+        };
+        let used = crate::to_slice(&input, &mut buf).unwrap();
+        // 5 bytes of seconds, plus a single byte for zero nanoseconds
+        assert_eq!(&used[..6], &[0x80, 0xC4, 0x9F, 0xD5, 0x0C, 0x00]);
+        // 5 bytes of seconds, plus 5 bytes of nanoseconds
+        assert_eq!(used.len(), 6 + 10);
+        assert_eq!(crate::from_bytes(used), Ok(input));
+
+        // Before the epoch
+        let input = Sample {
+            at: DateTime::from_timestamp(-1, 999_999_999).unwrap(),
+            local: DateTime::from_timestamp(0, 0).unwrap().naive_utc(),
+        };
+        let used = crate::to_slice(&input, &mut buf).unwrap();
+        assert_eq!(&used[..1], &[0x01]);
+        assert_eq!(crate::from_bytes(used), Ok(input));
+
+        // Out of range
+        let used = crate::to_slice(&(i64::MAX, 0u32, 0i64, 0u32), &mut buf).unwrap();
+        assert_eq!(crate::from_bytes::<Sample>(used), Err(Error::SerdeDeCustom));
+    }
+}
@@ -0,0 +1,172 @@
+/// How collection lengths are encoded.
+///
+/// This applies to the lengths of sequences, maps, strings, and byte slices.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LengthEncoding {
+    /// Lengths are encoded as a varint `usize` (the default).
+    #[default]
+    Varint,
+    /// Lengths are encoded as a fixed size, little endian `u64`.
+    Fixed,
+}
+
+/// How integers larger than one byte are encoded.
+///
+/// This applies to `u16`, `u32`, `u64`, `u128`, and their signed counterparts. Enum
+/// discriminants are always encoded as a varint.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IntEncoding {
+    /// Integers are encoded as a varint, with signed integers zigzag encoded first (the default).
+    #[default]
+    Varint,
+    /// Integers are encoded with their full width, in little endian byte order.
+    Fixed,
+    /// Integers are encoded in the style of MessagePack, with a tag byte followed by the
+    /// smallest big endian integer that can hold the value.
+    ///
+    /// Values from `-32` to `127` are their own tag byte, so e.g. `-1` and `100` take a
+    /// single byte, where a varint takes two. Larger values take one byte more than
+    /// their width, such as three bytes for `300`. 128-bit integers that don't fit in
+    /// 64 bits are a MessagePack `fixext 16`: the tag `0xD8`, an extension type of `0x00`
+    /// (unsigned) or `0x01` (signed), and 16 bytes.
+    /// Unlike varints, signed integers are not zigzag encoded.
+    MsgPack,
+}
+
+/// How varint integers too large for the type being deserialized are handled.
+///
+/// For example, a `u16` field receiving a varint of `70000`, from a peer which sends
+/// that field as a `u32`. This applies to `u16`, `u32`, `u64`, and their signed
+/// counterparts, when using [`IntEncoding::Varint`]. 128-bit integers have no wider
+/// type to be sent as, and varints too large for them are always rejected with
+/// [`Error::DeserializeBadVarint`](crate::Error::DeserializeBadVarint).
+///
+/// NOTE: Saturating and wrapping are not part of the postcard specification, and silently
+/// change the value that was sent. They are only intended for reading data from peers
+/// which are known to be buggy, or which use a different version of a protocol.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum IntOverflow {
+    /// Integers that are too large fail with
+    /// [`Error::DeserializeIntegerOverflow`](crate::Error::DeserializeIntegerOverflow)
+    /// (the default).
+    #[default]
+    Error,
+    /// Integers that are too large are clamped to the smallest or largest value of the type.
+    Saturate,
+    /// Integers that are too large keep only their low bits, the same as an `as` cast.
+    Wrap,
+}
+
+/// How `char`s are encoded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CharEncoding {
+    /// Chars are encoded as a length prefixed UTF-8 string of one to four bytes (the default).
+    #[default]
+    Utf8,
+    /// Chars are encoded as a varint `u32` of the Unicode code point, e.g. one byte for ASCII.
+    Varint,
+}
+
+/// How enum variants are identified.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EnumEncoding {
+    /// Variants are identified by their index, encoded as a varint `u32` (the default).
+    #[default]
+    Index,
+    /// Variants are identified by their name, encoded as a length prefixed string.
+    ///
+    /// This is much larger on the wire, but makes captured messages easier to read,
+    /// e.g. while debugging.
+    Name,
+}
+
+/// Configuration of the postcard wire format.
+///
+/// By default, postcard uses the wire format described in the [postcard specification],
+/// which is the right choice for nearly all users. For the rare cases where a peer expects
+/// a slightly different layout, a `Config` can be used to change how some items are encoded.
+///
+/// NOTE: Messages encoded with a non-default `Config` are NOT compatible with the postcard
+/// specification, and can only be decoded using the same `Config`.
+///
+/// To serialize with a `Config`, use one of the `to_*_with_config` functions, or
+/// [`Serializer::with_config()`](crate::Serializer::with_config). To
+/// deserialize, use [`from_bytes_with_config()`](crate::from_bytes_with_config), or
+/// [`Deserializer::with_config()`](crate::Deserializer::with_config).
+///
+/// [postcard specification]: https://postcard.jamesmunns.com/wire-format
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{Config, IntEncoding, LengthEncoding};
+///
+/// let config = Config::new()
+///     .length_encoding(LengthEncoding::Varint)
+///     .int_encoding(IntEncoding::Fixed);
+///
+/// let mut buf = [0u8; 16];
+/// let used = postcard::to_slice_with_config(&(0x1234u16, "Hi"), &mut buf, config).unwrap();
+/// assert_eq!(used, &[0x34, 0x12, 0x02, b'H', b'i']);
+///
+/// let out: (u16, &str) = postcard::from_bytes_with_config(used, config).unwrap();
+/// assert_eq!(out, (0x1234, "Hi"));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Config {
+    pub(crate) length_encoding: LengthEncoding,
+    pub(crate) int_encoding: IntEncoding,
+    pub(crate) int_overflow: IntOverflow,
+    pub(crate) char_encoding: CharEncoding,
+    pub(crate) enum_encoding: EnumEncoding,
+}
+
+impl Config {
+    /// Create a new `Config`, matching the default postcard wire format.
+    pub const fn new() -> Self {
+        Config {
+            length_encoding: LengthEncoding::Varint,
+            int_encoding: IntEncoding::Varint,
+            int_overflow: IntOverflow::Error,
+            char_encoding: CharEncoding::Utf8,
+            enum_encoding: EnumEncoding::Index,
+        }
+    }
+
+    /// Set how collection lengths are encoded.
+    pub const fn length_encoding(mut self, length_encoding: LengthEncoding) -> Self {
+        self.length_encoding = length_encoding;
+        self
+    }
+
+    /// Set how integers larger than one byte are encoded.
+    pub const fn int_encoding(mut self, int_encoding: IntEncoding) -> Self {
+        self.int_encoding = int_encoding;
+        self
+    }
+
+    /// Set how varint integers too large for the type being deserialized are handled.
+    ///
+    /// This only affects deserialization.
+    pub const fn int_overflow(mut self, int_overflow: IntOverflow) -> Self {
+        self.int_overflow = int_overflow;
+        self
+    }
+
+    /// Set how `char`s are encoded.
+    pub const fn char_encoding(mut self, char_encoding: CharEncoding) -> Self {
+        self.char_encoding = char_encoding;
+        self
+    }
+
+    /// Set how enum variants are identified.
+    pub const fn enum_encoding(mut self, enum_encoding: EnumEncoding) -> Self {
+        self.enum_encoding = enum_encoding;
+        self
+    }
+}
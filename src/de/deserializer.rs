@@ -1,19 +1,49 @@
 use serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
 
+use crate::config::{CharEncoding, Config, EnumEncoding, IntEncoding, IntOverflow, LengthEncoding};
 use crate::de::flavors::{Flavor, Slice};
 use crate::error::{Error, Result};
+use crate::msgpack;
+use crate::raw::{BYTE_ARRAY, RAW_TAIL};
 use crate::varint::{max_of_last_byte, varint_max};
+use core::convert::TryFrom;
 use core::marker::PhantomData;
 
 /// A `serde` compatible deserializer, generic over “Flavors” of deserializing plugins.
 ///
 /// Please note that postcard messages are not self-describing and therefore incompatible with
-/// [internally tagged enums](https://serde.rs/enum-representations.html#internally-tagged).
+/// [internally tagged enums](https://serde.rs/enum-representations.html#internally-tagged),
+/// as well as untagged enums. Deserializing these returns [`Error::WontImplement`].
+/// Adjacently tagged enums are only supported for newtype and tuple variants.
 pub struct Deserializer<'de, F: Flavor<'de>> {
     flavor: F,
+    config: Config,
+    progress: Option<fn(usize)>,
+    max_str_len: usize,
+    finite_floats: bool,
+    element_budget: Option<usize>,
+    sorted_fields: bool,
+    breadcrumb: Option<Breadcrumb>,
     _plt: PhantomData<&'de ()>,
 }
 
+/// The position within a sequence, tuple, or struct where deserialization failed.
+///
+/// See [`Deserializer::breadcrumb()`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Breadcrumb {
+    /// The index of the element (or struct field) that failed to deserialize
+    pub index: usize,
+    /// The number of elements (or struct fields) that were expected
+    pub len: usize,
+}
+
+impl core::fmt::Display for Breadcrumb {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "while reading element {} of {}", self.index, self.len)
+    }
+}
+
 impl<'de, F> Deserializer<'de, F>
 where
     F: Flavor<'de> + 'de,
@@ -22,10 +52,268 @@ where
     pub fn from_flavor(flavor: F) -> Self {
         Deserializer {
             flavor,
+            config: Config::new(),
+            progress: None,
+            max_str_len: usize::MAX,
+            finite_floats: false,
+            element_budget: None,
+            sorted_fields: false,
+            breadcrumb: None,
+            _plt: PhantomData,
+        }
+    }
+
+    /// Use the given [`Config`] rather than the default postcard wire format
+    /// when deserializing.
+    ///
+    /// This must match the [`Config`] used when the data was serialized.
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Reject strings and byte slices longer than `max` bytes with
+    /// [`Error::DeserializeStringTooLong`].
+    ///
+    /// The length is checked before any of the contents are taken from the input, so
+    /// this can be used to cap the size of untrusted text fields. By default, there is
+    /// no limit.
+    ///
+    /// ```rust
+    /// use postcard::{Deserializer, Error};
+    /// use serde::Deserialize;
+    ///
+    /// let mut deserializer = Deserializer::from_bytes(&[0x03, b'a', b'b', b'c']).with_max_str_len(2);
+    /// assert_eq!(<&str>::deserialize(&mut deserializer), Err(Error::DeserializeStringTooLong));
+    /// ```
+    pub fn with_max_str_len(mut self, max: usize) -> Self {
+        self.max_str_len = max;
+        self
+    }
+
+    /// Reject `f32` and `f64` values that are NaN or infinite with
+    /// [`Error::DeserializeNonFiniteFloat`].
+    ///
+    /// This catches corrupted sensor readings early, before a NaN can propagate into
+    /// e.g. a control loop. By default, all float values are accepted.
+    ///
+    /// ```rust
+    /// use postcard::{Deserializer, Error};
+    /// use serde::Deserialize;
+    ///
+    /// let nan = f32::NAN.to_le_bytes();
+    /// let mut deserializer = Deserializer::from_bytes(&nan).with_finite_floats(true);
+    /// assert_eq!(f32::deserialize(&mut deserializer), Err(Error::DeserializeNonFiniteFloat));
+    /// ```
+    pub fn with_finite_floats(mut self, finite: bool) -> Self {
+        self.finite_floats = finite;
+        self
+    }
+
+    /// Reject messages containing more than `n` elements in total with
+    /// [`Error::DeserializeBudgetExceeded`].
+    ///
+    /// Every element of a sequence, tuple, or struct (including struct fields), and
+    /// every entry of a map, counts against a single budget for the whole message,
+    /// however deeply it is nested. Unlike limiting the length of each collection,
+    /// this bounds the total work done (and memory allocated) for untrusted input.
+    /// By default, there is no limit.
+    ///
+    /// ```rust
+    /// use postcard::{Deserializer, Error};
+    /// use serde::Deserialize;
+    ///
+    /// // Two pairs are six elements: two tuples, of two elements each
+    /// let mut deserializer = Deserializer::from_bytes(&[1, 2, 3, 4]).with_element_budget(5);
+    /// let res = <[(u8, u8); 2]>::deserialize(&mut deserializer);
+    /// assert_eq!(res, Err(Error::DeserializeBudgetExceeded));
+    /// ```
+    pub fn with_element_budget(mut self, n: usize) -> Self {
+        self.element_budget = Some(n);
+        self
+    }
+
+    /// Expect the fields of every struct (and struct variant) to be sorted by name,
+    /// rather than in the order they are declared in, as written by
+    /// [`to_allocvec_field_sorted()`](crate::to_allocvec_field_sorted).
+    ///
+    /// Names are compared byte by byte, the same as `str`'s `Ord`. By default, fields
+    /// are expected in declaration order.
+    ///
+    /// ```rust
+    /// use postcard::Deserializer;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize, Debug, PartialEq)]
+    /// struct Point {
+    ///     y: u8,
+    ///     x: u8,
+    /// }
+    ///
+    /// let mut deserializer = Deserializer::from_bytes(&[0x01, 0x02]).with_sorted_fields(true);
+    /// assert_eq!(Point::deserialize(&mut deserializer), Ok(Point { x: 1, y: 2 }));
+    /// ```
+    pub fn with_sorted_fields(mut self, sorted: bool) -> Self {
+        self.sorted_fields = sorted;
+        self
+    }
+
+    /// Count one element against the element budget, if any
+    #[inline]
+    fn spend_element(&mut self) -> Result<()> {
+        match &mut self.element_budget {
+            Some(0) => Err(Error::DeserializeBudgetExceeded),
+            Some(budget) => {
+                *budget -= 1;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Call `callback` with the number of bytes consumed so far, after each element
+    /// of a sequence, tuple, or struct (or each entry of a map) is deserialized.
+    ///
+    /// This can be used to report progress while decoding large messages, or to feed a
+    /// watchdog. The callback is only called if the [`Flavor`] keeps track of the
+    /// number of bytes consumed, see [`Flavor::consumed()`]. Without a callback, only
+    /// a single check per element is added.
+    ///
+    /// ```rust
+    /// use core::sync::atomic::{AtomicUsize, Ordering};
+    /// use postcard::Deserializer;
+    /// use serde::Deserialize;
+    ///
+    /// static CALLS: AtomicUsize = AtomicUsize::new(0);
+    /// static CONSUMED: AtomicUsize = AtomicUsize::new(0);
+    ///
+    /// let mut deserializer = Deserializer::from_bytes(&[0x01, 0xAC, 0x02]).with_progress(|consumed| {
+    ///     CALLS.fetch_add(1, Ordering::Relaxed);
+    ///     CONSUMED.store(consumed, Ordering::Relaxed);
+    /// });
+    /// let out = <[u16; 2]>::deserialize(&mut deserializer).unwrap();
+    ///
+    /// assert_eq!(out, [1, 300]);
+    /// assert_eq!(CALLS.load(Ordering::Relaxed), 2);
+    /// assert_eq!(CONSUMED.load(Ordering::Relaxed), 3);
+    /// ```
+    pub fn with_progress(mut self, callback: fn(usize)) -> Self {
+        self.progress = Some(callback);
+        self
+    }
+
+    #[inline]
+    fn report_progress(&self) {
+        if let Some(callback) = self.progress {
+            if let Some(consumed) = self.flavor.consumed() {
+                callback(consumed);
+            }
+        }
+    }
+
+    /// Return the innermost sequence, tuple, or struct element that failed to deserialize,
+    /// if any.
+    ///
+    /// Errors such as [`Error::DeserializeUnexpectedEnd`] don't say where in a message
+    /// they happened. This records the index of the element (or struct field) that was
+    /// being read when the first error occurred, for logging the context of the error.
+    /// Fixed size arrays are tuples on the wire, so for a truncated array, the index is
+    /// also the number of elements that were read successfully.
+    ///
+    /// ```rust
+    /// use postcard::{Breadcrumb, Deserializer, Error};
+    /// use serde::Deserialize;
+    ///
+    /// let mut deserializer = Deserializer::from_bytes(&[0x01, 0x02]);
+    /// let res = <(u8, u8, u8)>::deserialize(&mut deserializer);
+    ///
+    /// assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+    /// assert_eq!(deserializer.breadcrumb(), Some(Breadcrumb { index: 2, len: 3 }));
+    /// ```
+    pub fn breadcrumb(&self) -> Option<Breadcrumb> {
+        self.breadcrumb
+    }
+
+    /// Deserialize a length prefixed sub-message of type `T`, i.e. a message that was
+    /// serialized separately, and then embedded as a byte slice.
+    ///
+    /// This is useful for envelope protocols, where the type of the payload is only
+    /// known after reading the envelope. The sub-message is deserialized on its own,
+    /// using the same [`Config`] and limits, so it can't read beyond its length. If it doesn't
+    /// use all of its bytes, [`Error::DeserializeTrailingBytes`] is returned.
+    ///
+    /// ```rust
+    /// use postcard::Deserializer;
+    /// use serde::Deserialize;
+    ///
+    /// // A channel number, followed by a payload of `(u16, bool)`
+    /// let input = [0x02, 0x03, 0xAC, 0x02, 0x01];
+    /// let mut deserializer = Deserializer::from_bytes(&input);
+    /// assert_eq!(u8::deserialize(&mut deserializer), Ok(2));
+    /// assert_eq!(deserializer.deserialize_sub::<(u16, bool)>(), Ok((300, true)));
+    /// ```
+    pub fn deserialize_sub<T>(&mut self) -> Result<T>
+    where
+        T: de::Deserialize<'de>,
+    {
+        let len = self.try_take_length()?;
+        let bytes = self.flavor.try_take_n(len)?;
+        let mut sub = self.sub_deserializer(bytes);
+        let t = T::deserialize(&mut sub);
+        // The sub-message is part of this message, and shares its element budget
+        self.element_budget = sub.element_budget;
+        if self.breadcrumb.is_none() {
+            self.breadcrumb = sub.breadcrumb;
+        }
+        let t = t?;
+        if sub.remaining() != 0 {
+            return Err(Error::DeserializeTrailingBytes);
+        }
+        Ok(t)
+    }
+
+    /// A Deserializer for the given bytes of this message, with the same
+    /// [`Config`] and limits as this one.
+    fn sub_deserializer(&self, bytes: &'de [u8]) -> Deserializer<'de, Slice<'de>> {
+        Deserializer {
+            flavor: Slice::new(bytes),
+            config: self.config,
+            progress: self.progress,
+            max_str_len: self.max_str_len,
+            finite_floats: self.finite_floats,
+            element_budget: self.element_budget,
+            sorted_fields: self.sorted_fields,
+            breadcrumb: None,
             _plt: PhantomData,
         }
     }
 
+    /// Split off the next `n` bytes into a new Deserializer, and advance this one past
+    /// them.
+    ///
+    /// This is useful for layered protocols, where the length of a sub-message is known
+    /// from an earlier field. The returned Deserializer uses the same [`Config`] and
+    /// limits, and can't read beyond its `n` bytes. It starts with what is left of the
+    /// element budget, but doesn't spend it for this Deserializer. Unlike [`Deserializer::deserialize_sub()`], the
+    /// length is not read from the input, and the bytes may be read piece by piece.
+    ///
+    /// ```rust
+    /// use postcard::{Deserializer, Error};
+    /// use serde::Deserialize;
+    ///
+    /// // A header of two bytes, followed by a checksum
+    /// let input = [0x01, 0x02, 0xFF];
+    /// let mut deserializer = Deserializer::from_bytes(&input);
+    /// let mut header = deserializer.split_off(2).unwrap();
+    /// assert_eq!(<(u8, u8)>::deserialize(&mut header), Ok((1, 2)));
+    /// assert_eq!(u8::deserialize(&mut header), Err(Error::DeserializeUnexpectedEnd));
+    /// assert_eq!(u8::deserialize(&mut deserializer), Ok(0xFF));
+    /// ```
+    pub fn split_off(&mut self, n: usize) -> Result<Deserializer<'de, Slice<'de>>> {
+        let bytes = self.flavor.try_take_n(n)?;
+        Ok(self.sub_deserializer(bytes))
+    }
+
     /// Return the remaining (unused) bytes in the Deserializer along with any
     /// additional data provided by the [`Flavor`]
     pub fn finalize(self) -> Result<F::Remainder> {
@@ -38,12 +326,97 @@ impl<'de> Deserializer<'de, Slice<'de>> {
     pub fn from_bytes(input: &'de [u8]) -> Self {
         Deserializer {
             flavor: Slice::new(input),
+            config: Config::new(),
+            progress: None,
+            max_str_len: usize::MAX,
+            finite_floats: false,
+            element_budget: None,
+            sorted_fields: false,
+            breadcrumb: None,
             _plt: PhantomData,
         }
     }
+
+    /// Return the number of bytes in the input that have not been deserialized yet
+    pub fn remaining(&self) -> usize {
+        (self.flavor.end as usize) - (self.flavor.cursor as usize)
+    }
+
+    /// Take all of the remaining bytes of the input, without a length prefix.
+    ///
+    /// This is useful when the length of a trailing payload is framed externally.
+    /// The returned slice is borrowed from the input without copying, and
+    /// [`remaining()`](Self::remaining) is zero afterwards. See also
+    /// [`RawTail`](crate::raw::RawTail), for doing the same within a message type.
+    ///
+    /// ```rust
+    /// use postcard::Deserializer;
+    /// use serde::Deserialize;
+    ///
+    /// let input = [0x05, 0xAA, 0xBB];
+    /// let mut deserializer = Deserializer::from_bytes(&input);
+    /// assert_eq!(u8::deserialize(&mut deserializer), Ok(5));
+    /// assert_eq!(deserializer.take_rest(), &[0xAA, 0xBB]);
+    /// assert_eq!(deserializer.remaining(), 0);
+    /// ```
+    pub fn take_rest(&mut self) -> &'de [u8] {
+        let remain = self.remaining();
+        // Can't fail, as exactly the remaining bytes are taken
+        self.flavor.try_take_n(remain).unwrap_or_default()
+    }
+
+    /// Return the bytes surrounding the current position in the input, for logging
+    /// the context of a failed deserialization.
+    ///
+    /// This contains up to four bytes before the current position, which includes
+    /// the byte that caused an error such as [`Error::DeserializeBadBool`], followed
+    /// by up to four bytes that were not read yet. This never allocates.
+    ///
+    /// ```rust
+    /// use postcard::{Deserializer, Error};
+    /// use serde::Deserialize;
+    ///
+    /// let input = [0x01, 0x02, 0x03, 0x04, 0x05];
+    /// let mut deserializer = Deserializer::from_bytes(&input);
+    /// let res = <(u8, u8, bool)>::deserialize(&mut deserializer);
+    ///
+    /// assert_eq!(res, Err(Error::DeserializeBadBool));
+    /// assert_eq!(deserializer.error_context(), &[0x01, 0x02, 0x03, 0x04, 0x05]);
+    /// ```
+    pub fn error_context(&self) -> &'de [u8] {
+        self.flavor.around(4, 4)
+    }
 }
 
 impl<'de, F: Flavor<'de>> Deserializer<'de, F> {
+    #[inline]
+    pub(crate) fn try_take_length(&mut self) -> Result<usize> {
+        match self.config.length_encoding {
+            LengthEncoding::Varint => self.try_take_varint_usize(),
+            LengthEncoding::Fixed => {
+                let len = u64::from_le_bytes(self.try_take_array()?);
+                usize::try_from(len).map_err(|_| Error::DeserializeBadEncoding)
+            }
+        }
+    }
+
+    #[inline]
+    fn try_take_str_length(&mut self) -> Result<usize> {
+        let len = self.try_take_length()?;
+        if len > self.max_str_len {
+            return Err(Error::DeserializeStringTooLong);
+        }
+        Ok(len)
+    }
+
+    #[inline]
+    fn try_take_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let bytes = self.flavor.try_take_n(N)?;
+        let mut buf = [0u8; N];
+        buf.copy_from_slice(bytes);
+        Ok(buf)
+    }
+
     #[cfg(target_pointer_width = "8")]
     #[inline(always)]
     fn try_take_varint_usize(&mut self) -> Result<usize> {
@@ -68,35 +441,75 @@ impl<'de, F: Flavor<'de>> Deserializer<'de, F> {
         self.try_take_varint_u64().map(|u| u as usize)
     }
 
+    #[cfg(target_pointer_width = "16")]
     #[inline]
     fn try_take_varint_u16(&mut self) -> Result<u16> {
-        let mut out = 0;
-        for i in 0..varint_max::<u16>() {
+        match self.try_take_sized_varint::<u16>()? {
+            SizedVarint::Fits(v) => Ok(v as u16),
+            SizedVarint::Overflow(_) => Err(Error::DeserializeBadVarint),
+        }
+    }
+
+    #[inline]
+    fn try_take_varint_u32(&mut self) -> Result<u32> {
+        match self.try_take_sized_varint::<u32>()? {
+            SizedVarint::Fits(v) => Ok(v as u32),
+            SizedVarint::Overflow(_) => Err(Error::DeserializeBadVarint),
+        }
+    }
+
+    #[inline]
+    fn try_take_varint_u64(&mut self) -> Result<u64> {
+        match self.try_take_sized_varint::<u64>()? {
+            SizedVarint::Fits(v) => Ok(v),
+            SizedVarint::Overflow(_) => Err(Error::DeserializeBadVarint),
+        }
+    }
+
+    /// Take a varint of the unsigned integer type `T`, which is at most 64 bits wide.
+    ///
+    /// If the varint doesn't fit in `T`, the rest of it is still taken, as long as it
+    /// fits in a `u128`.
+    #[inline]
+    fn try_take_sized_varint<T>(&mut self) -> Result<SizedVarint> {
+        let mut out = 0u64;
+        for i in 0..varint_max::<T>() {
             let val = self.flavor.pop()?;
-            let carry = (val & 0x7F) as u16;
+            // This also catches a continuation bit on the last byte
+            if i == varint_max::<T>() - 1 && val > max_of_last_byte::<T>() {
+                return self
+                    .try_take_overflowed_varint(out.into(), i, val)
+                    .map(SizedVarint::Overflow);
+            }
+
+            let carry = (val & 0x7F) as u64;
             out |= carry << (7 * i);
 
             if (val & 0x80) == 0 {
-                if i == varint_max::<u16>() - 1 && val > max_of_last_byte::<u16>() {
-                    return Err(Error::DeserializeBadVarint);
-                } else {
-                    return Ok(out);
-                }
+                return Ok(SizedVarint::Fits(out));
             }
         }
         Err(Error::DeserializeBadVarint)
     }
 
-    #[inline]
-    fn try_take_varint_u32(&mut self) -> Result<u32> {
-        let mut out = 0;
-        for i in 0..varint_max::<u32>() {
-            let val = self.flavor.pop()?;
-            let carry = (val & 0x7F) as u32;
+    /// Take the rest of a varint that was too large for its type, where `out` holds
+    /// the first `taken` bytes, and `val` is the following byte.
+    #[cold]
+    fn try_take_overflowed_varint(
+        &mut self,
+        mut out: u128,
+        taken: usize,
+        mut val: u8,
+    ) -> Result<u128> {
+        for i in taken..varint_max::<u128>() {
+            if i != taken {
+                val = self.flavor.pop()?;
+            }
+            let carry = (val & 0x7F) as u128;
             out |= carry << (7 * i);
 
             if (val & 0x80) == 0 {
-                if i == varint_max::<u32>() - 1 && val > max_of_last_byte::<u32>() {
+                if i == varint_max::<u128>() - 1 && val > max_of_last_byte::<u128>() {
                     return Err(Error::DeserializeBadVarint);
                 } else {
                     return Ok(out);
@@ -106,27 +519,105 @@ impl<'de, F: Flavor<'de>> Deserializer<'de, F> {
         Err(Error::DeserializeBadVarint)
     }
 
+    /// Take a varint unsigned integer of type `T`, handling values too large for it
+    /// according to the configured [`IntOverflow`].
     #[inline]
-    fn try_take_varint_u64(&mut self) -> Result<u64> {
-        let mut out = 0;
-        for i in 0..varint_max::<u64>() {
+    fn try_take_varint_uint<T>(&mut self) -> Result<u64> {
+        let max = u64::MAX >> (64 - 8 * core::mem::size_of::<T>());
+        match self.try_take_sized_varint::<T>()? {
+            SizedVarint::Fits(v) => Ok(v),
+            SizedVarint::Overflow(v) => match self.config.int_overflow {
+                IntOverflow::Error => Err(Error::DeserializeIntegerOverflow),
+                IntOverflow::Saturate => Ok(max),
+                IntOverflow::Wrap => Ok(v as u64 & max),
+            },
+        }
+    }
+
+    /// Take a zigzag encoded varint signed integer of type `T`, handling values too
+    /// large for it according to the configured [`IntOverflow`].
+    ///
+    /// The result is still zigzag encoded.
+    #[inline]
+    fn try_take_varint_sint<T>(&mut self) -> Result<u64> {
+        let bits = 8 * core::mem::size_of::<T>() as u32;
+        let v = match self.try_take_sized_varint::<T>()? {
+            SizedVarint::Fits(v) => return Ok(v),
+            SizedVarint::Overflow(v) => de_zig_zag_i128(v),
+        };
+        let v = match self.config.int_overflow {
+            IntOverflow::Error => return Err(Error::DeserializeIntegerOverflow),
+            IntOverflow::Saturate => {
+                let max = (1i128 << (bits - 1)) - 1;
+                v.clamp(-max - 1, max) as i64
+            }
+            // Keep the low bits, and sign extend them
+            IntOverflow::Wrap => ((v as i64) << (64 - bits)) >> (64 - bits),
+        };
+        Ok(((v << 1) ^ (v >> 63)) as u64)
+    }
+
+    #[cfg(not(feature = "split-u128"))]
+    #[inline(always)]
+    fn try_take_varint_u128(&mut self) -> Result<u128> {
+        self.try_take_varint_u128_native()
+    }
+
+    #[cfg(feature = "split-u128")]
+    #[inline(always)]
+    fn try_take_varint_u128(&mut self) -> Result<u128> {
+        self.try_take_varint_u128_split()
+    }
+
+    #[cfg(not(feature = "split-u128"))]
+    #[inline(always)]
+    fn try_take_fixed_u128(&mut self) -> Result<u128> {
+        Ok(u128::from_le_bytes(self.try_take_array()?))
+    }
+
+    #[cfg(feature = "split-u128")]
+    #[inline(always)]
+    fn try_take_fixed_u128(&mut self) -> Result<u128> {
+        let lo = u64::from_le_bytes(self.try_take_array()?);
+        let hi = u64::from_le_bytes(self.try_take_array()?);
+        Ok(join_u128(lo, hi))
+    }
+
+    /// Take a varint u128, accumulating it as two u64 halves rather than with
+    /// (potentially expensive) 128-bit shifts.
+    #[cfg(any(test, feature = "split-u128"))]
+    #[inline]
+    fn try_take_varint_u128_split(&mut self) -> Result<u128> {
+        let mut lo: u64 = 0;
+        let mut hi: u64 = 0;
+        for i in 0..varint_max::<u128>() {
             let val = self.flavor.pop()?;
             let carry = (val & 0x7F) as u64;
-            out |= carry << (7 * i);
+            let shift = 7 * i as u32;
+            if shift < 64 {
+                lo |= carry << shift;
+                // The seven bits may straddle the two halves
+                if shift > 57 {
+                    hi |= carry >> (64 - shift);
+                }
+            } else {
+                hi |= carry << (shift - 64);
+            }
 
             if (val & 0x80) == 0 {
-                if i == varint_max::<u64>() - 1 && val > max_of_last_byte::<u64>() {
+                if i == varint_max::<u128>() - 1 && val > max_of_last_byte::<u128>() {
                     return Err(Error::DeserializeBadVarint);
                 } else {
-                    return Ok(out);
+                    return Ok(join_u128(lo, hi));
                 }
             }
         }
         Err(Error::DeserializeBadVarint)
     }
 
+    #[cfg_attr(feature = "split-u128", allow(dead_code))]
     #[inline]
-    fn try_take_varint_u128(&mut self) -> Result<u128> {
+    fn try_take_varint_u128_native(&mut self) -> Result<u128> {
         let mut out = 0;
         for i in 0..varint_max::<u128>() {
             let val = self.flavor.pop()?;
@@ -145,8 +636,99 @@ impl<'de, F: Flavor<'de>> Deserializer<'de, F> {
     }
 }
 
+impl<'de, F: Flavor<'de>> Deserializer<'de, F> {
+    /// Take a MessagePack style integer of up to 64 bits, as the integer type `T`
+    #[inline]
+    fn try_take_msgpack_int<T: TryFrom<i128>>(&mut self) -> Result<T> {
+        let tag = self.flavor.pop()?;
+        let v = self.try_take_msgpack_tagged(tag)?;
+        T::try_from(v).map_err(|_| Error::DeserializeIntegerOverflow)
+    }
+
+    fn try_take_msgpack_u128(&mut self) -> Result<u128> {
+        let v = match self.flavor.pop()? {
+            msgpack::FIXEXT16 => match self.try_take_msgpack_ext128()? {
+                Ok(v) => return Ok(v),
+                Err(v) => u128::try_from(v),
+            },
+            tag => u128::try_from(self.try_take_msgpack_tagged(tag)?),
+        };
+        v.map_err(|_| Error::DeserializeIntegerOverflow)
+    }
+
+    fn try_take_msgpack_i128(&mut self) -> Result<i128> {
+        match self.flavor.pop()? {
+            msgpack::FIXEXT16 => match self.try_take_msgpack_ext128()? {
+                Ok(v) => i128::try_from(v).map_err(|_| Error::DeserializeIntegerOverflow),
+                Err(v) => Ok(v),
+            },
+            tag => self.try_take_msgpack_tagged(tag),
+        }
+    }
+
+    /// Take the rest of a 128-bit integer, after its `FIXEXT16` tag byte, as either
+    /// an unsigned (`Ok`) or signed (`Err`) value
+    #[inline]
+    fn try_take_msgpack_ext128(&mut self) -> Result<core::result::Result<u128, i128>> {
+        match self.flavor.pop()? {
+            msgpack::EXT_UINT128 => Ok(Ok(u128::from_be_bytes(self.try_take_array()?))),
+            msgpack::EXT_INT128 => Ok(Err(i128::from_be_bytes(self.try_take_array()?))),
+            _ => Err(Error::DeserializeBadEncoding),
+        }
+    }
+
+    /// Take the rest of a MessagePack style integer of up to 64 bits, after its tag byte
+    #[inline]
+    fn try_take_msgpack_tagged(&mut self, tag: u8) -> Result<i128> {
+        let v = match tag {
+            0..=msgpack::POSITIVE_FIXINT_MAX => tag.into(),
+            msgpack::NEGATIVE_FIXINT_MIN..=0xFF => (tag as i8).into(),
+            msgpack::UINT8 => u8::from_be_bytes(self.try_take_array()?).into(),
+            msgpack::UINT16 => u16::from_be_bytes(self.try_take_array()?).into(),
+            msgpack::UINT32 => u32::from_be_bytes(self.try_take_array()?).into(),
+            msgpack::UINT64 => u64::from_be_bytes(self.try_take_array()?).into(),
+            msgpack::INT8 => i8::from_be_bytes(self.try_take_array()?).into(),
+            msgpack::INT16 => i16::from_be_bytes(self.try_take_array()?).into(),
+            msgpack::INT32 => i32::from_be_bytes(self.try_take_array()?).into(),
+            msgpack::INT64 => i64::from_be_bytes(self.try_take_array()?).into(),
+            // Only used for values that don't fit in 64 bits
+            msgpack::FIXEXT16 => return Err(Error::DeserializeIntegerOverflow),
+            _ => return Err(Error::DeserializeBadEncoding),
+        };
+        Ok(v)
+    }
+
+    /// Deserialize the fields of a struct, or struct variant
+    #[inline]
+    fn deserialize_fields<V>(
+        &mut self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value>
+    where
+        V: Visitor<'de>,
+    {
+        if self.sorted_fields {
+            visitor.visit_map(SortedFieldAccess {
+                deserializer: self,
+                fields,
+                prev: None,
+            })
+        } else {
+            serde::de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+        }
+    }
+}
+
+/// A varint, which may have been too large for the type it was taken as
+enum SizedVarint {
+    Fits(u64),
+    Overflow(u128),
+}
+
 struct SeqAccess<'a, 'b: 'a, F: Flavor<'b>> {
     deserializer: &'a mut Deserializer<'b, F>,
+    index: usize,
     len: usize,
 }
 
@@ -155,12 +737,24 @@ impl<'a, 'b: 'a, F: Flavor<'b>> serde::de::SeqAccess<'b> for SeqAccess<'a, 'b, F
 
     #[inline]
     fn next_element_seed<V: DeserializeSeed<'b>>(&mut self, seed: V) -> Result<Option<V::Value>> {
-        if self.len > 0 {
-            self.len -= 1;
-            Ok(Some(DeserializeSeed::deserialize(
-                seed,
-                &mut *self.deserializer,
-            )?))
+        if self.index < self.len {
+            self.deserializer.spend_element()?;
+            let elem = match DeserializeSeed::deserialize(seed, &mut *self.deserializer) {
+                Ok(elem) => elem,
+                Err(e) => {
+                    // Keep the innermost breadcrumb, which is recorded first
+                    if self.deserializer.breadcrumb.is_none() {
+                        self.deserializer.breadcrumb = Some(Breadcrumb {
+                            index: self.index,
+                            len: self.len,
+                        });
+                    }
+                    return Err(e);
+                }
+            };
+            self.index += 1;
+            self.deserializer.report_progress();
+            Ok(Some(elem))
         } else {
             Ok(None)
         }
@@ -168,7 +762,7 @@ impl<'a, 'b: 'a, F: Flavor<'b>> serde::de::SeqAccess<'b> for SeqAccess<'a, 'b, F
 
     #[inline]
     fn size_hint(&self) -> Option<usize> {
-        Some(self.len)
+        Some(self.len - self.index)
     }
 }
 
@@ -184,6 +778,7 @@ impl<'a, 'b: 'a, F: Flavor<'b>> serde::de::MapAccess<'b> for MapAccess<'a, 'b, F
     fn next_key_seed<K: DeserializeSeed<'b>>(&mut self, seed: K) -> Result<Option<K::Value>> {
         if self.len > 0 {
             self.len -= 1;
+            self.deserializer.spend_element()?;
             Ok(Some(DeserializeSeed::deserialize(
                 seed,
                 &mut *self.deserializer,
@@ -195,7 +790,9 @@ impl<'a, 'b: 'a, F: Flavor<'b>> serde::de::MapAccess<'b> for MapAccess<'a, 'b, F
 
     #[inline]
     fn next_value_seed<V: DeserializeSeed<'b>>(&mut self, seed: V) -> Result<V::Value> {
-        DeserializeSeed::deserialize(seed, &mut *self.deserializer)
+        let value = DeserializeSeed::deserialize(seed, &mut *self.deserializer)?;
+        self.deserializer.report_progress();
+        Ok(value)
     }
 
     #[inline]
@@ -204,7 +801,7 @@ impl<'a, 'b: 'a, F: Flavor<'b>> serde::de::MapAccess<'b> for MapAccess<'a, 'b, F
     }
 }
 
-impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de, F> {
+impl<'de, F: Flavor<'de>> de::Deserializer<'de> for &mut Deserializer<'de, F> {
     type Error = Error;
 
     #[inline]
@@ -249,8 +846,12 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     where
         V: Visitor<'de>,
     {
-        let v = self.try_take_varint_u16()?;
-        visitor.visit_i16(de_zig_zag_i16(v))
+        let v = match self.config.int_encoding {
+            IntEncoding::Varint => de_zig_zag_i16(self.try_take_varint_sint::<i16>()? as u16),
+            IntEncoding::Fixed => i16::from_le_bytes(self.try_take_array()?),
+            IntEncoding::MsgPack => self.try_take_msgpack_int()?,
+        };
+        visitor.visit_i16(v)
     }
 
     #[inline]
@@ -258,8 +859,12 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     where
         V: Visitor<'de>,
     {
-        let v = self.try_take_varint_u32()?;
-        visitor.visit_i32(de_zig_zag_i32(v))
+        let v = match self.config.int_encoding {
+            IntEncoding::Varint => de_zig_zag_i32(self.try_take_varint_sint::<i32>()? as u32),
+            IntEncoding::Fixed => i32::from_le_bytes(self.try_take_array()?),
+            IntEncoding::MsgPack => self.try_take_msgpack_int()?,
+        };
+        visitor.visit_i32(v)
     }
 
     #[inline]
@@ -267,8 +872,12 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     where
         V: Visitor<'de>,
     {
-        let v = self.try_take_varint_u64()?;
-        visitor.visit_i64(de_zig_zag_i64(v))
+        let v = match self.config.int_encoding {
+            IntEncoding::Varint => de_zig_zag_i64(self.try_take_varint_sint::<i64>()?),
+            IntEncoding::Fixed => i64::from_le_bytes(self.try_take_array()?),
+            IntEncoding::MsgPack => self.try_take_msgpack_int()?,
+        };
+        visitor.visit_i64(v)
     }
 
     #[inline]
@@ -276,8 +885,12 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     where
         V: Visitor<'de>,
     {
-        let v = self.try_take_varint_u128()?;
-        visitor.visit_i128(de_zig_zag_i128(v))
+        let v = match self.config.int_encoding {
+            IntEncoding::Varint => de_zig_zag_i128(self.try_take_varint_u128()?),
+            IntEncoding::Fixed => self.try_take_fixed_u128()? as i128,
+            IntEncoding::MsgPack => self.try_take_msgpack_i128()?,
+        };
+        visitor.visit_i128(v)
     }
 
     #[inline]
@@ -293,7 +906,11 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     where
         V: Visitor<'de>,
     {
-        let v = self.try_take_varint_u16()?;
+        let v = match self.config.int_encoding {
+            IntEncoding::Varint => self.try_take_varint_uint::<u16>()? as u16,
+            IntEncoding::Fixed => u16::from_le_bytes(self.try_take_array()?),
+            IntEncoding::MsgPack => self.try_take_msgpack_int()?,
+        };
         visitor.visit_u16(v)
     }
 
@@ -302,7 +919,11 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     where
         V: Visitor<'de>,
     {
-        let v = self.try_take_varint_u32()?;
+        let v = match self.config.int_encoding {
+            IntEncoding::Varint => self.try_take_varint_uint::<u32>()? as u32,
+            IntEncoding::Fixed => u32::from_le_bytes(self.try_take_array()?),
+            IntEncoding::MsgPack => self.try_take_msgpack_int()?,
+        };
         visitor.visit_u32(v)
     }
 
@@ -311,7 +932,11 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     where
         V: Visitor<'de>,
     {
-        let v = self.try_take_varint_u64()?;
+        let v = match self.config.int_encoding {
+            IntEncoding::Varint => self.try_take_varint_uint::<u64>()?,
+            IntEncoding::Fixed => u64::from_le_bytes(self.try_take_array()?),
+            IntEncoding::MsgPack => self.try_take_msgpack_int()?,
+        };
         visitor.visit_u64(v)
     }
 
@@ -320,7 +945,11 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     where
         V: Visitor<'de>,
     {
-        let v = self.try_take_varint_u128()?;
+        let v = match self.config.int_encoding {
+            IntEncoding::Varint => self.try_take_varint_u128()?,
+            IntEncoding::Fixed => self.try_take_fixed_u128()?,
+            IntEncoding::MsgPack => self.try_take_msgpack_u128()?,
+        };
         visitor.visit_u128(v)
     }
 
@@ -332,7 +961,11 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
         let bytes = self.flavor.try_take_n(4)?;
         let mut buf = [0u8; 4];
         buf.copy_from_slice(bytes);
-        visitor.visit_f32(f32::from_bits(u32::from_le_bytes(buf)))
+        let v = f32::from_bits(u32::from_le_bytes(buf));
+        if self.finite_floats && !v.is_finite() {
+            return Err(Error::DeserializeNonFiniteFloat);
+        }
+        visitor.visit_f32(v)
     }
 
     #[inline]
@@ -343,7 +976,11 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
         let bytes = self.flavor.try_take_n(8)?;
         let mut buf = [0u8; 8];
         buf.copy_from_slice(bytes);
-        visitor.visit_f64(f64::from_bits(u64::from_le_bytes(buf)))
+        let v = f64::from_bits(u64::from_le_bytes(buf));
+        if self.finite_floats && !v.is_finite() {
+            return Err(Error::DeserializeNonFiniteFloat);
+        }
+        visitor.visit_f64(v)
     }
 
     #[inline]
@@ -351,7 +988,13 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     where
         V: Visitor<'de>,
     {
-        let sz = self.try_take_varint_usize()?;
+        if self.config.char_encoding == CharEncoding::Varint {
+            let v = self.try_take_varint_u32()?;
+            let character = char::from_u32(v).ok_or(Error::DeserializeBadChar)?;
+            return visitor.visit_char(character);
+        }
+
+        let sz = self.try_take_length()?;
         if sz > 4 {
             return Err(Error::DeserializeBadChar);
         }
@@ -373,9 +1016,13 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     where
         V: Visitor<'de>,
     {
-        let sz = self.try_take_varint_usize()?;
+        if let Some(str_sl) = self.flavor.try_take_interned()? {
+            return visitor.visit_borrowed_str(str_sl);
+        }
+        let sz = self.try_take_str_length()?;
         let bytes: &'de [u8] = self.flavor.try_take_n(sz)?;
         let str_sl = core::str::from_utf8(bytes).map_err(|_| Error::DeserializeBadUtf8)?;
+        self.flavor.record_interned(str_sl)?;
 
         visitor.visit_borrowed_str(str_sl)
     }
@@ -393,7 +1040,7 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     where
         V: Visitor<'de>,
     {
-        let sz = self.try_take_varint_usize()?;
+        let sz = self.try_take_str_length()?;
         let bytes: &'de [u8] = self.flavor.try_take_n(sz)?;
         visitor.visit_borrowed_bytes(bytes)
     }
@@ -439,10 +1086,13 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     }
 
     #[inline]
-    fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V) -> Result<V::Value>
+    fn deserialize_newtype_struct<V>(self, name: &'static str, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        if name == RAW_TAIL {
+            return visitor.visit_borrowed_bytes(self.flavor.try_take_rest()?);
+        }
         visitor.visit_newtype_struct(self)
     }
 
@@ -451,10 +1101,11 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     where
         V: Visitor<'de>,
     {
-        let len = self.try_take_varint_usize()?;
+        let len = self.try_take_length()?;
 
         visitor.visit_seq(SeqAccess {
             deserializer: self,
+            index: 0,
             len,
         })
     }
@@ -466,6 +1117,7 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     {
         visitor.visit_seq(SeqAccess {
             deserializer: self,
+            index: 0,
             len,
         })
     }
@@ -473,13 +1125,16 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     #[inline]
     fn deserialize_tuple_struct<V>(
         self,
-        _name: &'static str,
+        name: &'static str,
         len: usize,
         visitor: V,
     ) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        if name == BYTE_ARRAY {
+            return visitor.visit_borrowed_bytes(self.flavor.try_take_n(len)?);
+        }
         self.deserialize_tuple(len, visitor)
     }
 
@@ -488,7 +1143,7 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     where
         V: Visitor<'de>,
     {
-        let len = self.try_take_varint_usize()?;
+        let len = self.try_take_length()?;
 
         visitor.visit_map(MapAccess {
             deserializer: self,
@@ -506,30 +1161,50 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     where
         V: Visitor<'de>,
     {
-        self.deserialize_tuple(fields.len(), visitor)
+        self.deserialize_fields(fields, visitor)
     }
 
     #[inline]
     fn deserialize_enum<V>(
         self,
         _name: &'static str,
-        _variants: &'static [&'static str],
+        variants: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
+        if self.config.enum_encoding == EnumEncoding::Name {
+            let sz = self.try_take_length()?;
+            let name = self.flavor.try_take_n(sz)?;
+            let index = variants
+                .iter()
+                .position(|v| v.as_bytes() == name)
+                .ok_or(Error::DeserializeBadEnum)?;
+            return visitor.visit_enum(NamedEnumAccess {
+                deserializer: self,
+                index: index as u32,
+            });
+        }
+
         visitor.visit_enum(self)
     }
 
-    // As a binary format, Postcard does not encode identifiers
+    // As a binary format, Postcard does not encode the identifiers of struct fields.
+    // The only identifiers found on the wire are the tags of adjacently tagged enums,
+    // which are encoded like unit variants: by index, or by name.
     #[inline]
-    fn deserialize_identifier<V>(self, _visitor: V) -> Result<V::Value>
+    fn deserialize_identifier<V>(self, visitor: V) -> Result<V::Value>
     where
         V: Visitor<'de>,
     {
-        // Will not support
-        Err(Error::WontImplement)
+        if self.config.enum_encoding == EnumEncoding::Name {
+            let sz = self.try_take_length()?;
+            let name = self.flavor.try_take_n(sz)?;
+            return visitor.visit_borrowed_bytes(name);
+        }
+
+        visitor.visit_u32(self.try_take_varint_u32()?)
     }
 
     #[inline]
@@ -542,7 +1217,7 @@ impl<'de, 'a, F: Flavor<'de>> de::Deserializer<'de> for &'a mut Deserializer<'de
     }
 }
 
-impl<'de, 'a, F: Flavor<'de>> serde::de::VariantAccess<'de> for &'a mut Deserializer<'de, F> {
+impl<'de, F: Flavor<'de>> serde::de::VariantAccess<'de> for &mut Deserializer<'de, F> {
     type Error = Error;
 
     #[inline]
@@ -566,11 +1241,11 @@ impl<'de, 'a, F: Flavor<'de>> serde::de::VariantAccess<'de> for &'a mut Deserial
         fields: &'static [&'static str],
         visitor: V,
     ) -> Result<V::Value> {
-        serde::de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+        self.deserialize_fields(fields, visitor)
     }
 }
 
-impl<'de, 'a, F: Flavor<'de>> serde::de::EnumAccess<'de> for &'a mut Deserializer<'de, F> {
+impl<'de, F: Flavor<'de>> serde::de::EnumAccess<'de> for &mut Deserializer<'de, F> {
     type Error = Error;
     type Variant = Self;
 
@@ -582,6 +1257,67 @@ impl<'de, 'a, F: Flavor<'de>> serde::de::EnumAccess<'de> for &'a mut Deserialize
     }
 }
 
+/// Map access for the fields of a struct, which are sorted by name
+struct SortedFieldAccess<'a, 'b: 'a, F: Flavor<'b>> {
+    deserializer: &'a mut Deserializer<'b, F>,
+    fields: &'static [&'static str],
+    prev: Option<&'static str>,
+}
+
+impl<'a, 'b: 'a, F: Flavor<'b>> serde::de::MapAccess<'b> for SortedFieldAccess<'a, 'b, F> {
+    type Error = Error;
+
+    #[inline]
+    fn next_key_seed<K: DeserializeSeed<'b>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        // The next field is the first one by name that wasn't read yet
+        let next = self
+            .fields
+            .iter()
+            .copied()
+            .filter(|field| Some(*field) > self.prev)
+            .min();
+        match next {
+            Some(field) => {
+                self.prev = Some(field);
+                self.deserializer.spend_element()?;
+                let key: de::value::StrDeserializer<'_, Error> = field.into_deserializer();
+                DeserializeSeed::deserialize(seed, key).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    #[inline]
+    fn next_value_seed<V: DeserializeSeed<'b>>(&mut self, seed: V) -> Result<V::Value> {
+        let value = DeserializeSeed::deserialize(seed, &mut *self.deserializer)?;
+        self.deserializer.report_progress();
+        Ok(value)
+    }
+}
+
+/// Enum access for a variant that was already identified by name
+struct NamedEnumAccess<'a, 'b: 'a, F: Flavor<'b>> {
+    deserializer: &'a mut Deserializer<'b, F>,
+    index: u32,
+}
+
+impl<'a, 'b: 'a, F: Flavor<'b>> serde::de::EnumAccess<'b> for NamedEnumAccess<'a, 'b, F> {
+    type Error = Error;
+    type Variant = &'a mut Deserializer<'b, F>;
+
+    #[inline]
+    fn variant_seed<V: DeserializeSeed<'b>>(self, seed: V) -> Result<(V::Value, Self::Variant)> {
+        let v = DeserializeSeed::deserialize(seed, self.index.into_deserializer())?;
+        Ok((v, self.deserializer))
+    }
+}
+
+#[cfg(any(test, feature = "split-u128"))]
+#[inline(always)]
+fn join_u128(lo: u64, hi: u64) -> u128 {
+    ((hi as u128) << 64) | (lo as u128)
+}
+
 fn de_zig_zag_i16(n: u16) -> i16 {
     ((n >> 1) as i16) ^ (-((n & 0b1) as i16))
 }
@@ -597,3 +1333,55 @@ fn de_zig_zag_i64(n: u64) -> i64 {
 fn de_zig_zag_i128(n: u128) -> i128 {
     ((n >> 1) as i128) ^ (-((n & 0b1) as i128))
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::varint::varint_u128;
+
+    #[test]
+    fn split_u128_matches_native() {
+        let mut vals = [
+            0,
+            1,
+            127,
+            128,
+            u64::MAX as u128,
+            (u64::MAX as u128) + 1,
+            1 << 63,
+            1 << 64,
+            0x0123_4567_89AB_CDEF_FEDC_BA98_7654_3210,
+            u128::MAX - 1,
+            u128::MAX,
+        ]
+        .to_vec();
+        // Every single bit, to cover the seven bits straddling the two halves
+        vals.extend((0..128).map(|i| 1u128 << i));
+
+        for val in vals {
+            let mut buf = [0u8; varint_max::<u128>()];
+            let used = varint_u128(val, &mut buf);
+
+            let native = Deserializer::from_bytes(used).try_take_varint_u128_native();
+            let split = Deserializer::from_bytes(used).try_take_varint_u128_split();
+            assert_eq!(native, Ok(val));
+            assert_eq!(split, native);
+
+            let bytes = val.to_le_bytes();
+            let mut de = Deserializer::from_bytes(&bytes);
+            let lo = u64::from_le_bytes(de.try_take_array().unwrap());
+            let hi = u64::from_le_bytes(de.try_take_array().unwrap());
+            assert_eq!(join_u128(lo, hi), val);
+        }
+
+        // Invalid encodings are rejected identically
+        for bad in [&[0xFF; 19][..], &[0xFF; 20][..], &[0x80; 18][..]] {
+            let mut buf = bad.to_vec();
+            buf.push(0x04);
+            let native = Deserializer::from_bytes(&buf).try_take_varint_u128_native();
+            let split = Deserializer::from_bytes(&buf).try_take_varint_u128_split();
+            assert!(native.is_err());
+            assert_eq!(split, native);
+        }
+    }
+}
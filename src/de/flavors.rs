@@ -5,7 +5,7 @@
 //!
 //! 1. The source medium of the deserialization, e.g. whether the data is serialized from a `[u8]` slice, or some other container
 //! 2. The format of the deserialization, such as if the original data is encoded in a COBS format, contains a CRC32 checksum
-//!      appended to the message, etc.
+//!    appended to the message, etc.
 //!
 //! Flavors are implemented using the [`Flavor`] trait, which acts as a "middleware" for retrieving the bytes before they
 //! are passed to `serde` for deserialization
@@ -98,6 +98,43 @@ pub trait Flavor<'de>: 'de {
     /// Attempt to take the next `ct` bytes from the serialized message
     fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]>;
 
+    /// The number of bytes taken from the serialized message so far, if this
+    /// flavor keeps track of it.
+    ///
+    /// This is used for progress reporting, see
+    /// [`Deserializer::with_progress()`](crate::Deserializer::with_progress).
+    /// By default, `None` is returned.
+    fn consumed(&self) -> Option<usize> {
+        None
+    }
+
+    /// Attempt to take all of the remaining bytes of the serialized message
+    ///
+    /// This is used for [`RawTail`](crate::raw::RawTail). By default,
+    /// [`Error::WontImplement`] is returned, for flavors that don't know where
+    /// the message ends.
+    fn try_take_rest(&mut self) -> Result<&'de [u8]> {
+        Err(Error::WontImplement)
+    }
+
+    /// Read the string table reference in front of a string, for flavors that intern
+    /// repeated strings, such as [`Interned`].
+    ///
+    /// Returns `Ok(Some(s))` if the reference refers to an earlier string, and
+    /// `Ok(None)` if the string follows as usual. By default, nothing is read, and
+    /// `Ok(None)` is returned.
+    fn try_take_interned(&mut self) -> Result<Option<&'de str>> {
+        Ok(None)
+    }
+
+    /// Record a string that followed as usual, after
+    /// [`try_take_interned()`](Flavor::try_take_interned) returned `Ok(None)`.
+    ///
+    /// By default, this does nothing.
+    fn record_interned(&mut self, _s: &'de str) -> Result<()> {
+        Ok(())
+    }
+
     /// Complete the deserialization process.
     ///
     /// This is typically called separately, after the `serde` deserialization
@@ -109,6 +146,7 @@ pub trait Flavor<'de>: 'de {
 pub struct Slice<'de> {
     // This string starts with the input data and characters are truncated off
     // the beginning as data is parsed.
+    pub(crate) start: *const u8,
     pub(crate) cursor: *const u8,
     pub(crate) end: *const u8,
     pub(crate) _pl: PhantomData<&'de [u8]>,
@@ -118,11 +156,22 @@ impl<'de> Slice<'de> {
     /// Create a new [Slice] from the given buffer
     pub fn new(sli: &'de [u8]) -> Self {
         Self {
+            start: sli.as_ptr(),
             cursor: sli.as_ptr(),
             end: unsafe { sli.as_ptr().add(sli.len()) },
             _pl: PhantomData,
         }
     }
+
+    /// Return up to `before` already consumed bytes and up to `after` unread bytes,
+    /// surrounding the current position
+    pub(crate) fn around(&self, before: usize, after: usize) -> &'de [u8] {
+        let consumed = (self.cursor as usize) - (self.start as usize);
+        let remain = (self.end as usize) - (self.cursor as usize);
+        let before = before.min(consumed);
+        let after = after.min(remain);
+        unsafe { core::slice::from_raw_parts(self.cursor.sub(before), before + after) }
+    }
 }
 
 impl<'de> Flavor<'de> for Slice<'de> {
@@ -156,6 +205,17 @@ impl<'de> Flavor<'de> for Slice<'de> {
         }
     }
 
+    #[inline]
+    fn consumed(&self) -> Option<usize> {
+        Some((self.cursor as usize) - (self.start as usize))
+    }
+
+    #[inline]
+    fn try_take_rest(&mut self) -> Result<&'de [u8]> {
+        let remain = (self.end as usize) - (self.cursor as usize);
+        self.try_take_n(remain)
+    }
+
     /// Return the remaining (unused) bytes in the Deserializer
     fn finalize(self) -> Result<&'de [u8]> {
         let remain = (self.end as usize) - (self.cursor as usize);
@@ -163,6 +223,176 @@ impl<'de> Flavor<'de> for Slice<'de> {
     }
 }
 
+/// A [`Slice`] flavor, for messages with interned strings, as written by the
+/// [`Interned`](crate::ser_flavors::Interned) serialization flavor.
+///
+/// This keeps a table of up to `N` strings, which must be at least as large as the
+/// table used when serializing. See [`from_bytes_interned()`](crate::from_bytes_interned).
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub struct Interned<'de, const N: usize> {
+    slice: Slice<'de>,
+    table: heapless::Vec<&'de str, N>,
+    pending: bool,
+}
+
+#[cfg(feature = "heapless")]
+impl<'de, const N: usize> Interned<'de, N> {
+    /// Create a new `Interned` flavor from the given buffer
+    pub fn new(sli: &'de [u8]) -> Self {
+        Self {
+            slice: Slice::new(sli),
+            table: heapless::Vec::new(),
+            pending: false,
+        }
+    }
+
+    fn try_take_varint_usize(&mut self) -> Result<usize> {
+        use crate::varint::{max_of_last_byte, varint_max};
+
+        let mut out = 0usize;
+        for i in 0..varint_max::<usize>() {
+            let byte = self.slice.pop()?;
+            // This also catches a continuation bit on the last byte
+            if i == varint_max::<usize>() - 1 && byte > max_of_last_byte::<usize>() {
+                return Err(Error::DeserializeBadVarint);
+            }
+            out |= ((byte & 0x7F) as usize) << (7 * i);
+            if byte & 0x80 == 0 {
+                return Ok(out);
+            }
+        }
+        Err(Error::DeserializeBadVarint)
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<'de, const N: usize> Flavor<'de> for Interned<'de, N> {
+    type Remainder = &'de [u8];
+    type Source = &'de [u8];
+
+    #[inline]
+    fn pop(&mut self) -> Result<u8> {
+        self.slice.pop()
+    }
+
+    #[inline]
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        self.slice.try_take_n(ct)
+    }
+
+    #[inline]
+    fn consumed(&self) -> Option<usize> {
+        self.slice.consumed()
+    }
+
+    #[inline]
+    fn try_take_rest(&mut self) -> Result<&'de [u8]> {
+        self.slice.try_take_rest()
+    }
+
+    fn try_take_interned(&mut self) -> Result<Option<&'de str>> {
+        match self.try_take_varint_usize()? {
+            0 => {
+                self.pending = true;
+                Ok(None)
+            }
+            1 => Ok(None),
+            idx => self
+                .table
+                .get(idx - 2)
+                .copied()
+                .map(Some)
+                .ok_or(Error::DeserializeBadEncoding),
+        }
+    }
+
+    fn record_interned(&mut self, s: &'de str) -> Result<()> {
+        if core::mem::take(&mut self.pending) {
+            self.table
+                .push(s)
+                .map_err(|_| Error::DeserializeBufferFull)?;
+        }
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<&'de [u8]> {
+        self.slice.finalize()
+    }
+}
+
+/// A flavor dequeueing bytes on demand from a lock-free `heapless::spsc::Queue`,
+/// through its `Consumer`, as written by [`to_spsc()`](crate::to_spsc).
+///
+/// Only the bytes of the message are dequeued. As the queue can't be borrowed from,
+/// strings and byte slices are copied into a scratch buffer first, which must be
+/// large enough for all of them together, or [`Error::DeserializeBufferFull`] is
+/// returned. If the queue runs empty before the end of the message,
+/// [`Error::DeserializeUnexpectedEnd`] is returned, and the bytes dequeued so far are
+/// lost. See [`from_spsc()`](crate::from_spsc).
+///
+/// The remainder of this flavor is the unused portion of the scratch buffer.
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub struct SpscConsumer<'de, 'a, 'q, const N: usize> {
+    consumer: &'a mut heapless::spsc::Consumer<'q, u8, N>,
+    scratch: &'de mut [u8],
+    consumed: usize,
+}
+
+#[cfg(feature = "heapless")]
+impl<'de, 'a, 'q, const N: usize> SpscConsumer<'de, 'a, 'q, N> {
+    /// Create a new `SpscConsumer` flavor, dequeueing bytes from the given consumer
+    pub fn new(
+        consumer: &'a mut heapless::spsc::Consumer<'q, u8, N>,
+        scratch: &'de mut [u8],
+    ) -> Self {
+        Self {
+            consumer,
+            scratch,
+            consumed: 0,
+        }
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<'de, 'a: 'de, 'q: 'de, const N: usize> Flavor<'de> for SpscConsumer<'de, 'a, 'q, N> {
+    type Remainder = &'de mut [u8];
+    type Source = &'de [u8];
+
+    #[inline]
+    fn pop(&mut self) -> Result<u8> {
+        let byte = self
+            .consumer
+            .dequeue()
+            .ok_or(Error::DeserializeUnexpectedEnd)?;
+        self.consumed += 1;
+        Ok(byte)
+    }
+
+    #[inline]
+    fn try_take_n(&mut self, ct: usize) -> Result<&'de [u8]> {
+        if ct > self.scratch.len() {
+            return Err(Error::DeserializeBufferFull);
+        }
+        let (buf, rest) = core::mem::take(&mut self.scratch).split_at_mut(ct);
+        self.scratch = rest;
+        for byte in buf.iter_mut() {
+            *byte = self.pop()?;
+        }
+        Ok(buf)
+    }
+
+    #[inline]
+    fn consumed(&self) -> Option<usize> {
+        Some(self.consumed)
+    }
+
+    fn finalize(self) -> Result<&'de mut [u8]> {
+        Ok(self.scratch)
+    }
+}
+
 /// Support for [std::io] or [embedded-io] traits
 #[cfg(any(feature = "embedded-io", feature = "use-std"))]
 pub mod io {
@@ -269,6 +499,7 @@ pub mod io {
 
     /// Support for [std::io] traits
     #[cfg(feature = "use-std")]
+    #[allow(clippy::module_inception)]
     pub mod io {
         use super::super::Flavor;
         use super::SlidingBuffer;
@@ -413,6 +644,11 @@ pub mod crc {
                             }
                         }
 
+                        #[inline]
+                        fn consumed(&self) -> Option<usize> {
+                            self.flav.consumed()
+                        }
+
                         fn finalize(mut self) -> Result<Self::Remainder> {
                             match self.flav.try_take_n(core::mem::size_of::<$int>()) {
                                 Ok(prev_crc_bytes) => match self.flav.finalize() {
@@ -0,0 +1,230 @@
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+use core::{fmt, marker::PhantomData};
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+use serde::de::{Deserialize, SeqAccess, Visitor};
+use serde::Deserializer;
+
+use crate::de::deserializer::Deserializer as PostcardDeserializer;
+use crate::error::Result;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+/// A type that can be deserialized into an existing value, reusing its storage.
+///
+/// `Deserialize` always creates a fresh value, so deserializing a `Vec` in a loop
+/// allocates each time. Types implementing `DeserializeInto` instead clear the
+/// existing value, and refill it in place, keeping the storage (such as the capacity
+/// of a `Vec`) that was already allocated.
+///
+/// This uses the same wire format as `Deserialize`. See [`from_bytes_into()`].
+pub trait DeserializeInto<'de> {
+    /// Deserialize a value into `place`, replacing its previous contents.
+    ///
+    /// If an error is returned, the contents of `place` are unspecified.
+    fn deserialize_into<D>(deserializer: D, place: &mut Self) -> core::result::Result<(), D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+/// Visits a sequence, pushing each element with `push`
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+struct RefillVisitor<'p, C, T> {
+    place: &'p mut C,
+    _pd: PhantomData<T>,
+}
+
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+impl<'p, C, T> RefillVisitor<'p, C, T> {
+    fn new(place: &'p mut C) -> Self {
+        RefillVisitor {
+            place,
+            _pd: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'de, 'p, T> Visitor<'de> for RefillVisitor<'p, alloc::vec::Vec<T>, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> core::result::Result<(), A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        self.place.clear();
+        while let Some(elem) = seq.next_element()? {
+            self.place.push(elem);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<'de, T> DeserializeInto<'de> for alloc::vec::Vec<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize_into<D>(deserializer: D, place: &mut Self) -> core::result::Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(RefillVisitor::new(place))
+    }
+}
+
+#[cfg(feature = "use-smallvec")]
+impl<'de, 'p, A> Visitor<'de> for RefillVisitor<'p, smallvec::SmallVec<A>, A::Item>
+where
+    A: smallvec::Array,
+    A::Item: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence")
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> core::result::Result<(), S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        self.place.clear();
+        while let Some(elem) = seq.next_element()? {
+            self.place.push(elem);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "use-smallvec")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "use-smallvec")))]
+impl<'de, A> DeserializeInto<'de> for smallvec::SmallVec<A>
+where
+    A: smallvec::Array,
+    A::Item: Deserialize<'de>,
+{
+    fn deserialize_into<D>(deserializer: D, place: &mut Self) -> core::result::Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(RefillVisitor::new(place))
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<'de, 'p, T, const N: usize> Visitor<'de> for RefillVisitor<'p, heapless::Vec<T, N>, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a sequence of at most {} elements", N)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> core::result::Result<(), A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        self.place.clear();
+        while let Some(elem) = seq.next_element()? {
+            if self.place.push(elem).is_err() {
+                return Err(serde::de::Error::invalid_length(N + 1, &self));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+impl<'de, T, const N: usize> DeserializeInto<'de> for heapless::Vec<T, N>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize_into<D>(deserializer: D, place: &mut Self) -> core::result::Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(RefillVisitor::new(place))
+    }
+}
+
+/// Deserialize a message from a byte slice into an existing `target`, reusing its
+/// storage instead of allocating a new value. The unused portion (if any) of the
+/// byte slice is not returned.
+///
+/// See [`DeserializeInto`] for the supported types.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::from_bytes_into;
+/// use heapless::Vec;
+///
+/// let mut buf: Vec<u8, 8> = Vec::new();
+/// from_bytes_into(&[0x02, 0x0A, 0x0B], &mut buf).unwrap();
+/// assert_eq!(&buf, &[0x0A, 0x0B]);
+///
+/// from_bytes_into(&[0x01, 0x0C], &mut buf).unwrap();
+/// assert_eq!(&buf, &[0x0C]);
+/// ```
+pub fn from_bytes_into<'a, T>(s: &'a [u8], target: &mut T) -> Result<()>
+where
+    T: DeserializeInto<'a> + ?Sized,
+{
+    let mut deserializer = PostcardDeserializer::from_bytes(s);
+    T::deserialize_into(&mut deserializer, target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::from_bytes_into;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn reuses_allocvec() {
+        use super::alloc::vec::Vec;
+
+        let mut target: Vec<u8> = Vec::new();
+        from_bytes_into(&[0x04, 0x01, 0x02, 0x03, 0x04], &mut target).unwrap();
+        assert_eq!(target, [1, 2, 3, 4]);
+        let ptr = target.as_ptr();
+        let capacity = target.capacity();
+
+        // A shorter message refills the same allocation
+        from_bytes_into(&[0x02, 0x05, 0x06], &mut target).unwrap();
+        assert_eq!(target, [5, 6]);
+        assert_eq!(target.as_ptr(), ptr);
+        assert_eq!(target.capacity(), capacity);
+
+        // Same for non-byte elements
+        let mut words: Vec<u16> = Vec::with_capacity(4);
+        let ptr = words.as_ptr();
+        from_bytes_into(&[0x02, 0xAC, 0x02, 0x01], &mut words).unwrap();
+        assert_eq!(words, [300, 1]);
+        assert_eq!(words.as_ptr(), ptr);
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn refills_heapless() {
+        let mut target: heapless::Vec<u8, 3> = heapless::Vec::new();
+        from_bytes_into(&[0x03, 0x01, 0x02, 0x03], &mut target).unwrap();
+        assert_eq!(&target, &[1, 2, 3]);
+        from_bytes_into(&[0x00], &mut target).unwrap();
+        assert!(target.is_empty());
+
+        let res = from_bytes_into(&[0x04, 0x01, 0x02, 0x03, 0x04], &mut target);
+        assert_eq!(res, Err(crate::Error::SerdeDeCustom));
+    }
+}
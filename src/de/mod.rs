@@ -1,12 +1,21 @@
-use cobs::{decode_in_place, decode_in_place_report};
+use cobs::{decode_in_place, decode_in_place_report, CobsDecoder};
+use core::convert::TryFrom;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
 
 pub(crate) mod deserializer;
 pub mod flavors;
+mod into;
 
+pub use into::{from_bytes_into, DeserializeInto};
+
+use crate::config::{Config, EnumEncoding};
 use crate::error::{Error, Result};
 use deserializer::Deserializer;
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 /// Deserialize a message of type `T` from a byte slice. The unused portion (if any)
 /// of the byte slice is not returned.
 pub fn from_bytes<'a, T>(s: &'a [u8]) -> Result<T>
@@ -18,6 +27,56 @@ where
     Ok(t)
 }
 
+/// Deserialize a message of type `T`, which does not borrow from the input, from a byte
+/// slice. The unused portion (if any) of the byte slice is not returned.
+///
+/// This behaves like [`from_bytes()`], but makes the owned-only intent explicit, so
+/// generic code doesn't need to tie `T` to the lifetime of the input, and the input
+/// buffer can be reused as soon as this returns.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::from_bytes_owned;
+///
+/// let mut buf = [0x03, b'H', b'i', b'!'];
+/// let out: heapless::String<8> = from_bytes_owned(&buf).unwrap();
+/// buf.fill(0);
+/// assert_eq!(out, "Hi!");
+/// ```
+///
+/// Types borrowing from the input are rejected at compile time:
+///
+/// ```rust,compile_fail
+/// let out: &str = postcard::from_bytes_owned(&[0x03, b'H', b'i', b'!']).unwrap();
+/// ```
+///
+/// For a result that outlives the input, use owned fields such as `String` or `Vec`.
+/// `Cow` fields work as well, as long as they don't use `#[serde(borrow)]`: they then
+/// always own a copy of their data, while the same type can still hold borrowed data
+/// when it is serialized.
+///
+/// ```rust
+/// use serde::Deserialize;
+/// use std::borrow::Cow;
+///
+/// #[derive(Deserialize)]
+/// struct Label<'a> {
+///     text: Cow<'a, str>,
+/// }
+///
+/// let buf = vec![0x02, b'o', b'k'];
+/// let label: Label<'static> = postcard::from_bytes_owned(&buf).unwrap();
+/// drop(buf);
+/// assert!(matches!(label.text, Cow::Owned(_)));
+/// ```
+pub fn from_bytes_owned<T>(s: &[u8]) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    from_bytes(s)
+}
+
 /// Deserialize a message of type `T` from a cobs-encoded byte slice. The
 /// unused portion (if any) of the byte slice is not returned.
 /// The used portion of the input slice is modified during deserialization (even if an error is returned).
@@ -30,6 +89,45 @@ where
     from_bytes::<T>(&s[..sz])
 }
 
+/// Deserialize a message of type `T` from a cobs-encoded byte slice, decoding into
+/// the given `scratch` buffer. The unused portion (if any) of the byte slice is not returned.
+///
+/// Unlike [`from_bytes_cobs()`], the input slice is not modified, so the encoded
+/// message remains available, e.g. for retransmission. The deserialized value may
+/// borrow from `scratch`. If `scratch` is too small to hold the decoded message,
+/// [`Error::DeserializeBufferFull`] is returned.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::from_bytes_cobs_to;
+///
+/// let encoded = [0x05, 0x03, b'H', b'i', b'!', 0x00];
+/// let mut scratch = [0u8; 8];
+/// let out: &str = from_bytes_cobs_to(&encoded, &mut scratch).unwrap();
+/// assert_eq!(out, "Hi!");
+/// ```
+pub fn from_bytes_cobs_to<'a, T>(s: &[u8], scratch: &'a mut [u8]) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let scratch_len = scratch.len();
+    let mut decoder = CobsDecoder::new(scratch);
+    let mut res = decoder.push(s);
+
+    // Like `from_bytes_cobs`, the terminator is optional
+    if matches!(res, Ok(None)) && s.last() != Some(&0) {
+        res = decoder.push(&[0]);
+    }
+
+    let sz = match res {
+        Ok(Some((sz, _))) => sz,
+        Err(written) if written == scratch_len => return Err(Error::DeserializeBufferFull),
+        _ => return Err(Error::DeserializeBadEncoding),
+    };
+    from_bytes::<T>(&scratch[..sz])
+}
+
 /// Deserialize a message of type `T` from a cobs-encoded byte slice. The
 /// unused portion (if any) of the byte slice is returned for further usage.
 /// The used portion of the input slice is modified during deserialization (even if an error is returned).
@@ -56,6 +154,38 @@ where
     Ok((from_bytes::<T>(dst_used)?, src_unused))
 }
 
+/// Split a byte slice at the first `0x00` frame delimiter, without COBS decoding.
+///
+/// Returns the frame before the delimiter, and the remaining bytes after it. The
+/// delimiter itself is not included in either. This is useful for transports that
+/// delimit frames with a zero byte, but guarantee by other means that the frame
+/// itself contains no zero bytes. For COBS encoded frames, use [`from_bytes_cobs()`]
+/// or [`take_from_bytes_cobs()`] instead.
+///
+/// If there is no delimiter, the frame is incomplete, and
+/// [`Error::DeserializeUnexpectedEnd`] is returned.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{from_bytes, take_until_zero};
+///
+/// let input = [0x01, 0x02, 0x00, 0x03];
+/// let (frame, remain) = take_until_zero(&input).unwrap();
+/// assert_eq!(frame, &[0x01, 0x02]);
+/// assert_eq!(remain, &[0x03]);
+///
+/// let val: (u8, u8) = from_bytes(frame).unwrap();
+/// assert_eq!(val, (1, 2));
+/// ```
+pub fn take_until_zero(s: &[u8]) -> Result<(&[u8], &[u8])> {
+    let pos = s
+        .iter()
+        .position(|b| *b == 0)
+        .ok_or(Error::DeserializeUnexpectedEnd)?;
+    Ok((&s[..pos], &s[pos + 1..]))
+}
+
 /// Deserialize a message of type `T` from a byte slice. The unused portion (if any)
 /// of the byte slice is returned for further usage
 pub fn take_from_bytes<'a, T>(s: &'a [u8]) -> Result<(T, &'a [u8])>
@@ -67,6 +197,515 @@ where
     Ok((t, deserializer.finalize()?))
 }
 
+/// Deserialize a message of type `T` from a byte slice, which must be followed by
+/// exactly one `sentinel` byte.
+///
+/// This is a lightweight sanity check for simple protocols, which catches truncated
+/// or misaligned frames. If the byte following the message is not `sentinel`,
+/// [`Error::DeserializeBadSentinel`] is returned. If there are more bytes after the
+/// sentinel, [`Error::DeserializeTrailingBytes`] is returned.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{from_bytes_sentinel, Error};
+///
+/// let out: (u8, u16) = from_bytes_sentinel(&[0x01, 0xAC, 0x02, 0xA5], 0xA5).unwrap();
+/// assert_eq!(out, (1, 300));
+///
+/// let res = from_bytes_sentinel::<(u8, u16)>(&[0x01, 0xAC, 0x02, 0x00], 0xA5);
+/// assert_eq!(res, Err(Error::DeserializeBadSentinel));
+/// ```
+pub fn from_bytes_sentinel<'a, T>(s: &'a [u8], sentinel: u8) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let (t, rest) = take_from_bytes(s)?;
+    match rest {
+        [] => Err(Error::DeserializeUnexpectedEnd),
+        [last] if *last == sentinel => Ok(t),
+        [_] => Err(Error::DeserializeBadSentinel),
+        _ => Err(Error::DeserializeTrailingBytes),
+    }
+}
+
+/// Deserialize the last message of type `T` from a byte slice, which ends with the length
+/// of the message as a four byte little endian `u32` footer, as created by
+/// [`to_vec_footer_len()`](crate::to_vec_footer_len) or the
+/// [`LengthFooter`](crate::ser_flavors::LengthFooter) flavor.
+///
+/// The message is found by reading the footer at the end of the slice first, so this
+/// can read an append-only log backwards. Returns the message, and all of the bytes
+/// before it, e.g. earlier messages of the log.
+///
+/// If the footer is longer than the bytes before it, [`Error::DeserializeUnexpectedEnd`]
+/// is returned. If the message doesn't use exactly the number of bytes given by the
+/// footer, [`Error::DeserializeUnexpectedEnd`] or [`Error::DeserializeTrailingBytes`] is
+/// returned.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::from_bytes_footer_len;
+///
+/// // Two messages, each followed by its length
+/// let log = [0x01, 0x01, 0x00, 0x00, 0x00, 0xAC, 0x02, 0x02, 0x00, 0x00, 0x00];
+/// let (last, rest): (u16, _) = from_bytes_footer_len(&log).unwrap();
+/// assert_eq!(last, 300);
+/// let (first, rest): (u16, _) = from_bytes_footer_len(rest).unwrap();
+/// assert_eq!(first, 1);
+/// assert!(rest.is_empty());
+/// ```
+pub fn from_bytes_footer_len<'a, T>(s: &'a [u8]) -> Result<(T, &'a [u8])>
+where
+    T: Deserialize<'a>,
+{
+    let split = s
+        .len()
+        .checked_sub(4)
+        .ok_or(Error::DeserializeUnexpectedEnd)?;
+    let (data, footer) = s.split_at(split);
+    let mut len = [0u8; 4];
+    len.copy_from_slice(footer);
+    let len =
+        usize::try_from(u32::from_le_bytes(len)).map_err(|_| Error::DeserializeUnexpectedEnd)?;
+    let start = data
+        .len()
+        .checked_sub(len)
+        .ok_or(Error::DeserializeUnexpectedEnd)?;
+    let (rest, msg) = data.split_at(start);
+    match take_from_bytes(msg)? {
+        (t, []) => Ok((t, rest)),
+        _ => Err(Error::DeserializeTrailingBytes),
+    }
+}
+
+/// Deserialize an enum message of type `T` from a byte slice, only if its variant is
+/// one of the `allowed` variant indices.
+///
+/// The discriminant is checked before the rest of the message is decoded, so that an
+/// endpoint can reject commands it doesn't accept, even if they are well formed. If
+/// the variant is not allowed, [`Error::DeserializeForbiddenVariant`] is returned.
+///
+/// This expects `T` to be an enum, encoded with the default
+/// [`EnumEncoding::Index`](crate::EnumEncoding::Index).
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{from_bytes_allowed_variants, Error};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// enum Command {
+///     Status,
+///     SetLevel(u8),
+///     Reboot,
+/// }
+///
+/// let allowed = [0, 1];
+/// let cmd: Command = from_bytes_allowed_variants(&[0x01, 0x05], &allowed).unwrap();
+/// assert_eq!(cmd, Command::SetLevel(5));
+///
+/// let res = from_bytes_allowed_variants::<Command>(&[0x02], &allowed);
+/// assert_eq!(res, Err(Error::DeserializeForbiddenVariant));
+/// ```
+pub fn from_bytes_allowed_variants<'a, T>(s: &'a [u8], allowed: &[u32]) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let (variant, _) = take_from_bytes::<u32>(s)?;
+    if !allowed.contains(&variant) {
+        return Err(Error::DeserializeForbiddenVariant);
+    }
+    from_bytes(s)
+}
+
+/// Deserialize a message of type `T` from a byte slice, which starts with a `u8`
+/// protocol version, only if the version is at least `min`. The unused portion (if
+/// any) of the byte slice is not returned.
+///
+/// The version byte is checked before the rest of the message is decoded. If it is
+/// older than `min`, [`Error::DeserializeUnsupportedVersion`] is returned. Newer
+/// versions are accepted, so these are expected to only add to the message, e.g.
+/// with new trailing fields.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{from_bytes_min_version, Error};
+///
+/// let level: u8 = from_bytes_min_version(&[0x02, 0x05], 2).unwrap();
+/// assert_eq!(level, 5);
+///
+/// let res = from_bytes_min_version::<u8>(&[0x01, 0x05], 2);
+/// assert_eq!(res, Err(Error::DeserializeUnsupportedVersion));
+/// ```
+pub fn from_bytes_min_version<'a, T>(s: &'a [u8], min: u8) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let (version, msg) = s.split_first().ok_or(Error::DeserializeUnexpectedEnd)?;
+    if *version < min {
+        return Err(Error::DeserializeUnsupportedVersion);
+    }
+    from_bytes(msg)
+}
+
+/// Borrow a `#[repr(C)]` plain old data type `T` directly from a byte slice, without
+/// copying or deserializing it field by field.
+///
+/// The slice must be exactly `size_of::<T>()` bytes long, and aligned for `T`. A
+/// shorter slice returns [`Error::DeserializeUnexpectedEnd`], a longer one returns
+/// [`Error::DeserializeTrailingBytes`], and a misaligned one returns
+/// [`Error::DeserializeMisaligned`].
+///
+/// NOTE: This is not the postcard wire format. The bytes are the memory of `T` on the
+/// target, so they use its native endianness, and the layout of the type as the
+/// compiler lays it out, including any padding. Data written on a target with another
+/// endianness is silently misread. Use the
+/// [`CLayout`](crate::experimental::c_layout::CLayout) derive to produce such bytes
+/// portably from a little endian description.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{from_bytes_ref, Error};
+///
+/// #[repr(C)]
+/// #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+/// struct Sample {
+///     channel: u16,
+///     level: u16,
+/// }
+///
+/// // Aligned storage, as a `[u8; 4]` on its own may be placed anywhere
+/// let words: [u16; 2] = [3, 300];
+/// let bytes: &[u8] = bytemuck::cast_slice(&words);
+///
+/// let sample: &Sample = from_bytes_ref(bytes).unwrap();
+/// assert_eq!((sample.channel, sample.level), (3, 300));
+///
+/// let res = from_bytes_ref::<Sample>(&bytes[..3]);
+/// assert_eq!(res.err(), Some(Error::DeserializeUnexpectedEnd));
+/// ```
+#[cfg(feature = "use-bytemuck")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "use-bytemuck")))]
+pub fn from_bytes_ref<T: bytemuck::Pod>(s: &[u8]) -> Result<&T> {
+    let size = core::mem::size_of::<T>();
+    if s.len() < size {
+        return Err(Error::DeserializeUnexpectedEnd);
+    }
+    if s.len() > size {
+        return Err(Error::DeserializeTrailingBytes);
+    }
+    bytemuck::try_from_bytes(s).map_err(|_| Error::DeserializeMisaligned)
+}
+
+/// The framing of a message, for use with [`from_frame()`].
+#[derive(Clone, Copy)]
+#[non_exhaustive]
+pub enum Framing {
+    /// The message as is, as used by [`from_bytes()`]
+    Raw,
+    /// A COBS encoded message, as used by [`from_bytes_cobs()`]
+    Cobs,
+    /// A message followed by its CRC32, as used by [`from_bytes_crc32()`]
+    #[cfg(feature = "use-crc")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "use-crc")))]
+    Crc32(&'static crc::Crc<u32>),
+    /// A message prefixed with its length in bytes, encoded as a varint
+    LengthPrefixed,
+}
+
+/// Deserialize a message of type `T` from a frame, with the given [`Framing`]. The
+/// unused portion (if any) of the byte slice is not returned.
+///
+/// This is useful when the framing is only known at runtime, such as from a
+/// configuration option. As COBS frames are decoded in place, the contents of the
+/// byte slice are unspecified afterwards.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{from_frame, Framing};
+///
+/// let mut raw = [0x01, 0x00, 0x20];
+/// let mut cobs = [0x02, 0x01, 0x02, 0x20, 0x00];
+/// let mut prefixed = [0x03, 0x01, 0x00, 0x20];
+///
+/// let expected = (1u8, 0u8, 0x20u8);
+/// assert_eq!(from_frame(&mut raw, Framing::Raw), Ok(expected));
+/// assert_eq!(from_frame(&mut cobs, Framing::Cobs), Ok(expected));
+/// assert_eq!(from_frame(&mut prefixed, Framing::LengthPrefixed), Ok(expected));
+/// ```
+pub fn from_frame<'a, T>(s: &'a mut [u8], framing: Framing) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    match framing {
+        Framing::Raw => from_bytes(s),
+        Framing::Cobs => from_bytes_cobs(s),
+        #[cfg(feature = "use-crc")]
+        Framing::Crc32(crc) => from_bytes_crc32(s, crc.digest()),
+        Framing::LengthPrefixed => {
+            let (len, rest) = take_from_bytes::<usize>(s)?;
+            let frame = rest.get(..len).ok_or(Error::DeserializeUnexpectedEnd)?;
+            from_bytes(frame)
+        }
+    }
+}
+
+/// Deserialize a message of type `T` from each of the given frames, independently.
+///
+/// The result of each frame is collected at the same index, so a corrupt frame does
+/// not abort the whole batch, and the indices of the failed frames can be reported.
+/// This is a convenience wrapper around [`from_bytes()`].
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{from_bytes_batch, Error};
+///
+/// let frames: [&[u8]; 3] = [&[0x01], &[0x02], &[]];
+/// let results = from_bytes_batch::<bool, _>(frames);
+/// assert_eq!(
+///     results,
+///     [Ok(true), Err(Error::DeserializeBadBool), Err(Error::DeserializeUnexpectedEnd)]
+/// );
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub fn from_bytes_batch<'a, T, I>(frames: I) -> alloc::vec::Vec<Result<T>>
+where
+    T: Deserialize<'a>,
+    I: IntoIterator<Item = &'a [u8]>,
+{
+    frames.into_iter().map(from_bytes).collect()
+}
+
+/// Deserialize a sequence of `T` (such as a `Vec<T>`) from a byte slice, keeping the
+/// elements that were decoded before an error.
+///
+/// Instead of discarding the whole sequence when an element fails to deserialize, e.g.
+/// because the frame was truncated, the leading elements are returned, along with the
+/// error. If the whole sequence was decoded, the error is `None`.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{from_bytes_partial_seq, Error};
+///
+/// // Three `u16`s, where the last one is truncated
+/// let (out, err) = from_bytes_partial_seq::<u16>(&[0x03, 0x01, 0xAC, 0x02, 0x80]);
+/// assert_eq!(out, [1, 300]);
+/// assert_eq!(err, Some(Error::DeserializeUnexpectedEnd));
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub fn from_bytes_partial_seq<'a, T>(s: &'a [u8]) -> (alloc::vec::Vec<T>, Option<Error>)
+where
+    T: Deserialize<'a>,
+{
+    let mut out = alloc::vec::Vec::new();
+    let mut deserializer = Deserializer::from_bytes(s);
+    let len = match deserializer.try_take_length() {
+        Ok(len) => len,
+        Err(e) => return (out, Some(e)),
+    };
+    for _ in 0..len {
+        match T::deserialize(&mut deserializer) {
+            Ok(t) => out.push(t),
+            Err(e) => return (out, Some(e)),
+        }
+    }
+    (out, None)
+}
+
+/// Deserialize a message of type `T` from a byte slice, which is padded to a multiple
+/// of `BLOCK` bytes with PKCS#7 padding, e.g. after decrypting it with a block cipher.
+/// The unused portion (if any) of the byte slice is not returned.
+///
+/// The padding is validated before deserializing: the length must be a non-zero multiple
+/// of `BLOCK`, and the last byte `n` must be between `1` and `BLOCK`, with the last `n`
+/// bytes all equal to `n`. Otherwise, [`Error::DeserializeBadEncoding`] is returned. See
+/// the [`Pkcs7`](crate::ser_flavors::Pkcs7) flavor for a description of the padding.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::from_bytes_padded;
+///
+/// let val: (u8, u8) = from_bytes_padded::<_, 4>(&[0x01, 0x02, 0x02, 0x02]).unwrap();
+/// assert_eq!(val, (1, 2));
+/// ```
+pub fn from_bytes_padded<'a, T, const BLOCK: usize>(s: &'a [u8]) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let blocks = s.chunks_exact(BLOCK.max(1));
+    if s.is_empty() || !blocks.remainder().is_empty() {
+        return Err(Error::DeserializeBadEncoding);
+    }
+    let pad = s[s.len() - 1];
+    let unpadded = s.len().wrapping_sub(pad as usize);
+    if pad == 0 || pad as usize > BLOCK || s[unpadded..].iter().any(|b| *b != pad) {
+        return Err(Error::DeserializeBadEncoding);
+    }
+    from_bytes(&s[..unpadded])
+}
+
+/// Deserialize a message of type `T` from a byte slice, which is prefixed with a
+/// `u16` type ID. The unused portion (if any) of the byte slice is not returned.
+///
+/// If the type ID does not match `expected_id`, [`Error::DeserializeWrongType`] is
+/// returned, and the rest of the message is not deserialized. The type ID is encoded
+/// like any other `u16` (as a varint), so the message can be created by serializing
+/// the tuple `(id, msg)`.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{from_bytes_typed, to_slice, Error};
+///
+/// const PING_ID: u16 = 0x0101;
+/// const PONG_ID: u16 = 0x0102;
+///
+/// let mut buf = [0u8; 32];
+/// let used = to_slice(&(PING_ID, 42u8), &mut buf).unwrap();
+///
+/// assert_eq!(from_bytes_typed::<u8>(used, PING_ID), Ok(42));
+/// assert_eq!(from_bytes_typed::<u8>(used, PONG_ID), Err(Error::DeserializeWrongType));
+/// ```
+pub fn from_bytes_typed<'a, T>(s: &'a [u8], expected_id: u16) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = Deserializer::from_bytes(s);
+    let id = u16::deserialize(&mut deserializer)?;
+    if id != expected_id {
+        return Err(Error::DeserializeWrongType);
+    }
+    let t = T::deserialize(&mut deserializer)?;
+    Ok(t)
+}
+
+/// Deserialize a message of type `T` from a byte slice, with the fields of every struct
+/// (and struct variant) sorted by name, as written by
+/// [`to_allocvec_field_sorted()`](crate::to_allocvec_field_sorted). The unused portion
+/// (if any) of the byte slice is not returned.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::from_bytes_field_sorted;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct Reading {
+///     value: u16,
+///     sensor: u8,
+/// }
+///
+/// // `sensor` is before `value`, as it is first by name
+/// let out: Reading = from_bytes_field_sorted(&[0x03, 0xAC, 0x02]).unwrap();
+/// assert_eq!(out, Reading { value: 300, sensor: 3 });
+/// ```
+pub fn from_bytes_field_sorted<'a, T>(s: &'a [u8]) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = Deserializer::from_bytes(s).with_sorted_fields(true);
+    let t = T::deserialize(&mut deserializer)?;
+    Ok(t)
+}
+
+/// Deserialize a message of type `T` from a byte slice, using a non-default [`Config`].
+/// The unused portion (if any) of the byte slice is not returned.
+///
+/// The `config` must match the one used to serialize the message.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{from_bytes_with_config, Config, IntEncoding};
+///
+/// let config = Config::new().int_encoding(IntEncoding::Fixed);
+/// let val: u32 = from_bytes_with_config(&[0x2C, 0x01, 0x00, 0x00], config).unwrap();
+/// assert_eq!(val, 300);
+/// ```
+pub fn from_bytes_with_config<'a, T>(s: &'a [u8], config: Config) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = Deserializer::from_bytes(s).with_config(config);
+    let t = T::deserialize(&mut deserializer)?;
+    Ok(t)
+}
+
+/// Deserialize a message of type `T` from a byte slice, with enum variants identified by
+/// their name rather than their index. The unused portion (if any) of the byte slice is
+/// not returned.
+///
+/// This is a shorthand for [`from_bytes_with_config()`] with [`EnumEncoding::Name`], and
+/// is the counterpart of [`to_vec_named()`](crate::to_vec_named). An unknown variant name
+/// returns [`Error::DeserializeBadEnum`].
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::from_bytes_named;
+///
+/// let val: Result<u8, ()> = from_bytes_named(&[0x02, b'O', b'k', 0x05]).unwrap();
+/// assert_eq!(val, Ok(5));
+/// ```
+pub fn from_bytes_named<'a, T>(s: &'a [u8]) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    from_bytes_with_config(s, Config::new().enum_encoding(EnumEncoding::Name))
+}
+
+/// Deserialize a message of type `T` from a byte slice, with deduplicated strings as
+/// written by [`to_vec_interned()`](crate::to_vec_interned). The unused portion (if any)
+/// of the byte slice is not returned.
+///
+/// Repeated strings are borrowed from their first occurrence in the input. Up to `N`
+/// strings are kept, which must be at least as many as were interned when serializing.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::from_bytes_interned;
+///
+/// let input = [0x02, 0x00, 0x02, b'o', b'k', 0x02];
+/// let out: (u8, &str, &str) = from_bytes_interned::<_, 8>(&input).unwrap();
+/// assert_eq!(out, (2, "ok", "ok"));
+/// ```
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub fn from_bytes_interned<'a, T, const N: usize>(s: &'a [u8]) -> Result<T>
+where
+    T: Deserialize<'a>,
+{
+    let flavor = flavors::Interned::<'a, N>::new(s);
+    let mut deserializer = Deserializer::from_flavor(flavor);
+    T::deserialize(&mut deserializer)
+}
+
+/// Deserialize a message of type `T` from a byte slice, using a non-default [`Config`].
+/// The unused portion (if any) of the byte slice is returned for further usage
+///
+/// The `config` must match the one used to serialize the message.
+pub fn take_from_bytes_with_config<'a, T>(s: &'a [u8], config: Config) -> Result<(T, &'a [u8])>
+where
+    T: Deserialize<'a>,
+{
+    let mut deserializer = Deserializer::from_bytes(s).with_config(config);
+    let t = T::deserialize(&mut deserializer)?;
+    Ok((t, deserializer.finalize()?))
+}
+
 /// Deserialize a message of type `T` from a [embedded_io::blocking::Read].
 #[cfg(feature = "embedded-io")]
 pub fn from_eio<'a, T, R>(val: (R, &'a mut [u8])) -> Result<(T, (R, &'a mut [u8]))>
@@ -80,6 +719,50 @@ where
     Ok((t, deserializer.finalize()?))
 }
 
+/// Deserialize a message of type `T` directly from a `heapless::spsc::Queue`, through
+/// its `Consumer`, dequeueing only the bytes of the message.
+///
+/// Strings and byte slices are copied into `scratch` before deserializing them, so
+/// `T` must be an owned type, and `scratch` must be large enough for all of them
+/// together. See [`SpscConsumer`](crate::de_flavors::SpscConsumer) for details.
+///
+/// If the queue runs empty before the end of the message,
+/// [`Error::DeserializeUnexpectedEnd`] is returned, and the bytes dequeued so far are
+/// lost, so the whole message should be in the queue before calling this, e.g. as
+/// written by [`to_spsc()`](crate::to_spsc).
+///
+/// ## Example
+///
+/// ```rust
+/// use heapless::spsc::Queue;
+/// use postcard::from_spsc;
+///
+/// let mut queue: Queue<u8, 8> = Queue::new();
+/// let (mut producer, mut consumer) = queue.split();
+/// for byte in [0x01, 0x02, b'H', b'i', 0xFF] {
+///     producer.enqueue(byte).unwrap();
+/// }
+///
+/// let mut scratch = [0u8; 4];
+/// let out: (u8, heapless::String<4>) = from_spsc(&mut consumer, &mut scratch).unwrap();
+/// assert_eq!(out, (1, "Hi".into()));
+/// assert_eq!(consumer.dequeue(), Some(0xFF));
+/// ```
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub fn from_spsc<T, const N: usize>(
+    consumer: &mut heapless::spsc::Consumer<'_, u8, N>,
+    scratch: &mut [u8],
+) -> Result<T>
+where
+    T: DeserializeOwned,
+{
+    let flavor = flavors::SpscConsumer::new(consumer, scratch);
+    let mut deserializer = Deserializer::from_flavor(flavor);
+    let t = T::deserialize(&mut deserializer)?;
+    Ok(t)
+}
+
 /// Deserialize a message of type `T` from a[std::io::Read].
 #[cfg(feature = "use-std")]
 pub fn from_io<'a, T, R>(val: (R, &'a mut [u8])) -> Result<(T, (R, &'a mut [u8]))>
@@ -139,7 +822,7 @@ mod test_heapless {
     #[test]
     fn de_u8() {
         let output: Vec<u8, 1> = to_vec(&0x05u8).unwrap();
-        assert!(&[5] == output.deref());
+        assert!([5] == output.deref());
 
         let out: u8 = from_bytes(output.deref()).unwrap();
         assert_eq!(out, 0x05);
@@ -148,7 +831,7 @@ mod test_heapless {
     #[test]
     fn de_u16() {
         let output: Vec<u8, { varint_max::<u16>() }> = to_vec(&0xA5C7u16).unwrap();
-        assert!(&[0xC7, 0xCB, 0x02] == output.deref());
+        assert!([0xC7, 0xCB, 0x02] == output.deref());
 
         let out: u16 = from_bytes(output.deref()).unwrap();
         assert_eq!(out, 0xA5C7);
@@ -166,7 +849,7 @@ mod test_heapless {
     #[test]
     fn de_u64() {
         let output: Vec<u8, { varint_max::<u64>() }> = to_vec(&0x1234_5678_90AB_CDEFu64).unwrap();
-        assert!(&[0xEF, 0x9B, 0xAF, 0x85, 0x89, 0xCF, 0x95, 0x9A, 0x12] == output.deref());
+        assert!([0xEF, 0x9B, 0xAF, 0x85, 0x89, 0xCF, 0x95, 0x9A, 0x12] == output.deref());
 
         let out: u64 = from_bytes(output.deref()).unwrap();
         assert_eq!(out, 0x1234_5678_90AB_CDEFu64);
@@ -177,7 +860,7 @@ mod test_heapless {
         let output: Vec<u8, { varint_max::<u128>() }> =
             to_vec(&0x1234_5678_90AB_CDEF_1234_5678_90AB_CDEFu128).unwrap();
         assert!(
-            &[
+            [
                 0xEF, 0x9B, 0xAF, 0x85, 0x89, 0xCF, 0x95, 0x9A, 0x92, 0xDE, 0xB7, 0xDE, 0x8A, 0x92,
                 0x9E, 0xAB, 0xB4, 0x24,
             ] == output.deref()
@@ -310,22 +993,22 @@ mod test_heapless {
         assert_eq!(out, BasicEnum::Bim);
 
         let output: Vec<u8, { 1 + varint_max::<u64>() }> =
-            to_vec(&DataEnum::Bim(u64::max_value())).unwrap();
+            to_vec(&DataEnum::Bim(u64::MAX)).unwrap();
         assert_eq!(
             &[0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01],
             output.deref()
         );
 
         let output: Vec<u8, { 1 + varint_max::<u16>() }> =
-            to_vec(&DataEnum::Bib(u16::max_value())).unwrap();
+            to_vec(&DataEnum::Bib(u16::MAX)).unwrap();
         assert_eq!(&[0x00, 0xFF, 0xFF, 0x03], output.deref());
         let out: DataEnum = from_bytes(output.deref()).unwrap();
-        assert_eq!(out, DataEnum::Bib(u16::max_value()));
+        assert_eq!(out, DataEnum::Bib(u16::MAX));
 
-        let output: Vec<u8, 2> = to_vec(&DataEnum::Bap(u8::max_value())).unwrap();
+        let output: Vec<u8, 2> = to_vec(&DataEnum::Bap(u8::MAX)).unwrap();
         assert_eq!(&[0x02, 0xFF], output.deref());
         let out: DataEnum = from_bytes(output.deref()).unwrap();
-        assert_eq!(out, DataEnum::Bap(u8::max_value()));
+        assert_eq!(out, DataEnum::Bap(u8::MAX));
 
         let output: Vec<u8, 8> = to_vec(&DataEnum::Kim(EnumStruct {
             eight: 0xF0,
@@ -374,6 +1057,25 @@ mod test_heapless {
         assert_eq!(out, (1u8, 10u32, "Hello!"));
     }
 
+    #[test]
+    fn mixed_ownership_tuples() {
+        let owned: Vec<u8, 8> = Vec::from_slice(&[0x01, 0x02, 0x03]).unwrap();
+        let output: Vec<u8, 128> = to_vec(&(300u32, "Hi!", &owned)).unwrap();
+        assert_eq!(
+            &[0xAC, 0x02, 0x03, b'H', b'i', b'!', 0x03, 0x01, 0x02, 0x03],
+            output.deref()
+        );
+
+        let (num, text, bytes): (u32, &str, Vec<u8, 8>) = from_bytes(output.deref()).unwrap();
+        assert_eq!((num, text, &bytes), (300, "Hi!", &owned));
+
+        // The str borrows from the input, even though an owned element follows it
+        assert_eq!(text.as_ptr(), output[3..].as_ptr());
+        // ...while the Vec holds its own copy
+        let input_range = output.as_ptr_range();
+        assert!(!input_range.contains(&bytes.as_ptr()));
+    }
+
     #[derive(Debug, Eq, PartialEq)]
     pub struct ByteSliceStruct<'a> {
         bytes: &'a [u8],
@@ -533,25 +1235,137 @@ mod test_heapless {
     }
 
     #[test]
-    fn cobs_test() {
-        let message = "hElLo";
-        let bytes = [0x01, 0x00, 0x02, 0x20];
-        let input = RefStruct {
-            bytes: &bytes,
-            str_s: message,
-        };
+    fn heapless_index_map() {
+        let mut input: FnvIndexMap<u8, u16, 8> = FnvIndexMap::new();
+        for (k, v) in [(1, 10), (2, 300), (30, 0), (4, 0xFFFF)] {
+            input.insert(k, v).unwrap();
+        }
+        let output: Vec<u8, 32> = to_vec(&input).unwrap();
+        assert_eq!(
+            &[0x04, 0x01, 0x0A, 0x02, 0xAC, 0x02, 0x1E, 0x00, 0x04, 0xFF, 0xFF, 0x03],
+            output.deref()
+        );
+        let out: FnvIndexMap<u8, u16, 8> = from_bytes(output.deref()).unwrap();
+        assert_eq!(input, out);
+        assert_eq!(out.keys().copied().collect::<Vec<u8, 8>>(), [1, 2, 30, 4]);
 
-        let output: Vec<u8, 11> = to_vec(&input).unwrap();
+        // A map with more entries than the capacity is rejected, like a `heapless::Vec`
+        let res = from_bytes::<FnvIndexMap<u8, u16, 2>>(output.deref());
+        assert_eq!(res, Err(Error::SerdeDeCustom));
+        let res = from_bytes::<Vec<u8, 2>>(&[0x03, 0x01, 0x02, 0x03]);
+        assert_eq!(res, Err(Error::SerdeDeCustom));
+    }
 
-        let mut encode_buf = [0u8; 32];
-        let sz = cobs::encode(output.deref(), &mut encode_buf);
-        let out = from_bytes_cobs::<RefStruct>(&mut encode_buf[..sz]).unwrap();
+    #[test]
+    fn heapless_index_map_keys() {
+        // Maps are fully supported without `alloc`, with any key type, so there is no
+        // need to restrict keys to integers, e.g. for register maps
+        let mut registers: FnvIndexMap<u16, u32, 4> = FnvIndexMap::new();
+        registers.insert(0x0100, 0xDEAD_BEEF).unwrap();
+        registers.insert(0x7F, 1).unwrap();
+        let output: Vec<u8, 32> = to_vec(&registers).unwrap();
+        assert_eq!(
+            &[0x02, 0x80, 0x02, 0xEF, 0xFD, 0xB6, 0xF5, 0x0D, 0x7F, 0x01],
+            output.deref()
+        );
+        let out: FnvIndexMap<u16, u32, 4> = from_bytes(output.deref()).unwrap();
+        assert_eq!(out, registers);
+
+        // Keys may be owned strings...
+        let mut named: FnvIndexMap<String<8>, u8, 4> = FnvIndexMap::new();
+        named.insert(String::from("gain"), 3).unwrap();
+        named.insert(String::from("mode"), 1).unwrap();
+        let output: Vec<u8, 32> = to_vec(&named).unwrap();
+        assert_eq!(
+            &[0x02, 0x04, b'g', b'a', b'i', b'n', 0x03, 0x04, b'm', b'o', b'd', b'e', 0x01],
+            output.deref()
+        );
+        let out: FnvIndexMap<String<8>, u8, 4> = from_bytes(output.deref()).unwrap();
+        assert_eq!(out, named);
 
-        assert_eq!(input, out);
+        // ...or borrowed from the input
+        let out: FnvIndexMap<&str, u8, 4> = from_bytes(output.deref()).unwrap();
+        assert_eq!(out.get("mode"), Some(&1));
+        assert_eq!(out.keys().next().unwrap().as_ptr(), output[2..].as_ptr());
     }
 
     #[test]
-    fn take_from_includes_terminator() {
+    fn map_size_hint() {
+        use serde::de::{MapAccess, Visitor};
+
+        const ENTRIES: u16 = 1000;
+
+        struct ManyEntries;
+
+        impl Serialize for ManyEntries {
+            fn serialize<S: Serializer>(
+                &self,
+                serializer: S,
+            ) -> core::result::Result<S::Ok, S::Error> {
+                serializer.collect_map((0..ENTRIES).map(|i| (i, u32::from(i) * 3)))
+            }
+        }
+
+        // Checks that entries are handed out one at a time, with an accurate
+        // hint of how many are left, rather than being buffered up front.
+        struct Probe;
+
+        impl<'de> Deserialize<'de> for Probe {
+            fn deserialize<D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> core::result::Result<Self, D::Error> {
+                deserializer.deserialize_map(Probe)
+            }
+        }
+
+        impl<'de> Visitor<'de> for Probe {
+            type Value = Probe;
+
+            fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+                formatter.write_str("a map")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(
+                self,
+                mut map: A,
+            ) -> core::result::Result<Probe, A::Error> {
+                assert_eq!(map.size_hint(), Some(usize::from(ENTRIES)));
+                let mut expected = 0;
+                while let Some((k, v)) = map.next_entry::<u16, u32>()? {
+                    assert_eq!(k, expected);
+                    assert_eq!(v, u32::from(expected) * 3);
+                    expected += 1;
+                    assert_eq!(map.size_hint(), Some(usize::from(ENTRIES - expected)));
+                }
+                assert_eq!(expected, ENTRIES);
+                Ok(Probe)
+            }
+        }
+
+        let output: Vec<u8, 8192> = to_vec(&ManyEntries).unwrap();
+        from_bytes::<Probe>(output.deref()).unwrap();
+    }
+
+    #[test]
+    fn cobs_test() {
+        let message = "hElLo";
+        let bytes = [0x01, 0x00, 0x02, 0x20];
+        let input = RefStruct {
+            bytes: &bytes,
+            str_s: message,
+        };
+
+        let output: Vec<u8, 11> = to_vec(&input).unwrap();
+
+        let mut encode_buf = [0u8; 32];
+        let sz = cobs::encode(output.deref(), &mut encode_buf);
+        let out = from_bytes_cobs::<RefStruct>(&mut encode_buf[..sz]).unwrap();
+
+        assert_eq!(input, out);
+    }
+
+    #[test]
+    fn take_from_includes_terminator() {
         // With the null terminator
         let mut output: Vec<u8, 32> = to_vec_cobs(&(4i32, 0u8, 4u64)).unwrap();
         let (val, remain) = take_from_bytes_cobs::<(i32, u8, u64)>(&mut output).unwrap();
@@ -565,4 +1379,1238 @@ mod test_heapless {
         assert_eq!((4, 0, 4), val);
         assert_eq!(remain.len(), 0);
     }
+
+    #[test]
+    fn typed() {
+        const READING_ID: u16 = 0x0200;
+
+        let input = DataEnum::Bim(u64::MAX);
+        let output: Vec<u8, 32> = to_vec(&(READING_ID, &input)).unwrap();
+
+        let out = from_bytes_typed::<DataEnum>(output.deref(), READING_ID).unwrap();
+        assert_eq!(input, out);
+
+        for wrong_id in [0, READING_ID - 1, READING_ID + 1, u16::MAX] {
+            let res = from_bytes_typed::<DataEnum>(output.deref(), wrong_id);
+            assert_eq!(res, Err(Error::DeserializeWrongType));
+        }
+
+        // A missing type ID is still an unexpected end
+        let res = from_bytes_typed::<DataEnum>(&[], READING_ID);
+        assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+    }
+
+    #[test]
+    fn remaining() {
+        let input = [0x01, 0xAC, 0x02, 0x02, b'H', b'i', 0xFF];
+        let mut deserializer = crate::Deserializer::from_bytes(&input);
+        assert_eq!(deserializer.remaining(), 7);
+
+        assert!(bool::deserialize(&mut deserializer).unwrap());
+        assert_eq!(deserializer.remaining(), 6);
+
+        assert_eq!(u32::deserialize(&mut deserializer).unwrap(), 300);
+        assert_eq!(deserializer.remaining(), 4);
+
+        assert_eq!(<&str>::deserialize(&mut deserializer).unwrap(), "Hi");
+        assert_eq!(deserializer.remaining(), 1);
+
+        assert_eq!(u8::deserialize(&mut deserializer).unwrap(), 0xFF);
+        assert_eq!(deserializer.remaining(), 0);
+
+        // A failed read does not consume anything
+        assert!(u8::deserialize(&mut deserializer).is_err());
+        assert_eq!(deserializer.remaining(), 0);
+        assert!(deserializer.finalize().unwrap().is_empty());
+    }
+
+    #[test]
+    fn borrowed_enum_variants() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+        enum Msg<'a> {
+            Text(&'a str),
+            Blob(&'a [u8]),
+            Pair { name: &'a str, data: &'a [u8] },
+        }
+
+        let output: Vec<u8, 32> = to_vec(&Msg::Text("hello")).unwrap();
+        let Msg::Text(text) = from_bytes::<Msg<'_>>(output.deref()).unwrap() else {
+            panic!("wrong variant");
+        };
+        assert_eq!(text, "hello");
+        // The payload borrows from the input: after the discriminant and length
+        assert_eq!(text.as_ptr(), output[2..].as_ptr());
+
+        let output: Vec<u8, 32> = to_vec(&Msg::Blob(&[0x01, 0x00, 0xFF])).unwrap();
+        let Msg::Blob(blob) = from_bytes::<Msg<'_>>(output.deref()).unwrap() else {
+            panic!("wrong variant");
+        };
+        assert_eq!(blob, &[0x01, 0x00, 0xFF]);
+        assert_eq!(blob.as_ptr(), output[2..].as_ptr());
+
+        let input = Msg::Pair {
+            name: "ab",
+            data: &[0x10],
+        };
+        let output: Vec<u8, 32> = to_vec(&input).unwrap();
+        let out = from_bytes::<Msg<'_>>(output.deref()).unwrap();
+        assert_eq!(out, input);
+        let Msg::Pair { name, data } = out else {
+            panic!("wrong variant");
+        };
+        assert_eq!(name.as_ptr(), output[2..].as_ptr());
+        assert_eq!(data.as_ptr(), output[5..].as_ptr());
+    }
+
+    #[test]
+    fn until_zero() {
+        // A delimited frame, followed by more data
+        let mut output: Vec<u8, 32> = to_vec(&(1u8, 300u32)).unwrap();
+        output.extend_from_slice(&[0x00, 0x05, 0x00]).unwrap();
+        let (frame, remain) = take_until_zero(&output).unwrap();
+        assert_eq!(frame, &[0x01, 0xAC, 0x02]);
+        assert_eq!(from_bytes::<(u8, u32)>(frame).unwrap(), (1, 300));
+
+        let (frame, remain) = take_until_zero(remain).unwrap();
+        assert_eq!(frame, &[0x05]);
+        assert!(remain.is_empty());
+
+        // An empty frame
+        let (frame, remain) = take_until_zero(&[0x00, 0x01]).unwrap();
+        assert!(frame.is_empty());
+        assert_eq!(remain, &[0x01]);
+
+        // No delimiter, the frame is not complete yet
+        let res = take_until_zero(&[0x01, 0x02]);
+        assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+        let res = take_until_zero(&[]);
+        assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+    }
+
+    #[test]
+    fn named_enums() {
+        let input = DataEnum::Chi { a: 0x20, b: 300 };
+        let output: Vec<u8, 32> = crate::to_vec_named(&input).unwrap();
+        assert_eq!(&[0x03, b'C', b'h', b'i', 0x20, 0xAC, 0x02], output.deref());
+
+        let out: DataEnum = from_bytes_named(output.deref()).unwrap();
+        assert_eq!(input, out);
+
+        // Unknown variant names are rejected
+        let res = from_bytes_named::<DataEnum>(&[0x03, b'C', b'h', b'o', 0x20, 0xAC, 0x02]);
+        assert_eq!(res, Err(Error::DeserializeBadEnum));
+    }
+
+    #[test]
+    fn cobs_to_scratch() {
+        let bytes = [0x01, 0x00, 0x02, 0x20];
+        let input = RefStruct {
+            bytes: &bytes,
+            str_s: "hElLo",
+        };
+
+        let encoded: Vec<u8, 32> = crate::to_vec_cobs(&input).unwrap();
+        let original = encoded.clone();
+
+        let mut scratch = [0u8; 32];
+        let out = from_bytes_cobs_to::<RefStruct>(&encoded, &mut scratch).unwrap();
+        assert_eq!(input, out);
+        assert_eq!(encoded, original);
+
+        // Without the terminator
+        let mut scratch = [0u8; 32];
+        let out = from_bytes_cobs_to::<RefStruct>(&encoded[..encoded.len() - 1], &mut scratch);
+        assert_eq!(out, Ok(input));
+
+        // The decoded message is 11 bytes
+        let mut scratch = [0u8; 10];
+        let res = from_bytes_cobs_to::<RefStruct>(&encoded, &mut scratch);
+        assert_eq!(res, Err(Error::DeserializeBufferFull));
+        assert_eq!(encoded, original);
+
+        let mut scratch = [0u8; 11];
+        assert!(from_bytes_cobs_to::<RefStruct>(&encoded, &mut scratch).is_ok());
+    }
+
+    #[test]
+    fn error_context() {
+        #[derive(Deserialize, Debug)]
+        #[allow(dead_code)]
+        struct Status {
+            id: u32,
+            name: [u8; 6],
+            ok: bool,
+            tail: u64,
+        }
+
+        let input = [
+            0x01, b'a', b'b', b'c', b'd', b'e', b'f', 0x07, 0x10, 0x11, 0x12, 0x13, 0x14,
+        ];
+        let mut deserializer = crate::Deserializer::from_bytes(&input);
+        let res = Status::deserialize(&mut deserializer);
+        assert_eq!(res.unwrap_err(), Error::DeserializeBadBool);
+
+        // The offending byte is the last of the consumed bytes
+        let context = deserializer.error_context();
+        assert_eq!(context, &[b'd', b'e', b'f', 0x07, 0x10, 0x11, 0x12, 0x13]);
+        assert_eq!(context[3], 0x07);
+
+        // Near the start and end of the input, the context is shorter
+        let mut deserializer = crate::Deserializer::from_bytes(&[0x02]);
+        assert_eq!(
+            bool::deserialize(&mut deserializer),
+            Err(Error::DeserializeBadBool)
+        );
+        assert_eq!(deserializer.error_context(), &[0x02]);
+    }
+
+    #[test]
+    fn progress() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        static CONSUMED: AtomicUsize = AtomicUsize::new(0);
+
+        let input: Vec<u16, 100> = (0..100).collect();
+        let output: Vec<u8, 256> = to_vec(&input).unwrap();
+
+        let mut deserializer = crate::Deserializer::from_bytes(&output).with_progress(|consumed| {
+            CALLS.fetch_add(1, Ordering::Relaxed);
+            CONSUMED.fetch_max(consumed, Ordering::Relaxed);
+        });
+        let out = Vec::<u16, 100>::deserialize(&mut deserializer).unwrap();
+        assert_eq!(out, input);
+        assert_eq!(CALLS.load(Ordering::Relaxed), 100);
+        assert_eq!(CONSUMED.load(Ordering::Relaxed), output.len());
+
+        // Without a callback, nothing is reported
+        let out: Vec<u16, 100> = from_bytes(&output).unwrap();
+        assert_eq!(out, input);
+        assert_eq!(CALLS.load(Ordering::Relaxed), 100);
+    }
+
+    #[test]
+    fn max_str_len() {
+        let input: &[u8] = b"\x09too long!";
+        let mut de = crate::Deserializer::from_bytes(input).with_max_str_len(8);
+        assert_eq!(
+            <&str>::deserialize(&mut de),
+            Err(Error::DeserializeStringTooLong)
+        );
+
+        let input: &[u8] = b"\x08just fit";
+        let mut de = crate::Deserializer::from_bytes(input).with_max_str_len(8);
+        assert_eq!(<&str>::deserialize(&mut de), Ok("just fit"));
+
+        // Byte slices are limited as well, and nothing is taken beyond the length
+        let input: &[u8] = &[0x09, 0, 1, 2, 3, 4, 5, 6, 7, 8];
+        let mut de = crate::Deserializer::from_bytes(input).with_max_str_len(8);
+        assert_eq!(
+            <&[u8]>::deserialize(&mut de),
+            Err(Error::DeserializeStringTooLong)
+        );
+        assert_eq!(de.remaining(), 9);
+
+        // A huge length is rejected without attempting to take it
+        let input: &[u8] = &[0xFF, 0xFF, 0xFF, 0xFF, 0x0F];
+        let mut de = crate::Deserializer::from_bytes(input).with_max_str_len(8);
+        assert_eq!(
+            <&str>::deserialize(&mut de),
+            Err(Error::DeserializeStringTooLong)
+        );
+    }
+
+    #[test]
+    fn finite_floats() {
+        let nan = f32::from_bits(0x7FC0_0001).to_le_bytes();
+        let inf = f64::INFINITY.to_le_bytes();
+        let neg_inf = f32::NEG_INFINITY.to_le_bytes();
+
+        let strict = |input| crate::Deserializer::from_bytes(input).with_finite_floats(true);
+        assert_eq!(
+            f32::deserialize(&mut strict(&nan)),
+            Err(Error::DeserializeNonFiniteFloat)
+        );
+        assert_eq!(
+            f64::deserialize(&mut strict(&inf)),
+            Err(Error::DeserializeNonFiniteFloat)
+        );
+        assert_eq!(
+            f32::deserialize(&mut strict(&neg_inf)),
+            Err(Error::DeserializeNonFiniteFloat)
+        );
+        let max = f64::MAX.to_le_bytes();
+        assert_eq!(f64::deserialize(&mut strict(&max)), Ok(f64::MAX));
+
+        // Accepted by default
+        assert!(from_bytes::<f32>(&nan).unwrap().is_nan());
+        assert_eq!(from_bytes::<f64>(&inf), Ok(f64::INFINITY));
+        assert_eq!(from_bytes::<f32>(&neg_inf), Ok(f32::NEG_INFINITY));
+    }
+
+    #[test]
+    fn deserialize_sub() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Envelope<'a> {
+            kind: u8,
+            payload: &'a [u8],
+            seq: u16,
+        }
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Temperature {
+            sensor: u8,
+            millis: i32,
+        }
+
+        let inner: Vec<u8, 16> = to_vec(&Temperature {
+            sensor: 4,
+            millis: -21_500,
+        })
+        .unwrap();
+        let outer: Vec<u8, 32> = to_vec(&Envelope {
+            kind: 1,
+            payload: &inner,
+            seq: 300,
+        })
+        .unwrap();
+
+        let mut de = crate::Deserializer::from_bytes(&outer);
+        assert_eq!(u8::deserialize(&mut de), Ok(1));
+        assert_eq!(
+            de.deserialize_sub::<Temperature>(),
+            Ok(Temperature {
+                sensor: 4,
+                millis: -21_500
+            })
+        );
+        assert_eq!(u16::deserialize(&mut de), Ok(300));
+        assert_eq!(de.remaining(), 0);
+
+        // A sub-message can't read into the fields following it...
+        let mut de = crate::Deserializer::from_bytes(&outer);
+        assert_eq!(u8::deserialize(&mut de), Ok(1));
+        assert_eq!(
+            de.deserialize_sub::<(Temperature, u8)>(),
+            Err(Error::DeserializeUnexpectedEnd)
+        );
+
+        // ...and must use all of its bytes
+        let mut de = crate::Deserializer::from_bytes(&outer);
+        assert_eq!(u8::deserialize(&mut de), Ok(1));
+        assert_eq!(
+            de.deserialize_sub::<u8>(),
+            Err(Error::DeserializeTrailingBytes)
+        );
+
+        // The limits of the outer message apply to the sub-message too
+        let input: &[u8] = b"\x09\x08abcdefgh";
+        let mut de = crate::Deserializer::from_bytes(input).with_max_str_len(2);
+        assert_eq!(
+            de.deserialize_sub::<&str>(),
+            Err(Error::DeserializeStringTooLong)
+        );
+        let mut input: Vec<u8, 8> = Vec::new();
+        input.push(4).unwrap();
+        input.extend_from_slice(&f32::NAN.to_le_bytes()).unwrap();
+        let mut de = crate::Deserializer::from_bytes(&input).with_finite_floats(true);
+        assert_eq!(
+            de.deserialize_sub::<f32>(),
+            Err(Error::DeserializeNonFiniteFloat)
+        );
+
+        // The position of a failure inside the sub-message is kept
+        let input: &[u8] = &[0x02, 0x01, 0x02];
+        let mut de = crate::Deserializer::from_bytes(input);
+        assert_eq!(
+            de.deserialize_sub::<(u8, u8, u8)>(),
+            Err(Error::DeserializeUnexpectedEnd)
+        );
+        assert_eq!(
+            de.breadcrumb(),
+            Some(crate::Breadcrumb { index: 2, len: 3 })
+        );
+    }
+
+    #[test]
+    fn split_off() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Temperature {
+            sensor: u8,
+            millis: i32,
+        }
+
+        let inner: Vec<u8, 16> = to_vec(&Temperature {
+            sensor: 4,
+            millis: -21_500,
+        })
+        .unwrap();
+        let mut input: Vec<u8, 32> = Vec::new();
+        input.push(inner.len() as u8).unwrap();
+        input.extend_from_slice(&inner).unwrap();
+        input.extend_from_slice(&[0xAC, 0x02]).unwrap();
+
+        let mut de = crate::Deserializer::from_bytes(&input);
+        let len = u8::deserialize(&mut de).unwrap();
+        let mut sub = de.split_off(len.into()).unwrap();
+        assert_eq!(
+            Temperature::deserialize(&mut sub),
+            Ok(Temperature {
+                sensor: 4,
+                millis: -21_500
+            })
+        );
+        assert_eq!(sub.remaining(), 0);
+        assert_eq!(u16::deserialize(&mut de), Ok(300));
+        assert_eq!(de.remaining(), 0);
+
+        // The sub-message can't read past its frame, while the parent carries on
+        let mut de = crate::Deserializer::from_bytes(&input);
+        let len = u8::deserialize(&mut de).unwrap();
+        let mut sub = de.split_off(len.into()).unwrap();
+        assert_eq!(
+            <(Temperature, u8)>::deserialize(&mut sub),
+            Err(Error::DeserializeUnexpectedEnd)
+        );
+        assert_eq!(u16::deserialize(&mut de), Ok(300));
+
+        // A frame longer than the input is rejected
+        let mut de = crate::Deserializer::from_bytes(&input);
+        assert!(matches!(
+            de.split_off(input.len() + 1),
+            Err(Error::DeserializeUnexpectedEnd)
+        ));
+
+        // The split off Deserializer inherits the limits of its parent
+        let input: &[u8] = b"\x09too long!";
+        let mut de = crate::Deserializer::from_bytes(input).with_max_str_len(2);
+        let mut sub = de.split_off(input.len()).unwrap();
+        assert_eq!(
+            <&str>::deserialize(&mut sub),
+            Err(Error::DeserializeStringTooLong)
+        );
+        let nan = f64::NAN.to_le_bytes();
+        let mut de = crate::Deserializer::from_bytes(&nan).with_finite_floats(true);
+        let mut sub = de.split_off(nan.len()).unwrap();
+        assert_eq!(
+            f64::deserialize(&mut sub),
+            Err(Error::DeserializeNonFiniteFloat)
+        );
+    }
+
+    #[test]
+    fn element_budget() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Batch {
+            id: u8,
+            rows: Vec<Vec<u8, 4>, 4>,
+            tags: FnvIndexMap<u8, u8, 4>,
+        }
+
+        let mut rows = Vec::new();
+        for row in [[1, 2, 3], [4, 5, 6], [7, 8, 9]] {
+            rows.push(Vec::from_slice(&row).unwrap()).unwrap();
+        }
+        let mut tags = FnvIndexMap::new();
+        tags.insert(1, 2).unwrap();
+        let input = Batch { id: 1, rows, tags };
+        let bytes: Vec<u8, 32> = to_vec(&input).unwrap();
+
+        // 3 fields, 3 rows of 3 bytes, and 1 map entry
+        let total = 3 + 3 + 3 * 3 + 1;
+        let mut de = crate::Deserializer::from_bytes(&bytes).with_element_budget(total);
+        assert_eq!(Batch::deserialize(&mut de), Ok(input));
+
+        // Each row is within any per-collection limit, but the message as a whole is not
+        for budget in 0..total {
+            let mut de = crate::Deserializer::from_bytes(&bytes).with_element_budget(budget);
+            assert_eq!(
+                Batch::deserialize(&mut de),
+                Err(Error::DeserializeBudgetExceeded)
+            );
+        }
+
+        // Sub-messages share the budget of their parent
+        let mut framed: Vec<u8, 8> = Vec::new();
+        framed
+            .extend_from_slice(&[0x03, 0x02, 0x0A, 0x0B, 0x0C])
+            .unwrap();
+        let mut de = crate::Deserializer::from_bytes(&framed).with_element_budget(3);
+        assert_eq!(
+            de.deserialize_sub::<Vec<u8, 2>>(),
+            Ok(Vec::from_slice(&[0x0A, 0x0B]).unwrap())
+        );
+        assert_eq!(u8::deserialize(&mut de), Ok(0x0C));
+        let mut de = crate::Deserializer::from_bytes(&framed).with_element_budget(1);
+        assert_eq!(
+            de.deserialize_sub::<Vec<u8, 2>>(),
+            Err(Error::DeserializeBudgetExceeded)
+        );
+    }
+
+    #[test]
+    fn take_rest() {
+        let input: &[u8] = &[0xAC, 0x02, 0x10, 0x20, 0x30];
+        let mut de = crate::Deserializer::from_bytes(input);
+        assert_eq!(u16::deserialize(&mut de), Ok(300));
+
+        let rest = de.take_rest();
+        assert_eq!(rest, &[0x10, 0x20, 0x30]);
+        assert_eq!(rest.as_ptr(), input[2..].as_ptr());
+        assert_eq!(de.remaining(), 0);
+        assert!(de.take_rest().is_empty());
+        assert!(de.finalize().unwrap().is_empty());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn batch() {
+        let mut log = [0u8; 64];
+        let good: Vec<u8, 16> = to_vec(&RefStruct {
+            bytes: &[0x01, 0x02],
+            str_s: "ok",
+        })
+        .unwrap();
+        let frames: [&[u8]; 5] = [
+            &good,
+            &good[..3],
+            &[0x01, 0x01, 0x02, 0xC3, 0x28],
+            &[0x00, 0x00],
+            &good,
+        ];
+        log[..good.len()].copy_from_slice(&good);
+
+        let results = crate::from_bytes_batch::<RefStruct<'_>, _>(frames);
+        assert_eq!(results.len(), 5);
+        let pattern: alloc::vec::Vec<bool> = results.iter().map(Result::is_ok).collect();
+        assert_eq!(pattern, [true, false, false, true, true]);
+        assert_eq!(results[1], Err(Error::DeserializeUnexpectedEnd));
+        assert_eq!(results[2], Err(Error::DeserializeBadUtf8));
+        assert_eq!(
+            results[3],
+            Ok(RefStruct {
+                bytes: &[],
+                str_s: ""
+            })
+        );
+        assert_eq!(results[4], from_bytes(&log[..good.len()]));
+    }
+
+    #[test]
+    fn tuple_breadcrumb() {
+        use crate::{Breadcrumb, Config, IntEncoding};
+
+        // Three fixed size `u32`s need 12 bytes, so the third one runs out
+        let input = [0u8; 9];
+        let config = Config::new().int_encoding(IntEncoding::Fixed);
+        let mut de = crate::Deserializer::from_bytes(&input).with_config(config);
+        let res = <(u32, u32, u32)>::deserialize(&mut de);
+        assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+        let crumb = de.breadcrumb().unwrap();
+        assert_eq!(crumb, Breadcrumb { index: 2, len: 3 });
+
+        let mut text: String<64> = String::new();
+        write!(&mut text, "{}", crumb).unwrap();
+        assert_eq!(text, "while reading element 2 of 3");
+
+        // The innermost element is recorded
+        let mut de = crate::Deserializer::from_bytes(&[0x05, 0x01, 0x02]);
+        let res = <(u8, (u8, u8, u8))>::deserialize(&mut de);
+        assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+        assert_eq!(de.breadcrumb(), Some(Breadcrumb { index: 2, len: 3 }));
+
+        // Nothing is recorded on success
+        let mut de = crate::Deserializer::from_bytes(&[0x01, 0x02, 0x03]);
+        assert!(<(u8, u8, u8)>::deserialize(&mut de).is_ok());
+        assert_eq!(de.breadcrumb(), None);
+    }
+
+    #[test]
+    fn array_breadcrumb() {
+        use crate::{Breadcrumb, Config, IntEncoding};
+
+        // Three and a half fixed size `u32`s
+        let input = [0xAAu8; 14];
+        let config = Config::new().int_encoding(IntEncoding::Fixed);
+        let mut de = crate::Deserializer::from_bytes(&input).with_config(config);
+        let res = <[u32; 4]>::deserialize(&mut de);
+        assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+        // Elements 0 to 2 were read, the element at index 3 was not
+        let crumb = de.breadcrumb().unwrap();
+        assert_eq!(crumb, Breadcrumb { index: 3, len: 4 });
+
+        let mut text: String<64> = String::new();
+        write!(&mut text, "{}", crumb).unwrap();
+        assert_eq!(text, "while reading element 3 of 4");
+
+        // The same with varints, where the last one doesn't terminate
+        let input = [0x01, 0x02, 0x03, 0x80, 0x80];
+        let mut de = crate::Deserializer::from_bytes(&input);
+        let res = <[u32; 4]>::deserialize(&mut de);
+        assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+        assert_eq!(de.breadcrumb(), Some(Breadcrumb { index: 3, len: 4 }));
+    }
+
+    #[test]
+    fn pkcs7_bad_padding() {
+        let from = |s: &[u8]| from_bytes_padded::<u8, 4>(s);
+        assert_eq!(from(&[0x07, 0x03, 0x03, 0x03]), Ok(7));
+        assert_eq!(
+            from(&[0x07, 0x03, 0x03, 0x03, 0x04, 0x04, 0x04, 0x04]),
+            Ok(7)
+        );
+
+        // Not a multiple of the block size, or empty
+        assert_eq!(
+            from(&[0x07, 0x02, 0x02]),
+            Err(Error::DeserializeBadEncoding)
+        );
+        assert_eq!(from(&[]), Err(Error::DeserializeBadEncoding));
+        // Padding bytes that are zero, too large, or inconsistent
+        assert_eq!(
+            from(&[0x07, 0x00, 0x00, 0x00]),
+            Err(Error::DeserializeBadEncoding)
+        );
+        assert_eq!(
+            from(&[0x05, 0x05, 0x05, 0x05]),
+            Err(Error::DeserializeBadEncoding)
+        );
+        assert_eq!(
+            from(&[0x07, 0x03, 0x02, 0x03]),
+            Err(Error::DeserializeBadEncoding)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn owned() {
+        use alloc::string::String;
+
+        #[derive(Deserialize, Debug, PartialEq)]
+        struct Owned {
+            id: u8,
+            name: String,
+        }
+
+        fn decode<T: serde::de::DeserializeOwned>(frame: &[u8]) -> Result<T> {
+            // The frame is a temporary copy, which the result can't borrow from
+            let copy: Vec<u8, 16> = Vec::from_slice(frame).unwrap();
+            from_bytes_owned(&copy)
+        }
+
+        let out: Owned = decode(&[0x07, 0x02, b'o', b'k']).unwrap();
+        assert_eq!(
+            out,
+            Owned {
+                id: 7,
+                name: String::from("ok")
+            }
+        );
+        assert_eq!(
+            decode::<Owned>(&[0x07, 0x02, b'o']),
+            Err(Error::DeserializeUnexpectedEnd)
+        );
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn owned_outlives_input() {
+        use alloc::{borrow::Cow, string::String, vec::Vec as AllocVec};
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Entry<'a> {
+            key: Cow<'a, str>,
+            tags: AllocVec<String>,
+            blob: AllocVec<u8>,
+        }
+
+        fn load(frame: &[u8]) -> Entry<'static> {
+            // The result outlives this temporary copy of the frame
+            let copy = AllocVec::from(frame);
+            let out = from_bytes_owned(&copy).unwrap();
+            drop(copy);
+            out
+        }
+
+        let input = Entry {
+            key: Cow::Borrowed("fw"),
+            tags: alloc::vec![String::from("a"), String::from("bc")],
+            blob: alloc::vec![0xFF, 0x00],
+        };
+        let frame: Vec<u8, 32> = to_vec(&input).unwrap();
+        let out = load(&frame);
+        assert_eq!(out, input);
+        assert!(matches!(out.key, Cow::Owned(_)));
+    }
+
+    #[test]
+    fn sentinel() {
+        let input: Vec<u8, 16> = to_vec(&(7u8, "hi")).unwrap();
+        let mut frame: Vec<u8, 16> = input.clone();
+        frame.push(0xA5).unwrap();
+        assert_eq!(from_bytes_sentinel(&frame, 0xA5), Ok((7u8, "hi")));
+
+        // Wrong sentinel
+        let res = from_bytes_sentinel::<(u8, &str)>(&frame, 0x5A);
+        assert_eq!(res, Err(Error::DeserializeBadSentinel));
+
+        // Extra bytes after the sentinel
+        frame.push(0xA5).unwrap();
+        let res = from_bytes_sentinel::<(u8, &str)>(&frame, 0xA5);
+        assert_eq!(res, Err(Error::DeserializeTrailingBytes));
+
+        // Missing sentinel
+        let res = from_bytes_sentinel::<(u8, &str)>(&input, 0xA5);
+        assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+    }
+
+    #[cfg(feature = "use-bytemuck")]
+    #[test]
+    fn borrowed_pod() {
+        #[repr(C)]
+        #[derive(Clone, Copy, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+        struct Record {
+            id: u32,
+            flags: u16,
+            level: i16,
+        }
+
+        let records = [
+            Record {
+                id: 0x0102_0304,
+                flags: 0x8000,
+                level: -1,
+            },
+            Record {
+                id: 7,
+                flags: 0,
+                level: 300,
+            },
+        ];
+        let bytes: &[u8] = bytemuck::cast_slice(&records);
+
+        let out: &Record = from_bytes_ref(&bytes[8..]).unwrap();
+        assert_eq!(out, &records[1]);
+        // The record is borrowed in place, rather than copied
+        assert!(core::ptr::eq(out, &records[1]));
+
+        assert_eq!(
+            from_bytes_ref::<Record>(&bytes[..7]).err(),
+            Some(Error::DeserializeUnexpectedEnd)
+        );
+        assert_eq!(
+            from_bytes_ref::<Record>(&bytes[..9]).err(),
+            Some(Error::DeserializeTrailingBytes)
+        );
+        assert_eq!(
+            from_bytes_ref::<Record>(&bytes[2..10]).err(),
+            Some(Error::DeserializeMisaligned)
+        );
+    }
+
+    #[test]
+    fn allowed_variants() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        enum Request<'a> {
+            Read { addr: u32 },
+            Write { addr: u32, data: &'a [u8] },
+            Erase,
+        }
+
+        let read: Vec<u8, 16> = to_vec(&Request::Read { addr: 0x200 }).unwrap();
+        let write: Vec<u8, 16> = to_vec(&Request::Write {
+            addr: 0x200,
+            data: &[1, 2],
+        })
+        .unwrap();
+        let erase: Vec<u8, 16> = to_vec(&Request::Erase).unwrap();
+
+        // A read only endpoint
+        let read_only = [0];
+        assert_eq!(
+            from_bytes_allowed_variants(&read, &read_only),
+            Ok(Request::Read { addr: 0x200 })
+        );
+        for forbidden in [&write, &erase] {
+            assert_eq!(
+                from_bytes_allowed_variants::<Request<'_>>(forbidden, &read_only),
+                Err(Error::DeserializeForbiddenVariant)
+            );
+        }
+
+        // The rest of the message is still checked for allowed variants
+        assert_eq!(
+            from_bytes_allowed_variants::<Request<'_>>(&write[..3], &[0, 1]),
+            Err(Error::DeserializeUnexpectedEnd)
+        );
+        assert_eq!(
+            from_bytes_allowed_variants::<Request<'_>>(&[], &[0, 1]),
+            Err(Error::DeserializeUnexpectedEnd)
+        );
+        assert_eq!(
+            from_bytes_allowed_variants::<Request<'_>>(&[0x00], &[]),
+            Err(Error::DeserializeForbiddenVariant)
+        );
+    }
+
+    #[test]
+    fn footer_len() {
+        use crate::to_vec_footer_len;
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Entry<'a> {
+            seq: u32,
+            text: &'a str,
+        }
+
+        let entries = [
+            Entry {
+                seq: 1,
+                text: "boot",
+            },
+            Entry {
+                seq: 300,
+                text: "ready",
+            },
+        ];
+
+        // An append-only log of two entries
+        let mut log: Vec<u8, 64> = Vec::new();
+        for entry in entries.iter() {
+            let ser: Vec<u8, 32> = to_vec_footer_len(entry).unwrap();
+            let plain: Vec<u8, 32> = to_vec(entry).unwrap();
+            assert_eq!(&ser[..plain.len()], plain.as_slice());
+            assert_eq!(&ser[plain.len()..], &(plain.len() as u32).to_le_bytes());
+            log.extend_from_slice(&ser).unwrap();
+        }
+
+        // ...read backwards
+        let (last, rest) = from_bytes_footer_len::<Entry<'_>>(&log).unwrap();
+        assert_eq!(last, entries[1]);
+        let (first, rest) = from_bytes_footer_len::<Entry<'_>>(rest).unwrap();
+        assert_eq!(first, entries[0]);
+        assert!(rest.is_empty());
+
+        // A footer that doesn't match the message is rejected
+        assert_eq!(
+            from_bytes_footer_len::<u16>(&[0x02, 0xAC, 0x01, 0x00, 0x00, 0x00]),
+            Err(Error::DeserializeUnexpectedEnd)
+        );
+        assert_eq!(
+            from_bytes_footer_len::<u8>(&[0xAC, 0x02, 0x02, 0x00, 0x00, 0x00]),
+            Err(Error::DeserializeTrailingBytes)
+        );
+        assert_eq!(
+            from_bytes_footer_len::<u16>(&[0xAC, 0x02, 0x03, 0x00, 0x00, 0x00]),
+            Err(Error::DeserializeUnexpectedEnd)
+        );
+        assert_eq!(
+            from_bytes_footer_len::<u16>(&[0x02, 0x00, 0x00]),
+            Err(Error::DeserializeUnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn min_version() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Telemetry {
+            version: u8,
+            temp: i16,
+        }
+
+        let mut buf = [0u8; 8];
+        for version in [2, 3, 4] {
+            let msg = Telemetry { version, temp: -40 };
+            let used = crate::to_slice(&msg, &mut buf).unwrap();
+            let res = from_bytes_min_version::<i16>(used, 3);
+            if version < 3 {
+                assert_eq!(res, Err(Error::DeserializeUnsupportedVersion));
+            } else {
+                assert_eq!(res, Ok(-40));
+            }
+        }
+
+        // The rest of the message is still checked
+        assert_eq!(
+            from_bytes_min_version::<i16>(&[0x03], 3),
+            Err(Error::DeserializeUnexpectedEnd)
+        );
+        assert_eq!(
+            from_bytes_min_version::<i16>(&[], 0),
+            Err(Error::DeserializeUnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn interned_strings() {
+        use crate::ser_flavors::Interned;
+        use crate::{serialize_with_flavor, to_vec_interned};
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Report<'a> {
+            unit: &'a str,
+            status: [&'a str; 4],
+            source: &'a str,
+            values: [(&'a str, u8); 3],
+        }
+
+        let input = Report {
+            unit: "celsius",
+            status: ["nominal", "nominal", "degraded", "nominal"],
+            source: "celsius",
+            values: [("nominal", 1), ("degraded", 2), ("nominal", 3)],
+        };
+        let plain: Vec<u8, 128> = to_vec(&input).unwrap();
+        let interned: Vec<u8, 128> = to_vec_interned::<_, 128, 32>(&input).unwrap();
+        assert_eq!(plain.len(), 77);
+        assert_eq!(interned.len(), 37);
+        assert_eq!(from_bytes_interned::<Report<'_>, 32>(&interned), Ok(input));
+
+        // Only the first string fits in a table of one
+        let data = ["a", "b", "a", "b"];
+        let used: Vec<u8, 16> = serialize_with_flavor(&data, Interned::<16, 1>::new()).unwrap();
+        assert_eq!(
+            &used,
+            &[0x00, 0x01, b'a', 0x01, 0x01, b'b', 0x02, 0x01, 0x01, b'b']
+        );
+        let mut de = crate::Deserializer::from_flavor(flavors::Interned::<'_, 1>::new(&used));
+        assert_eq!(<[&str; 4]>::deserialize(&mut de), Ok(data));
+
+        // References to strings that are not in the table
+        let res = from_bytes_interned::<(&str, &str), 32>(&[0x00, 0x01, b'a', 0x03]);
+        assert_eq!(res, Err(Error::DeserializeBadEncoding));
+
+        // Tags that overflow a usize are rejected rather than truncated
+        let res = from_bytes_interned::<&str, 32>(&[0xFF; 10]);
+        assert_eq!(res, Err(Error::DeserializeBadVarint));
+        let mut de = crate::Deserializer::from_flavor(flavors::Interned::<'_, 0>::new(&used));
+        assert_eq!(
+            <[&str; 4]>::deserialize(&mut de),
+            Err(Error::DeserializeBufferFull)
+        );
+
+        // Chars are not interned, even between repeated strings
+        let data = ("ok", 'x', "ok");
+        let interned: Vec<u8, 16> = to_vec_interned::<_, 16, 32>(&data).unwrap();
+        assert_eq!(&interned, &[0x00, 0x02, b'o', b'k', 0x01, b'x', 0x02]);
+        assert_eq!(
+            from_bytes_interned::<(&str, char, &str), 32>(&interned),
+            Ok(data)
+        );
+    }
+
+    #[test]
+    fn framing() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+        struct Payload<'a> {
+            id: u16,
+            name: &'a str,
+        }
+
+        let payload = Payload {
+            id: 0x0100,
+            name: "abc",
+        };
+        let raw: Vec<u8, 32> = to_vec(&payload).unwrap();
+        assert_eq!(&raw, &[0x80, 0x02, 0x03, b'a', b'b', b'c']);
+
+        let mut frame = raw.clone();
+        assert_eq!(from_frame(&mut frame, Framing::Raw), Ok(payload));
+
+        let mut frame: Vec<u8, 32> = to_vec_cobs(&payload).unwrap();
+        assert_eq!(from_frame(&mut frame, Framing::Cobs), Ok(payload));
+
+        let mut frame: Vec<u8, 32> = to_vec(&raw.deref()).unwrap();
+        assert_eq!(frame[0], 6);
+        assert_eq!(from_frame(&mut frame, Framing::LengthPrefixed), Ok(payload));
+        let res = from_frame::<Payload<'_>>(&mut frame[..6], Framing::LengthPrefixed);
+        assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+
+        #[cfg(feature = "use-crc")]
+        {
+            static CRC: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISCSI);
+
+            let mut frame: Vec<u8, 32> = crate::to_vec_crc32(&payload, CRC.digest()).unwrap();
+            assert_eq!(frame.len(), raw.len() + 4);
+            assert_eq!(from_frame(&mut frame, Framing::Crc32(&CRC)), Ok(payload));
+            frame[0] ^= 0x01;
+            let res = from_frame::<Payload<'_>>(&mut frame, Framing::Crc32(&CRC));
+            assert_eq!(res, Err(Error::DeserializeBadCrc));
+        }
+    }
+
+    #[test]
+    fn str_slices() {
+        let input: &[&str] = &["alpha", "beta", "gamma"];
+        let ser: Vec<u8, 32> = to_vec(input).unwrap();
+        assert_eq!(ser[..2], [0x03, 0x05]);
+
+        // Each element borrows from the input, without copying
+        let out: Vec<&str, 4> = from_bytes(&ser).unwrap();
+        assert_eq!(out, input);
+        let mut offset = 2;
+        for s in out.iter() {
+            assert_eq!(s.as_ptr(), ser[offset..].as_ptr());
+            offset += s.len() + 1;
+        }
+        assert_eq!(offset - 1, ser.len());
+
+        #[cfg(feature = "alloc")]
+        {
+            let out: alloc::vec::Vec<&str> = from_bytes(&ser).unwrap();
+            assert_eq!(out, input);
+            assert_eq!(out[2].as_ptr(), ser[ser.len() - 5..].as_ptr());
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn partial_seq() {
+        let input: &[RefStruct<'_>] = &[
+            RefStruct {
+                bytes: &[0x01],
+                str_s: "one",
+            },
+            RefStruct {
+                bytes: &[],
+                str_s: "two",
+            },
+            RefStruct {
+                bytes: &[0x03, 0x03],
+                str_s: "three",
+            },
+        ];
+        let ser: Vec<u8, 32> = to_vec(input).unwrap();
+
+        let (out, err) = from_bytes_partial_seq::<RefStruct<'_>>(&ser);
+        assert_eq!(out, input);
+        assert_eq!(err, None);
+
+        // The last element is truncated
+        let (out, err) = from_bytes_partial_seq::<RefStruct<'_>>(&ser[..ser.len() - 2]);
+        assert_eq!(out, &input[..2]);
+        assert_eq!(err, Some(Error::DeserializeUnexpectedEnd));
+
+        // A corrupt element stops decoding, even if more input follows
+        let (out, err) = from_bytes_partial_seq::<bool>(&[0x03, 0x01, 0x05, 0x00]);
+        assert_eq!(out, [true]);
+        assert_eq!(err, Some(Error::DeserializeBadBool));
+
+        let (out, err) = from_bytes_partial_seq::<bool>(&[]);
+        assert!(out.is_empty());
+        assert_eq!(err, Some(Error::DeserializeUnexpectedEnd));
+    }
+
+    #[test]
+    fn int_overflow() {
+        use crate::{Config, IntOverflow};
+
+        // 70000, and -70000, sent as varint `u32` and `i32`
+        let big = crate::to_vec::<(u32, u8), 8>(&(70000, 0xAB)).unwrap();
+        let small = crate::to_vec::<i32, 8>(&-70000).unwrap();
+
+        let modes = [
+            (IntOverflow::Error, None, None),
+            (IntOverflow::Saturate, Some(u16::MAX), Some(i16::MIN)),
+            (
+                IntOverflow::Wrap,
+                Some(70000u32 as u16),
+                Some(-70000i32 as i16),
+            ),
+        ];
+        for (mode, unsigned, signed) in modes {
+            let config = Config::new().int_overflow(mode);
+            let res = from_bytes_with_config::<(u16, u8)>(&big, config);
+            match unsigned {
+                Some(v) => assert_eq!(res, Ok((v, 0xAB)), "{:?}", mode),
+                None => assert_eq!(res, Err(Error::DeserializeIntegerOverflow)),
+            }
+            let res = from_bytes_with_config::<i16>(&small, config);
+            match signed {
+                Some(v) => assert_eq!(res, Ok(v), "{:?}", mode),
+                None => assert_eq!(res, Err(Error::DeserializeIntegerOverflow)),
+            }
+
+            // Values which fit are unaffected
+            assert_eq!(
+                from_bytes_with_config::<u16>(&[0xFF, 0xFF, 0x03], config),
+                Ok(u16::MAX)
+            );
+            assert_eq!(from_bytes_with_config::<i64>(&[0x03], config), Ok(-2));
+
+            // Varints which are too long for any integer are still malformed
+            let res = from_bytes_with_config::<u16>(&[0xFF; 20], config);
+            assert_eq!(res, Err(Error::DeserializeBadVarint));
+        }
+
+        // Values from a wider varint saturate in both directions
+        let config = Config::new().int_overflow(IntOverflow::Saturate);
+        let wide = crate::to_vec::<i128, 24>(&i128::MAX).unwrap();
+        assert_eq!(from_bytes_with_config::<i64>(&wide, config), Ok(i64::MAX));
+        let wide = crate::to_vec::<u64, 16>(&u64::MAX).unwrap();
+        assert_eq!(from_bytes_with_config::<u32>(&wide, config), Ok(u32::MAX));
+
+        // Lengths are never saturated or wrapped
+        let config = Config::new().int_overflow(IntOverflow::Wrap);
+        let res = from_bytes_with_config::<&[u8]>(&[0xFF; 20], config);
+        assert_eq!(res, Err(Error::DeserializeBadVarint));
+    }
+
+    #[test]
+    fn from_spsc() {
+        use crate::to_spsc;
+        use heapless::spsc::Queue;
+
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Reading {
+            sensor: String<8>,
+            values: Vec<i16, 4>,
+            ok: bool,
+        }
+
+        let first = Reading {
+            sensor: "temp".into(),
+            values: Vec::from_slice(&[-40, 300]).unwrap(),
+            ok: true,
+        };
+        let second = Reading {
+            sensor: "rh".into(),
+            values: Vec::new(),
+            ok: false,
+        };
+
+        let mut queue: Queue<u8, 32> = Queue::new();
+        let (mut producer, mut consumer) = queue.split();
+        to_spsc(&first, &mut producer).unwrap();
+        to_spsc(&second, &mut producer).unwrap();
+
+        // Only the bytes of each message are dequeued
+        let mut scratch = [0u8; 4];
+        assert_eq!(super::from_spsc(&mut consumer, &mut scratch), Ok(first));
+        assert_eq!(super::from_spsc(&mut consumer, &mut scratch), Ok(second));
+        assert_eq!(consumer.len(), 0);
+
+        // Strings must fit in the scratch buffer
+        to_spsc(&"temp", &mut producer).unwrap();
+        let mut scratch = [0u8; 3];
+        assert_eq!(
+            super::from_spsc::<String<8>, 32>(&mut consumer, &mut scratch),
+            Err(Error::DeserializeBufferFull)
+        );
+
+        // A message that is still being received
+        while consumer.dequeue().is_some() {}
+        producer.enqueue(0x01).unwrap();
+        assert_eq!(
+            super::from_spsc::<(u8, u8), 32>(&mut consumer, &mut scratch),
+            Err(Error::DeserializeUnexpectedEnd)
+        );
+    }
+
+    #[test]
+    fn msgpack_ints() {
+        use crate::{to_vec_with_config, Config, IntEncoding};
+
+        let config = Config::new().int_encoding(IntEncoding::MsgPack);
+
+        // Small values are a single byte, where `-32` and `127` are two byte varints
+        let used: Vec<u8, 32> = to_vec_with_config(&(0u16, 127u32, -1i16, -32i64), config).unwrap();
+        assert_eq!(used, &[0x00, 0x7F, 0xFF, 0xE0]);
+        let out = from_bytes_with_config::<(u16, u32, i16, i64)>(&used, config);
+        assert_eq!(out, Ok((0, 127, -1, -32)));
+
+        // Larger values fall back to the smallest big endian integer that fits
+        let cases: [(i128, &[u8]); 10] = [
+            (128, &[0xCC, 0x80]),
+            (300, &[0xCD, 0x01, 0x2C]),
+            (70_000, &[0xCE, 0x00, 0x01, 0x11, 0x70]),
+            (
+                1 << 40,
+                &[0xCF, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00],
+            ),
+            (-33, &[0xD0, 0xDF]),
+            (-300, &[0xD1, 0xFE, 0xD4]),
+            (-70_000, &[0xD2, 0xFF, 0xFE, 0xEE, 0x90]),
+            (i64::MIN.into(), &[0xD3, 0x80, 0, 0, 0, 0, 0, 0, 0]),
+            (
+                1 << 64,
+                &[0xD8, 0x00, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0],
+            ),
+            (
+                i128::MIN,
+                &[
+                    0xD8, 0x01, 0x80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+                ],
+            ),
+        ];
+        for (val, expected) in cases {
+            let used: Vec<u8, 32> = to_vec_with_config(&val, config).unwrap();
+            assert_eq!(used, expected, "{}", val);
+            assert_eq!(from_bytes_with_config::<i128>(&used, config), Ok(val));
+            if let Ok(val) = i64::try_from(val) {
+                let used: Vec<u8, 32> = to_vec_with_config(&val, config).unwrap();
+                assert_eq!(used, expected, "{}", val);
+                assert_eq!(from_bytes_with_config::<i64>(&used, config), Ok(val));
+            }
+        }
+        for val in [0, 127, 128, 255, 256, u64::MAX] {
+            let used: Vec<u8, 32> = to_vec_with_config(&val, config).unwrap();
+            assert_eq!(from_bytes_with_config::<u64>(&used, config), Ok(val));
+            let used: Vec<u8, 32> = to_vec_with_config(&u128::from(val), config).unwrap();
+            assert_eq!(
+                from_bytes_with_config::<u128>(&used, config),
+                Ok(val.into())
+            );
+        }
+        let used: Vec<u8, 32> = to_vec_with_config(&u128::MAX, config).unwrap();
+        assert_eq!(from_bytes_with_config::<u128>(&used, config), Ok(u128::MAX));
+
+        // 128-bit integers keep their sign, and are range checked when read as the other
+        assert_eq!(
+            from_bytes_with_config::<i128>(&used, config),
+            Err(Error::DeserializeIntegerOverflow)
+        );
+        let used: Vec<u8, 32> = to_vec_with_config(&i128::MIN, config).unwrap();
+        assert_eq!(
+            from_bytes_with_config::<u128>(&used, config),
+            Err(Error::DeserializeIntegerOverflow)
+        );
+        let used: Vec<u8, 32> = to_vec_with_config(&i128::MAX, config).unwrap();
+        assert_eq!(
+            from_bytes_with_config::<u128>(&used, config),
+            Ok(i128::MAX as u128)
+        );
+        assert_eq!(from_bytes_with_config::<i128>(&used, config), Ok(i128::MAX));
+        assert_eq!(
+            from_bytes_with_config::<u64>(&used, config),
+            Err(Error::DeserializeIntegerOverflow)
+        );
+        assert_eq!(
+            from_bytes_with_config::<i128>(&[0xD8, 0x02], config),
+            Err(Error::DeserializeBadEncoding)
+        );
+
+        // Single byte integers are unaffected
+        assert_eq!(
+            from_bytes_with_config::<u8>(&[0xCD, 0x01, 0x2C], config),
+            Ok(0xCD)
+        );
+
+        // Values must fit in the type they are deserialized as
+        assert_eq!(
+            from_bytes_with_config::<u16>(&[0xCE, 0x00, 0x01, 0x11, 0x70], config),
+            Err(Error::DeserializeIntegerOverflow)
+        );
+        assert_eq!(
+            from_bytes_with_config::<u32>(&[0xFF], config),
+            Err(Error::DeserializeIntegerOverflow)
+        );
+        assert_eq!(
+            from_bytes_with_config::<i16>(&[0xD8; 18], config),
+            Err(Error::DeserializeIntegerOverflow)
+        );
+        assert_eq!(
+            from_bytes_with_config::<i16>(&[0xC1], config),
+            Err(Error::DeserializeBadEncoding)
+        );
+    }
 }
@@ -0,0 +1,247 @@
+//! # Delta Encoding
+//!
+//! For streams of records where consecutive records differ only slightly, such as
+//! telemetry, a record can be encoded as a delta against the previous one. Only the
+//! fields that changed are sent, preceded by a bitmap of which fields changed.
+//!
+//! The [`postcard_delta!`](crate::postcard_delta) macro implements [`Delta`] for a
+//! struct, which can then be used with [`to_slice_delta()`] and [`from_bytes_delta()`].
+//!
+//! ```rust
+//! use postcard::{from_bytes_delta, postcard_delta, to_slice_delta};
+//!
+//! postcard_delta! {
+//!     #[derive(Clone, Debug, PartialEq)]
+//!     pub struct Sample {
+//!         pub timestamp: u32,
+//!         pub temp: i16,
+//!         pub humidity: u8,
+//!     }
+//! }
+//!
+//! let prev = Sample { timestamp: 1000, temp: -40, humidity: 55 };
+//! let cur = Sample { timestamp: 1001, ..prev.clone() };
+//!
+//! let mut buf = [0u8; 16];
+//! let used = to_slice_delta(&prev, &cur, &mut buf).unwrap();
+//! // The bitmap, followed by the only changed field
+//! assert_eq!(used, &[0x01, 0xE9, 0x07]);
+//!
+//! let out: Sample = from_bytes_delta(&prev, used).unwrap();
+//! assert_eq!(out, cur);
+//! ```
+//!
+//! On the wire, the bitmap is a varint-encoded `u64`, with bit `n` set when field `n`
+//! (in declaration order) changed. The changed fields follow, in order, each encoded as
+//! usual. A delta can only be decoded against the same previous record it was encoded
+//! against, so the first record of a stream is typically sent as a delta against a
+//! default value.
+
+use serde::{Deserialize, Serialize};
+
+use crate::de::deserializer::Deserializer;
+use crate::de::flavors::Flavor as DeFlavor;
+use crate::error::Result;
+use crate::ser::flavors::{Flavor as SerFlavor, Slice};
+use crate::ser::serializer::Serializer;
+
+/// A type that can be encoded as a delta against a previous value. Implemented by
+/// [`postcard_delta!`](crate::postcard_delta).
+pub trait Delta: Sized {
+    /// Serialize the fields of `cur` that differ from `prev`
+    fn serialize_delta<F: SerFlavor>(
+        prev: &Self,
+        cur: &Self,
+        serializer: &mut Serializer<F>,
+    ) -> Result<()>;
+
+    /// Deserialize a value, taking any fields that did not change from `prev`
+    fn deserialize_delta<'de, F: DeFlavor<'de>>(
+        prev: &Self,
+        deserializer: &mut Deserializer<'de, F>,
+    ) -> Result<Self>;
+}
+
+/// Serialize `cur` as a delta against `prev` to the given slice, containing only the
+/// fields that changed.
+///
+/// See the [`delta`](crate::delta) module for an example.
+pub fn to_slice_delta<'a, T>(prev: &T, cur: &T, buf: &'a mut [u8]) -> Result<&'a mut [u8]>
+where
+    T: Delta,
+{
+    let mut serializer = Serializer::from_flavor(Slice::new(buf));
+    T::serialize_delta(prev, cur, &mut serializer)?;
+    serializer.output.finalize()
+}
+
+/// Deserialize a delta from a byte slice, applying it to `prev`. The unused portion
+/// (if any) of the byte slice is not returned.
+///
+/// A bitmap with bits set past the last field of `T` is rejected with
+/// [`Error::DeserializeBadEncoding`](crate::Error::DeserializeBadEncoding).
+pub fn from_bytes_delta<T>(prev: &T, s: &[u8]) -> Result<T>
+where
+    T: Delta,
+{
+    let mut deserializer = Deserializer::from_bytes(s);
+    T::deserialize_delta(prev, &mut deserializer)
+}
+
+#[doc(hidden)]
+pub fn __serialize_field<F: SerFlavor, T: Serialize + ?Sized>(
+    serializer: &mut Serializer<F>,
+    value: &T,
+) -> Result<()> {
+    value.serialize(serializer)
+}
+
+#[doc(hidden)]
+pub fn __deserialize_field<'de, F: DeFlavor<'de>, T: Deserialize<'de>>(
+    deserializer: &mut Deserializer<'de, F>,
+) -> Result<T> {
+    T::deserialize(deserializer)
+}
+
+/// Define a struct, implementing [`Delta`] by comparing each of its fields.
+///
+/// The struct definition is passed through unchanged. Every field must implement
+/// `PartialEq` and `Clone`, as well as `Serialize` and `Deserialize`. Only structs with
+/// at most 64 named fields, and without generics or lifetimes, are supported.
+///
+/// See the [`delta`](crate::delta) module for an example.
+#[macro_export]
+macro_rules! postcard_delta {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$fmeta:meta])*
+                $fvis:vis $field:ident : $ty:ty
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $(
+                $(#[$fmeta])*
+                $fvis $field : $ty,
+            )*
+        }
+
+        impl $crate::delta::Delta for $name {
+            fn serialize_delta<F: $crate::ser_flavors::Flavor>(
+                prev: &Self,
+                cur: &Self,
+                serializer: &mut $crate::Serializer<F>,
+            ) -> $crate::Result<()> {
+                const _: () = assert!(
+                    [$(stringify!($field)),*].len() <= 64,
+                    "postcard_delta! supports at most 64 fields"
+                );
+
+                let mut changed: u64 = 0;
+                let mut bit: u64 = 1;
+                $(
+                    if prev.$field != cur.$field {
+                        changed |= bit;
+                    }
+                    bit <<= 1;
+                )*
+                let _ = bit;
+
+                $crate::delta::__serialize_field(serializer, &changed)?;
+                $(
+                    if prev.$field != cur.$field {
+                        $crate::delta::__serialize_field(serializer, &cur.$field)?;
+                    }
+                )*
+                Ok(())
+            }
+
+            fn deserialize_delta<'de, F: $crate::de_flavors::Flavor<'de>>(
+                prev: &Self,
+                deserializer: &mut $crate::Deserializer<'de, F>,
+            ) -> $crate::Result<Self> {
+                let changed: u64 = $crate::delta::__deserialize_field(deserializer)?;
+                let fields = [$(stringify!($field)),*].len() as u32;
+                if fields < 64 && (changed >> fields) != 0 {
+                    return Err($crate::Error::DeserializeBadEncoding);
+                }
+
+                let mut bit: u64 = 1;
+                $(
+                    let $field = if (changed & bit) != 0 {
+                        $crate::delta::__deserialize_field(deserializer)?
+                    } else {
+                        core::clone::Clone::clone(&prev.$field)
+                    };
+                    bit <<= 1;
+                )*
+                let _ = bit;
+
+                Ok($name { $($field),* })
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{from_bytes_delta, to_slice_delta};
+    use crate::Error;
+
+    postcard_delta! {
+        #[derive(Clone, Debug, Default, PartialEq)]
+        struct Telemetry {
+            seq: u32,
+            voltage: f32,
+            state: Option<u8>,
+            position: (i32, i32),
+        }
+    }
+
+    #[test]
+    fn small_delta() {
+        let mut buf = [0u8; 32];
+        let prev = Telemetry {
+            seq: 41,
+            voltage: 3.3,
+            state: Some(2),
+            position: (-100_000, 250_000),
+        };
+        let cur = Telemetry {
+            seq: 42,
+            state: None,
+            ..prev.clone()
+        };
+
+        let full = crate::to_slice(
+            &(42u32, 3.3f32, None::<u8>, (-100_000i32, 250_000i32)),
+            &mut [0u8; 32],
+        )
+        .unwrap()
+        .len();
+        let used = to_slice_delta(&prev, &cur, &mut buf).unwrap();
+        assert_eq!(used, &[0b0101, 0x2A, 0x00]);
+        assert!(used.len() < full);
+        assert_eq!(from_bytes_delta(&prev, used), Ok(cur.clone()));
+
+        // Nothing changed
+        let used = to_slice_delta(&cur, &cur, &mut buf).unwrap();
+        assert_eq!(used, &[0x00]);
+        assert_eq!(from_bytes_delta(&cur, used), Ok(cur.clone()));
+
+        // Everything changed, against a default starting point
+        let used = to_slice_delta(&Telemetry::default(), &prev, &mut buf).unwrap();
+        assert_eq!(used[0], 0b1111);
+        assert_eq!(from_bytes_delta(&Telemetry::default(), used), Ok(prev));
+    }
+
+    #[test]
+    fn unknown_fields_rejected() {
+        let prev = Telemetry::default();
+        let res = from_bytes_delta(&prev, &[0b1_0000]);
+        assert_eq!(res, Err(Error::DeserializeBadEncoding));
+    }
+}
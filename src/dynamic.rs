@@ -0,0 +1,305 @@
+//! A tagged, self-describing encoding of `serde_json::Value`s.
+//!
+//! Postcard messages are not self-describing, so data without a fixed schema (such as
+//! an arbitrary JSON document) can't be encoded directly. Instead, every value is
+//! encoded as if it was the following enum, so that each value is preceded by a
+//! varint tag describing its type:
+//!
+//! ```rust,ignore
+//! enum Value {
+//!     Null,                        // 0
+//!     Bool(bool),                  // 1
+//!     U64(u64),                    // 2, non-negative integers
+//!     I64(i64),                    // 3, negative integers
+//!     F64(f64),                    // 4, all other numbers
+//!     String(String),              // 5
+//!     Array(Vec<Value>),           // 6
+//!     Object(Map<String, Value>),  // 7
+//! }
+//! ```
+
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use serde::de::{self, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor};
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Number, Value};
+
+use crate::error::Result;
+
+const NAME: &str = "Value";
+const VARIANTS: &[&str] = &[
+    "Null", "Bool", "U64", "I64", "F64", "String", "Array", "Object",
+];
+
+/// Serializes a borrowed `Value` in the tagged encoding
+struct Tagged<'a>(&'a Value);
+
+impl Serialize for Tagged<'_> {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self.0 {
+            Value::Null => serializer.serialize_unit_variant(NAME, 0, VARIANTS[0]),
+            Value::Bool(b) => serializer.serialize_newtype_variant(NAME, 1, VARIANTS[1], b),
+            Value::Number(n) => {
+                if let Some(u) = n.as_u64() {
+                    serializer.serialize_newtype_variant(NAME, 2, VARIANTS[2], &u)
+                } else if let Some(i) = n.as_i64() {
+                    serializer.serialize_newtype_variant(NAME, 3, VARIANTS[3], &i)
+                } else {
+                    let f = n.as_f64().unwrap_or(f64::NAN);
+                    serializer.serialize_newtype_variant(NAME, 4, VARIANTS[4], &f)
+                }
+            }
+            Value::String(s) => serializer.serialize_newtype_variant(NAME, 5, VARIANTS[5], s),
+            Value::Array(arr) => {
+                serializer.serialize_newtype_variant(NAME, 6, VARIANTS[6], &TaggedArray(arr))
+            }
+            Value::Object(obj) => {
+                serializer.serialize_newtype_variant(NAME, 7, VARIANTS[7], &TaggedObject(obj))
+            }
+        }
+    }
+}
+
+struct TaggedArray<'a>(&'a [Value]);
+
+impl Serialize for TaggedArray<'_> {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for val in self.0 {
+            seq.serialize_element(&Tagged(val))?;
+        }
+        seq.end()
+    }
+}
+
+struct TaggedObject<'a>(&'a Map<String, Value>);
+
+impl Serialize for TaggedObject<'_> {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (key, val) in self.0 {
+            map.serialize_entry(key, &Tagged(val))?;
+        }
+        map.end()
+    }
+}
+
+/// Deserializes an owned `Value` from the tagged encoding
+struct Untagged(Value);
+
+impl<'de> Deserialize<'de> for Untagged {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ValueVisitor;
+
+        impl<'de> Visitor<'de> for ValueVisitor {
+            type Value = Untagged;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a tagged JSON value")
+            }
+
+            fn visit_enum<A>(self, data: A) -> core::result::Result<Untagged, A::Error>
+            where
+                A: EnumAccess<'de>,
+            {
+                let (tag, variant): (u32, _) = data.variant()?;
+                let value = match tag {
+                    0 => {
+                        variant.unit_variant()?;
+                        Value::Null
+                    }
+                    1 => Value::Bool(variant.newtype_variant()?),
+                    2 => Value::Number(variant.newtype_variant::<u64>()?.into()),
+                    3 => Value::Number(variant.newtype_variant::<i64>()?.into()),
+                    4 => {
+                        let f = variant.newtype_variant::<f64>()?;
+                        let n = Number::from_f64(f).ok_or_else(|| {
+                            de::Error::invalid_value(de::Unexpected::Float(f), &"a finite number")
+                        })?;
+                        Value::Number(n)
+                    }
+                    5 => Value::String(variant.newtype_variant()?),
+                    6 => Value::Array(variant.newtype_variant_seed(ArraySeed)?),
+                    7 => Value::Object(variant.newtype_variant_seed(ObjectSeed)?),
+                    _ => {
+                        return Err(de::Error::invalid_value(
+                            de::Unexpected::Unsigned(tag.into()),
+                            &"a tag between 0 and 7",
+                        ))
+                    }
+                };
+                Ok(Untagged(value))
+            }
+        }
+
+        deserializer.deserialize_enum(NAME, VARIANTS, ValueVisitor)
+    }
+}
+
+struct ArraySeed;
+
+impl<'de> de::DeserializeSeed<'de> for ArraySeed {
+    type Value = Vec<Value>;
+
+    fn deserialize<D>(self, deserializer: D) -> core::result::Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ArraySeed {
+    type Value = Vec<Value>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a sequence of tagged JSON values")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> core::result::Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        // Don't trust the length for preallocation, it is untrusted input
+        let mut out = Vec::new();
+        while let Some(Untagged(val)) = seq.next_element()? {
+            out.push(val);
+        }
+        Ok(out)
+    }
+}
+
+struct ObjectSeed;
+
+impl<'de> de::DeserializeSeed<'de> for ObjectSeed {
+    type Value = Map<String, Value>;
+
+    fn deserialize<D>(self, deserializer: D) -> core::result::Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for ObjectSeed {
+    type Value = Map<String, Value>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of tagged JSON values")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> core::result::Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut out = Map::new();
+        while let Some((key, Untagged(val))) = map.next_entry::<String, Untagged>()? {
+            out.insert(key, val);
+        }
+        Ok(out)
+    }
+}
+
+/// Serialize a `serde_json::Value` to an `alloc::vec::Vec<u8>`, using a tagged,
+/// self-describing encoding.
+///
+/// Unlike regular postcard messages, the result can be decoded without knowing its
+/// type ahead of time, using [`from_bytes_dynamic()`]. See the [`dynamic`](self) module
+/// for a description of the encoding.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{from_bytes_dynamic, to_vec_dynamic};
+/// use serde_json::json;
+///
+/// let value = json!({ "id": 7, "tags": ["a", null] });
+/// let ser = to_vec_dynamic(&value).unwrap();
+/// assert_eq!(from_bytes_dynamic(&ser).unwrap(), value);
+/// ```
+pub fn to_vec_dynamic(value: &Value) -> Result<Vec<u8>> {
+    crate::to_allocvec(&Tagged(value))
+}
+
+/// Deserialize a `serde_json::Value` from a byte slice created by [`to_vec_dynamic()`].
+///
+/// As a `serde_json::Value` is a self-describing `Deserializer`, the result can then
+/// be converted to any type, including those relying on `deserialize_any`, such as
+/// internally tagged enums, using `serde_json::from_value`.
+pub fn from_bytes_dynamic(s: &[u8]) -> Result<Value> {
+    crate::from_bytes::<Untagged>(s).map(|v| v.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn dynamic_round_trip() {
+        let value = json!({
+            "name": "sensor",
+            "ok": true,
+            "offset": -12,
+            "gain": 1.5,
+            "reading": 300,
+            "calibration": null,
+            "history": [1, [2, 3], { "nested": "yes" }],
+            "empty": {},
+        });
+
+        let ser = to_vec_dynamic(&value).unwrap();
+        assert_eq!(from_bytes_dynamic(&ser).unwrap(), value);
+
+        // Every value is preceded by its tag
+        assert_eq!(to_vec_dynamic(&json!(null)).unwrap(), &[0]);
+        assert_eq!(to_vec_dynamic(&json!(300)).unwrap(), &[2, 0xAC, 0x02]);
+        assert_eq!(to_vec_dynamic(&json!(-1)).unwrap(), &[3, 0x01]);
+        assert_eq!(
+            to_vec_dynamic(&json!(["a", false])).unwrap(),
+            &[6, 2, 5, 1, b'a', 1, 0]
+        );
+        assert_eq!(
+            to_vec_dynamic(&json!({ "k": 1 })).unwrap(),
+            &[7, 1, 1, b'k', 2, 1]
+        );
+
+        // Unknown tags are rejected
+        assert!(from_bytes_dynamic(&[8]).is_err());
+    }
+
+    #[test]
+    fn dynamic_deserialize_any() {
+        // Internally tagged enums need `deserialize_any`, which works through the `Value`
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        #[serde(tag = "type")]
+        enum Event {
+            Start { at: u64 },
+            Stop { reason: String },
+        }
+
+        let input = Event::Stop {
+            reason: "done".into(),
+        };
+        let ser = to_vec_dynamic(&serde_json::to_value(&input).unwrap()).unwrap();
+        let value = from_bytes_dynamic(&ser).unwrap();
+        assert_eq!(serde_json::from_value::<Event>(value).unwrap(), input);
+    }
+}
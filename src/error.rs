@@ -6,15 +6,29 @@ use core::fmt::{Display, Formatter};
 #[non_exhaustive]
 pub enum Error {
     /// This is a feature that PostCard will never implement
+    ///
+    /// This is returned when deserializing types that rely on a self-describing
+    /// format, e.g. through `deserialize_any` or `deserialize_ignored_any`. This includes
+    /// internally tagged and untagged enums, as well as the struct and unit variants of
+    /// adjacently tagged enums.
     WontImplement,
     /// This is a feature that Postcard intends to support, but does not yet
     NotYetImplemented,
     /// The serialize buffer is full
     SerializeBufferFull,
+    /// The serialize buffer is full, after the given number of bytes were written
+    ///
+    /// This is only returned by [`to_slice_partial()`](crate::to_slice_partial).
+    SerializeBufferFullAt {
+        /// The number of bytes at the start of the buffer that were written
+        written: usize,
+    },
     /// The length of a sequence must be known
     SerializeSeqLengthUnknown,
     /// Hit the end of buffer, expected more data
     DeserializeUnexpectedEnd,
+    /// The deserialize scratch buffer is full
+    DeserializeBufferFull,
     /// Found a varint that didn't terminate. Is the usize too big for this platform?
     DeserializeBadVarint,
     /// Found a bool that wasn't 0 or 1
@@ -31,6 +45,28 @@ pub enum Error {
     DeserializeBadEncoding,
     /// Bad CRC while deserializing
     DeserializeBadCrc,
+    /// The message type ID did not match the expected type ID
+    DeserializeWrongType,
+    /// Found a string or byte slice longer than the configured maximum length
+    DeserializeStringTooLong,
+    /// The byte following the message did not match the expected sentinel
+    DeserializeBadSentinel,
+    /// Found unexpected bytes after the end of the message
+    DeserializeTrailingBytes,
+    /// The schema hash of the message did not match the expected schema hash
+    DeserializeSchemaMismatch,
+    /// The input was not aligned as required by the type borrowed from it
+    DeserializeMisaligned,
+    /// Found a NaN or infinite float, while only finite floats were allowed
+    DeserializeNonFiniteFloat,
+    /// The enum variant of the message was not one of the allowed variants
+    DeserializeForbiddenVariant,
+    /// The protocol version of the message was older than the minimum supported version
+    DeserializeUnsupportedVersion,
+    /// Found a varint integer that was too large for the type being deserialized
+    DeserializeIntegerOverflow,
+    /// The message contained more elements than allowed by the element budget
+    DeserializeBudgetExceeded,
     /// Serde Serialization Error
     SerdeSerCustom,
     /// Serde Deserialization Error
@@ -51,8 +87,12 @@ impl Display for Error {
                     "This is a feature that Postcard intends to support, but does not yet"
                 }
                 SerializeBufferFull => "The serialize buffer is full",
+                SerializeBufferFullAt { .. } => {
+                    "The serialize buffer is full, after the given number of bytes were written"
+                }
                 SerializeSeqLengthUnknown => "The length of a sequence must be known",
                 DeserializeUnexpectedEnd => "Hit the end of buffer, expected more data",
+                DeserializeBufferFull => "The deserialize scratch buffer is full",
                 DeserializeBadVarint => {
                     "Found a varint that didn't terminate. Is the usize too big for this platform?"
                 }
@@ -63,6 +103,35 @@ impl Display for Error {
                 DeserializeBadEnum => "Found an enum discriminant that was > u32::max_value()",
                 DeserializeBadEncoding => "The original data was not well encoded",
                 DeserializeBadCrc => "Bad CRC while deserializing",
+                DeserializeWrongType => "The message type ID did not match the expected type ID",
+                DeserializeStringTooLong => {
+                    "Found a string or byte slice longer than the configured maximum length"
+                }
+                DeserializeBadSentinel => {
+                    "The byte following the message did not match the expected sentinel"
+                }
+                DeserializeTrailingBytes => "Found unexpected bytes after the end of the message",
+                DeserializeSchemaMismatch => {
+                    "The schema hash of the message did not match the expected schema hash"
+                }
+                DeserializeMisaligned => {
+                    "The input was not aligned as required by the type borrowed from it"
+                }
+                DeserializeNonFiniteFloat => {
+                    "Found a NaN or infinite float, while only finite floats were allowed"
+                }
+                DeserializeForbiddenVariant => {
+                    "The enum variant of the message was not one of the allowed variants"
+                }
+                DeserializeUnsupportedVersion => {
+                    "The protocol version of the message was older than the minimum supported version"
+                }
+                DeserializeIntegerOverflow => {
+                    "Found a varint integer that was too large for the type being deserialized"
+                }
+                DeserializeBudgetExceeded => {
+                    "The message contained more elements than allowed by the element budget"
+                }
                 SerdeSerCustom => "Serde Serialization Error",
                 SerdeDeCustom => "Serde Deserialization Error",
                 CollectStrError => "Error while processing `collect_str` during serialization",
@@ -71,6 +140,91 @@ impl Display for Error {
     }
 }
 
+impl Error {
+    /// A stable numeric code for this error, suitable for sending back to the peer
+    /// that produced a message that failed to deserialize.
+    ///
+    /// Unlike the `Display` text, these codes are part of the stable API: the code of
+    /// a variant never changes, and new variants are assigned new codes. Code `0` is
+    /// never used, so it may be used by applications to signal success.
+    ///
+    /// | Code | Variant                                  |
+    /// | ---- | ---------------------------------------- |
+    /// | 1    | [`Error::WontImplement`]                 |
+    /// | 2    | [`Error::NotYetImplemented`]             |
+    /// | 3    | [`Error::SerializeBufferFull`]           |
+    /// | 4    | [`Error::SerializeSeqLengthUnknown`]     |
+    /// | 5    | [`Error::DeserializeUnexpectedEnd`]      |
+    /// | 6    | [`Error::DeserializeBadVarint`]          |
+    /// | 7    | [`Error::DeserializeBadBool`]            |
+    /// | 8    | [`Error::DeserializeBadChar`]            |
+    /// | 9    | [`Error::DeserializeBadUtf8`]            |
+    /// | 10   | [`Error::DeserializeBadOption`]          |
+    /// | 11   | [`Error::DeserializeBadEnum`]            |
+    /// | 12   | [`Error::DeserializeBadEncoding`]        |
+    /// | 13   | [`Error::DeserializeBadCrc`]             |
+    /// | 14   | [`Error::SerdeSerCustom`]                |
+    /// | 15   | [`Error::SerdeDeCustom`]                 |
+    /// | 16   | [`Error::CollectStrError`]               |
+    /// | 17   | [`Error::SerializeBufferFullAt`]         |
+    /// | 18   | [`Error::DeserializeBufferFull`]         |
+    /// | 19   | [`Error::DeserializeWrongType`]          |
+    /// | 20   | [`Error::DeserializeStringTooLong`]      |
+    /// | 21   | [`Error::DeserializeBadSentinel`]        |
+    /// | 22   | [`Error::DeserializeTrailingBytes`]      |
+    /// | 23   | [`Error::DeserializeSchemaMismatch`]     |
+    /// | 24   | [`Error::DeserializeMisaligned`]         |
+    /// | 25   | [`Error::DeserializeNonFiniteFloat`]     |
+    /// | 26   | [`Error::DeserializeForbiddenVariant`]   |
+    /// | 27   | [`Error::DeserializeUnsupportedVersion`] |
+    /// | 28   | [`Error::DeserializeIntegerOverflow`]    |
+    /// | 29   | [`Error::DeserializeBudgetExceeded`]     |
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use postcard::{from_bytes, Error};
+    ///
+    /// let err = from_bytes::<bool>(&[0x02]).unwrap_err();
+    /// assert_eq!(err, Error::DeserializeBadBool);
+    /// assert_eq!(err.code(), 7);
+    /// ```
+    pub fn code(&self) -> u16 {
+        use Error::*;
+        match self {
+            WontImplement => 1,
+            NotYetImplemented => 2,
+            SerializeBufferFull => 3,
+            SerializeSeqLengthUnknown => 4,
+            DeserializeUnexpectedEnd => 5,
+            DeserializeBadVarint => 6,
+            DeserializeBadBool => 7,
+            DeserializeBadChar => 8,
+            DeserializeBadUtf8 => 9,
+            DeserializeBadOption => 10,
+            DeserializeBadEnum => 11,
+            DeserializeBadEncoding => 12,
+            DeserializeBadCrc => 13,
+            SerdeSerCustom => 14,
+            SerdeDeCustom => 15,
+            CollectStrError => 16,
+            SerializeBufferFullAt { .. } => 17,
+            DeserializeBufferFull => 18,
+            DeserializeWrongType => 19,
+            DeserializeStringTooLong => 20,
+            DeserializeBadSentinel => 21,
+            DeserializeTrailingBytes => 22,
+            DeserializeSchemaMismatch => 23,
+            DeserializeMisaligned => 24,
+            DeserializeNonFiniteFloat => 25,
+            DeserializeForbiddenVariant => 26,
+            DeserializeUnsupportedVersion => 27,
+            DeserializeIntegerOverflow => 28,
+            DeserializeBudgetExceeded => 29,
+        }
+    }
+}
+
 /// This is the Result type used by Postcard.
 pub type Result<T> = ::core::result::Result<T, Error>;
 
@@ -93,3 +247,49 @@ impl serde::de::Error for Error {
 }
 
 impl serde::ser::StdError for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::Error::{self, *};
+
+    #[test]
+    fn error_codes() {
+        // These codes are stable, and must never change
+        let documented: [(Error, u16); 29] = [
+            (WontImplement, 1),
+            (NotYetImplemented, 2),
+            (SerializeBufferFull, 3),
+            (SerializeSeqLengthUnknown, 4),
+            (DeserializeUnexpectedEnd, 5),
+            (DeserializeBadVarint, 6),
+            (DeserializeBadBool, 7),
+            (DeserializeBadChar, 8),
+            (DeserializeBadUtf8, 9),
+            (DeserializeBadOption, 10),
+            (DeserializeBadEnum, 11),
+            (DeserializeBadEncoding, 12),
+            (DeserializeBadCrc, 13),
+            (SerdeSerCustom, 14),
+            (SerdeDeCustom, 15),
+            (CollectStrError, 16),
+            (SerializeBufferFullAt { written: 3 }, 17),
+            (DeserializeBufferFull, 18),
+            (DeserializeWrongType, 19),
+            (DeserializeStringTooLong, 20),
+            (DeserializeBadSentinel, 21),
+            (DeserializeTrailingBytes, 22),
+            (DeserializeSchemaMismatch, 23),
+            (DeserializeMisaligned, 24),
+            (DeserializeNonFiniteFloat, 25),
+            (DeserializeForbiddenVariant, 26),
+            (DeserializeUnsupportedVersion, 27),
+            (DeserializeIntegerOverflow, 28),
+            (DeserializeBudgetExceeded, 29),
+        ];
+        for (i, (err, code)) in documented.iter().enumerate() {
+            assert_eq!(err.code(), *code, "{:?}", err);
+            assert!(documented[..i].iter().all(|(_, other)| other != code));
+        }
+        assert_eq!(SerializeBufferFullAt { written: 0 }.code(), 17);
+    }
+}
@@ -0,0 +1,510 @@
+//! # Forward Compatible Extensions
+//!
+//! Postcard messages are not self-describing, so a receiver can't skip over struct
+//! fields that it doesn't know about. This means that appending a field to a struct
+//! breaks older receivers.
+//!
+//! As a compromise, a struct can end with an [`Extensions`] field, which holds any
+//! fields added later on. The extensions are length prefixed on the wire, so a receiver
+//! only decodes the extension fields it knows about, and keeps the rest as raw bytes.
+//!
+//! ```rust
+//! use postcard::extensions::Extensions;
+//! use serde::{Deserialize, Serialize};
+//!
+//! // Version 1 of the protocol, which has no extension fields yet
+//! #[derive(Serialize, Deserialize)]
+//! struct ReadingV1<'a> {
+//!     id: u8,
+//!     #[serde(borrow)]
+//!     ext: Extensions<'a, ()>,
+//! }
+//!
+//! // Version 2 adds a field, inside of the extensions
+//! #[derive(Serialize, Deserialize)]
+//! struct ReadingV2<'a> {
+//!     id: u8,
+//!     #[serde(borrow)]
+//!     ext: Extensions<'a, ReadingExtV2>,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct ReadingExtV2 {
+//!     unit: u8,
+//! }
+//!
+//! let mut buf = [0u8; 16];
+//! let msg = ReadingV2 { id: 7, ext: Extensions::new(ReadingExtV2 { unit: 3 }) };
+//! let used = postcard::to_slice(&msg, &mut buf).unwrap();
+//! assert_eq!(used, &[0x07, 0x01, 0x03]);
+//!
+//! // A version 1 receiver keeps the unknown extension as raw bytes
+//! let old: ReadingV1 = postcard::from_bytes(used).unwrap();
+//! assert_eq!(old.id, 7);
+//! assert_eq!(old.ext.unknown, &[0x03]);
+//! ```
+//!
+//! NOTE: The [`Extensions`] field must be the last field of the struct, and further
+//! extension fields must always be appended to the end of the extension struct.
+//! Extensions are only supported with the default [`Config`](crate::Config).
+//!
+//! Similarly, adding a variant to an enum breaks older receivers, which don't know
+//! how long its data is. An [`ExtensibleEnum`] length prefixes the data of every
+//! variant, so older receivers keep unknown variants as raw bytes instead.
+
+use core::cell::Cell;
+use core::fmt;
+use serde::de::{DeserializeSeed, EnumAccess, Error as _, IntoDeserializer, SeqAccess, Visitor};
+use serde::ser::{Error as _, SerializeSeq, SerializeTuple};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::de_flavors::Slice;
+use crate::error::{Error, Result as PostcardResult};
+use crate::ser::flavors::Flavor as SerFlavor;
+use crate::varint::varint_max;
+
+/// Extension fields of a struct, length prefixed on the wire. See the
+/// [`extensions`](crate::extensions) module for details.
+///
+/// On the wire, this is encoded like a byte slice (`&[u8]`), containing the postcard
+/// encoding of the known extension fields `T`, followed by any unknown bytes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Extensions<'a, T> {
+    /// The extension fields known by this version of the protocol
+    pub value: T,
+    /// Any trailing bytes of extension fields unknown to this version of the protocol.
+    ///
+    /// These are serialized again after `value`, so relaying a message does not
+    /// drop the extensions that were added by newer versions.
+    pub unknown: &'a [u8],
+}
+
+impl<'a, T> Extensions<'a, T> {
+    /// Create new `Extensions`, without any unknown bytes
+    pub fn new(value: T) -> Self {
+        Extensions {
+            value,
+            unknown: &[],
+        }
+    }
+}
+
+/// Writes a length prefix, using the same encoding as a byte slice
+struct LengthPrefix(usize);
+
+impl Serialize for LengthPrefix {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // A sequence without elements is only its length prefix
+        serializer.serialize_seq(Some(self.0))?.end()
+    }
+}
+
+impl<'a, T: Serialize> Serialize for Extensions<'a, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let known = crate::ser::serialized_size(&self.value).map_err(S::Error::custom)?;
+        let mut tup = serializer.serialize_tuple(2 + self.unknown.len())?;
+        tup.serialize_element(&LengthPrefix(known + self.unknown.len()))?;
+        tup.serialize_element(&self.value)?;
+        for byte in self.unknown {
+            tup.serialize_element(byte)?;
+        }
+        tup.end()
+    }
+}
+
+impl<'de: 'a, 'a, T: Deserialize<'de>> Deserialize<'de> for Extensions<'a, T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct BlobVisitor;
+
+        impl<'de> Visitor<'de> for BlobVisitor {
+            type Value = &'de [u8];
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("length prefixed extensions")
+            }
+
+            fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+                Ok(v)
+            }
+        }
+
+        let blob = deserializer.deserialize_bytes(BlobVisitor)?;
+        let (value, unknown) = crate::take_from_bytes(blob).map_err(D::Error::custom)?;
+        Ok(Extensions { value, unknown })
+    }
+}
+
+/// An enum, with the data of its variant length prefixed on the wire, so that variants
+/// unknown to the receiver can be skipped. See the [`extensions`](crate::extensions)
+/// module for details.
+///
+/// On the wire, this is encoded as the discriminant of the variant of `T` (as usual, a
+/// varint), followed by its data, encoded like a byte slice (`&[u8]`). This costs one
+/// byte (or more, for long data) per message, but variants added in newer versions of
+/// the protocol deserialize to [`ExtensibleEnum::Unknown`] on older receivers, rather
+/// than failing. Unknown variants are serialized again unchanged, so they can be relayed.
+///
+/// `T` must be an enum. Data of a known variant that doesn't use exactly all of its
+/// bytes is rejected with [`Error::SerdeDeCustom`].
+///
+/// ```rust
+/// use postcard::extensions::ExtensibleEnum;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// enum CommandV1 {
+///     Stop,
+///     SetLevel(u8),
+/// }
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// enum CommandV2 {
+///     Stop,
+///     SetLevel(u8),
+///     Blink { on_ms: u16, off_ms: u16 },
+/// }
+///
+/// let mut buf = [0u8; 16];
+/// let cmd = ExtensibleEnum::Known(CommandV2::Blink { on_ms: 100, off_ms: 400 });
+/// let used = postcard::to_slice(&cmd, &mut buf).unwrap();
+/// assert_eq!(used, &[0x02, 0x03, 0x64, 0x90, 0x03]);
+///
+/// let old: ExtensibleEnum<CommandV1> = postcard::from_bytes(used).unwrap();
+/// assert_eq!(old, ExtensibleEnum::Unknown { discriminant: 2, raw: &[0x64, 0x90, 0x03] });
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExtensibleEnum<'a, T> {
+    /// A variant known by this version of the protocol
+    Known(T),
+    /// A variant unknown to this version of the protocol
+    Unknown {
+        /// The discriminant of the variant
+        discriminant: u32,
+        /// The data of the variant
+        raw: &'a [u8],
+    },
+}
+
+/// A flavor that records the discriminant of a serialized enum, and the size of its data
+struct Measure {
+    disc: [u8; varint_max::<u32>()],
+    disc_len: usize,
+    disc_done: bool,
+    data_len: usize,
+}
+
+impl SerFlavor for Measure {
+    type Output = Self;
+
+    #[inline]
+    fn try_push(&mut self, data: u8) -> PostcardResult<()> {
+        if self.disc_done {
+            self.data_len += 1;
+            return Ok(());
+        }
+        let slot = self
+            .disc
+            .get_mut(self.disc_len)
+            .ok_or(Error::SerializeBufferFull)?;
+        *slot = data;
+        self.disc_len += 1;
+        self.disc_done = (data & 0x80) == 0;
+        Ok(())
+    }
+
+    fn finalize(self) -> PostcardResult<Self> {
+        Ok(self)
+    }
+}
+
+/// A flavor that forwards the data of a serialized enum to a tuple, skipping its
+/// discriminant
+struct ForwardData<'a, S> {
+    tup: &'a mut S,
+    disc_done: bool,
+}
+
+impl<'a, S: SerializeTuple> SerFlavor for ForwardData<'a, S> {
+    type Output = ();
+
+    #[inline]
+    fn try_push(&mut self, data: u8) -> PostcardResult<()> {
+        if !self.disc_done {
+            self.disc_done = (data & 0x80) == 0;
+            return Ok(());
+        }
+        self.tup
+            .serialize_element(&data)
+            .map_err(|_| Error::SerdeSerCustom)
+    }
+
+    fn finalize(self) -> PostcardResult<()> {
+        Ok(())
+    }
+}
+
+impl<'a, T: Serialize> Serialize for ExtensibleEnum<'a, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            ExtensibleEnum::Known(value) => {
+                let measure = Measure {
+                    disc: [0; varint_max::<u32>()],
+                    disc_len: 0,
+                    disc_done: false,
+                    data_len: 0,
+                };
+                let measure =
+                    crate::serialize_with_flavor(value, measure).map_err(S::Error::custom)?;
+                let disc: u32 = crate::from_bytes(&measure.disc[..measure.disc_len])
+                    .map_err(S::Error::custom)?;
+
+                let mut tup = serializer.serialize_tuple(2 + measure.data_len)?;
+                tup.serialize_element(&disc)?;
+                tup.serialize_element(&LengthPrefix(measure.data_len))?;
+                let forward = ForwardData {
+                    tup: &mut tup,
+                    disc_done: false,
+                };
+                crate::serialize_with_flavor(value, forward).map_err(S::Error::custom)?;
+                tup.end()
+            }
+            ExtensibleEnum::Unknown { discriminant, raw } => {
+                let mut tup = serializer.serialize_tuple(2)?;
+                tup.serialize_element(discriminant)?;
+                tup.serialize_element(raw)?;
+                tup.end()
+            }
+        }
+    }
+}
+
+/// Deserializes an enum from a discriminant that was already read, and its data
+struct SplitEnum<'a, 'de> {
+    disc: u32,
+    data: &'a mut crate::Deserializer<'de, Slice<'de>>,
+    unknown: &'a Cell<bool>,
+}
+
+impl<'a, 'de> Deserializer<'de> for SplitEnum<'a, 'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> PostcardResult<V::Value> {
+        Err(Error::WontImplement)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> PostcardResult<V::Value> {
+        visitor.visit_enum(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+impl<'a, 'de> EnumAccess<'de> for SplitEnum<'a, 'de> {
+    type Error = Error;
+    type Variant = &'a mut crate::Deserializer<'de, Slice<'de>>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> PostcardResult<(V::Value, Self::Variant)> {
+        // Only the discriminant is checked here, so this can only fail for unknown variants
+        let v = seed
+            .deserialize(self.disc.into_deserializer())
+            .inspect_err(|_| self.unknown.set(true))?;
+        Ok((v, self.data))
+    }
+}
+
+impl<'de: 'a, 'a, T: Deserialize<'de>> Deserialize<'de> for ExtensibleEnum<'a, T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ExtensibleVisitor<T>(core::marker::PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for ExtensibleVisitor<T> {
+            type Value = ExtensibleEnum<'de, T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a discriminant, followed by length prefixed data")
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let discriminant: u32 = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+                let raw: &'de [u8] = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+
+                let unknown = Cell::new(false);
+                let mut data = crate::Deserializer::from_bytes(raw);
+                let split = SplitEnum {
+                    disc: discriminant,
+                    data: &mut data,
+                    unknown: &unknown,
+                };
+                match T::deserialize(split) {
+                    Ok(_) if data.remaining() != 0 => {
+                        Err(A::Error::custom("enum variant data was not fully used"))
+                    }
+                    Ok(value) => Ok(ExtensibleEnum::Known(value)),
+                    Err(_) if unknown.get() => Ok(ExtensibleEnum::Unknown { discriminant, raw }),
+                    Err(e) => Err(A::Error::custom(e)),
+                }
+            }
+        }
+
+        deserializer.deserialize_tuple(2, ExtensibleVisitor(core::marker::PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExtensibleEnum, Extensions};
+    use crate::Error;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct StatusV1<'a> {
+        id: u16,
+        #[serde(borrow)]
+        ext: Extensions<'a, ()>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct StatusV2<'a> {
+        id: u16,
+        #[serde(borrow)]
+        ext: Extensions<'a, StatusExtV2>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct StatusExtV2 {
+        temp: i32,
+        code: u16,
+    }
+
+    #[test]
+    fn newer_fields_land_in_unknown() {
+        let mut buf = [0u8; 32];
+        let newer = StatusV2 {
+            id: 300,
+            ext: Extensions::new(StatusExtV2 {
+                temp: -2,
+                code: 300,
+            }),
+        };
+        let used = crate::to_slice(&newer, &mut buf).unwrap();
+        assert_eq!(used, &[0xAC, 0x02, 0x03, 0x03, 0xAC, 0x02]);
+        let newer_bytes: &[u8] = &[0xAC, 0x02, 0x03, 0x03, 0xAC, 0x02];
+
+        // The older receiver keeps the newer field as raw bytes
+        let older: StatusV1<'_> = crate::from_bytes(newer_bytes).unwrap();
+        assert_eq!(older.id, 300);
+        assert_eq!(older.ext.unknown, &[0x03, 0xAC, 0x02]);
+
+        // ...and relays it unchanged
+        let mut relay = [0u8; 32];
+        let used = crate::to_slice(&older, &mut relay).unwrap();
+        assert_eq!(used, newer_bytes);
+
+        // The newer receiver sees the newer field
+        let out: StatusV2<'_> = crate::from_bytes(newer_bytes).unwrap();
+        assert_eq!(out, newer);
+        assert!(out.ext.unknown.is_empty());
+
+        // A message without extensions
+        let used = crate::to_slice(
+            &StatusV1 {
+                id: 1,
+                ext: Extensions::new(()),
+            },
+            &mut buf,
+        )
+        .unwrap();
+        assert_eq!(used, &[0x01, 0x00]);
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum CommandV1<'a> {
+        Stop,
+        Move(i16, i16),
+        Label(&'a str),
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum CommandV2<'a> {
+        Stop,
+        Move(i16, i16),
+        Label(&'a str),
+        Rotate { degrees: u16 },
+    }
+
+    #[test]
+    fn newer_variants_land_in_unknown() {
+        let mut buf = [0u8; 32];
+        let cases: [(CommandV1<'_>, &[u8]); 3] = [
+            (CommandV1::Stop, &[0x00, 0x00]),
+            (CommandV1::Move(1, -1), &[0x01, 0x02, 0x02, 0x01]),
+            (CommandV1::Label("hi"), &[0x02, 0x03, 0x02, b'h', b'i']),
+        ];
+        for (cmd, ser_rep) in cases {
+            let input = ExtensibleEnum::Known(cmd);
+            let used = crate::to_slice(&input, &mut buf).unwrap();
+            assert_eq!(used, ser_rep);
+            assert_eq!(crate::from_bytes(used), Ok(input));
+        }
+
+        // An older receiver keeps the newer variant as raw bytes...
+        let newer = ExtensibleEnum::Known(CommandV2::Rotate { degrees: 300 });
+        let used = crate::to_slice(&newer, &mut buf).unwrap();
+        let newer_bytes: &[u8] = &[0x03, 0x02, 0xAC, 0x02];
+        assert_eq!(used, newer_bytes);
+        let older: ExtensibleEnum<'_, CommandV1<'_>> = crate::from_bytes(newer_bytes).unwrap();
+        assert_eq!(
+            older,
+            ExtensibleEnum::Unknown {
+                discriminant: 3,
+                raw: &[0xAC, 0x02]
+            }
+        );
+
+        // ...and relays it unchanged
+        let mut relay = [0u8; 32];
+        assert_eq!(crate::to_slice(&older, &mut relay).unwrap(), newer_bytes);
+        assert_eq!(crate::from_bytes(newer_bytes), Ok(newer));
+
+        // Known variants must use all of their data, and only their data
+        let res = crate::from_bytes::<ExtensibleEnum<'_, CommandV1<'_>>>(&[0x00, 0x01, 0x00]);
+        assert_eq!(res, Err(Error::SerdeDeCustom));
+        let res = crate::from_bytes::<ExtensibleEnum<'_, CommandV1<'_>>>(&[0x01, 0x01, 0x02]);
+        assert_eq!(res, Err(Error::SerdeDeCustom));
+        let res = crate::from_bytes::<ExtensibleEnum<'_, CommandV1<'_>>>(&[0x01, 0x03, 0x02]);
+        assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+    }
+}
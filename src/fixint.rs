@@ -12,7 +12,14 @@
 //! order, these modules provide a choice to the user to encode the data
 //! in either little or big endian form, which may be useful for zero-copy
 //! applications.
+//!
+//! For large arrays of fixed size numbers, [`LeSlice`] can be used to borrow
+//! the elements directly from the input buffer where possible.
+//! For fixed size arrays of them, such as sample buffers, [`le_array`] copies
+//! all of the elements at once, rather than decoding them one by one.
 
+use core::marker::PhantomData;
+use core::mem::{align_of, size_of};
 use serde::{Deserialize, Serialize, Serializer};
 
 /// Use with the `#[serde(with = "postcard::fixint::le")]` field attribute.
@@ -91,6 +98,119 @@ pub mod be {
     }
 }
 
+/// Use with the `#[serde(with = "postcard::fixint::le_array")]` field attribute,
+/// for arrays (`[T; N]`) of any [`LePrimitive`], such as `[f32; 1024]`.
+///
+/// On the wire, this is the same as for `[T; N]`: the little endian bytes of every
+/// element, without a length prefix. However, on little endian platforms, the whole
+/// array is copied from the input at once, instead of decoding each element on its
+/// own. On big endian platforms, the elements are byte swapped one at a time.
+///
+/// Unlike serde's own implementations, this works for arrays of any length `N`.
+///
+/// With formats other than postcard, which can't lend the bytes from their input,
+/// deserializing fails with an "invalid type" error.
+///
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Spectrum {
+///     channel: u8,
+///     #[serde(with = "postcard::fixint::le_array")]
+///     bins: [f32; 256],
+/// }
+///
+/// let input = Spectrum { channel: 2, bins: [0.5; 256] };
+/// let mut buf = [0u8; 1 + 256 * 4];
+/// let used = postcard::to_slice(&input, &mut buf).unwrap();
+/// assert_eq!(&used[..5], &[0x02, 0x00, 0x00, 0x00, 0x3F]);
+///
+/// let out: Spectrum = postcard::from_bytes(used).unwrap();
+/// assert_eq!(out.bins, input.bins);
+/// ```
+pub mod le_array {
+    use core::fmt;
+    use core::mem::{size_of, MaybeUninit};
+    use serde::de::Visitor;
+    use serde::ser::SerializeTuple;
+    use serde::{Deserializer, Serializer};
+
+    use super::{LePrimitive, LeSlice};
+    use crate::raw::BYTE_ARRAY;
+
+    /// Serialize the array as the little endian bytes of its elements.
+    pub fn serialize<S, T, const N: usize>(val: &[T; N], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: LePrimitive,
+    {
+        let bytes = LeSlice::new(val).bytes;
+        let mut tup = serializer.serialize_tuple(bytes.len())?;
+        for chunk in bytes.chunks_exact(size_of::<T>()) {
+            if cfg!(target_endian = "little") {
+                chunk.iter().try_for_each(|b| tup.serialize_element(b))?;
+            } else {
+                chunk
+                    .iter()
+                    .rev()
+                    .try_for_each(|b| tup.serialize_element(b))?;
+            }
+        }
+        tup.end()
+    }
+
+    /// Deserialize the array from the little endian bytes of its elements.
+    pub fn deserialize<'de, D, T, const N: usize>(deserializer: D) -> Result<[T; N], D::Error>
+    where
+        D: Deserializer<'de>,
+        T: LePrimitive,
+    {
+        struct ArrayVisitor<T, const N: usize>(core::marker::PhantomData<T>);
+
+        impl<'de, T: LePrimitive, const N: usize> Visitor<'de> for ArrayVisitor<T, N> {
+            type Value = [T; N];
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "{} borrowed bytes", N * size_of::<T>())
+            }
+
+            fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                if v.len() != N * size_of::<T>() {
+                    return Err(E::invalid_length(v.len(), &self));
+                }
+                if cfg!(target_endian = "big") {
+                    return Ok(core::array::from_fn(|i| {
+                        T::from_le_chunk(&v[i * size_of::<T>()..][..size_of::<T>()])
+                    }));
+                }
+                let mut out = MaybeUninit::<[T; N]>::uninit();
+                // SAFETY: `v` is exactly the size of `[T; N]` (checked above), the
+                // destination is a fresh local that can't overlap it, and every bit pattern
+                // is a valid `T` (ensured by the sealed `LePrimitive` trait), so the array
+                // is fully initialized, in native (little endian) byte order.
+                unsafe {
+                    core::ptr::copy_nonoverlapping(
+                        v.as_ptr(),
+                        out.as_mut_ptr().cast::<u8>(),
+                        v.len(),
+                    );
+                    Ok(out.assume_init())
+                }
+            }
+        }
+
+        deserializer.deserialize_tuple_struct(
+            BYTE_ARRAY,
+            N * size_of::<T>(),
+            ArrayVisitor(core::marker::PhantomData),
+        )
+    }
+}
+
 #[doc(hidden)]
 pub struct LE<T>(T);
 
@@ -149,6 +269,188 @@ macro_rules! impl_fixint {
 
 impl_fixint![i16, i32, i64, i128, u16, u32, u64, u128];
 
+/// A primitive number that can be borrowed from a [`LeSlice`].
+///
+/// This trait is sealed, and is implemented for all fixed size integers larger than one
+/// byte, as well as `f32` and `f64`. Every bit pattern of these types is a valid value.
+pub trait LePrimitive: Copy + sealed::Sealed {
+    #[doc(hidden)]
+    fn from_le_chunk(chunk: &[u8]) -> Self;
+
+    #[doc(hidden)]
+    fn from_ne_chunk(chunk: &[u8]) -> Self;
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_le_primitive {
+    ($( $ty:ty ),*) => {
+        $(
+            impl sealed::Sealed for $ty {}
+
+            impl LePrimitive for $ty {
+                #[inline]
+                fn from_le_chunk(chunk: &[u8]) -> Self {
+                    let mut buf = [0u8; size_of::<$ty>()];
+                    buf.copy_from_slice(chunk);
+                    <$ty>::from_le_bytes(buf)
+                }
+
+                #[inline]
+                fn from_ne_chunk(chunk: &[u8]) -> Self {
+                    let mut buf = [0u8; size_of::<$ty>()];
+                    buf.copy_from_slice(chunk);
+                    <$ty>::from_ne_bytes(buf)
+                }
+            }
+        )*
+    };
+}
+
+impl_le_primitive![i16, i32, i64, i128, u16, u32, u64, u128, f32, f64];
+
+/// A slice of fixed size numbers, stored in **Little Endian** order on the wire.
+///
+/// On the wire, a `LeSlice` is encoded as a byte slice (`&[u8]`), containing the
+/// concatenated little endian bytes of every element. This means the length prefix is
+/// the number of *bytes*, rather than the number of elements.
+///
+/// Deserializing a `LeSlice` never copies the data. On little endian platforms, if the
+/// data happens to be suitably aligned for `T`, [`LeSlice::as_slice()`] can borrow the
+/// elements directly, without any per-element decoding. Otherwise, the elements can
+/// always be decoded one by one with [`LeSlice::iter()`].
+///
+/// ```rust
+/// use postcard::fixint::LeSlice;
+///
+/// let data: &[u16] = &[0x0102, 0x0304];
+/// let mut buf = [0u8; 32];
+/// let used = postcard::to_slice(&LeSlice::new(data), &mut buf).unwrap();
+/// assert_eq!(used, &[0x04, 0x02, 0x01, 0x04, 0x03]);
+///
+/// let out: LeSlice<'_, u16> = postcard::from_bytes(used).unwrap();
+/// assert_eq!(out.len(), 2);
+/// assert!(out.iter().eq([0x0102, 0x0304]));
+///
+/// // Only borrowable when the platform and the alignment permit
+/// if let Some(borrowed) = out.as_slice() {
+///     assert_eq!(borrowed, data);
+/// }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct LeSlice<'a, T: LePrimitive> {
+    bytes: &'a [u8],
+    // Are the bytes in native order (from `new`), rather than little endian order?
+    native: bool,
+    _pd: PhantomData<T>,
+}
+
+impl<'a, T: LePrimitive> LeSlice<'a, T> {
+    /// Create a `LeSlice` for serializing the given elements.
+    ///
+    /// On big endian platforms, the elements are byte swapped one at a time
+    /// while serializing.
+    pub fn new(data: &'a [T]) -> Self {
+        // SAFETY: `T` is a plain old number type without padding, so the slice is
+        // valid to view as `size_of_val(data)` initialized bytes for the same lifetime.
+        let bytes = unsafe {
+            core::slice::from_raw_parts(data.as_ptr().cast::<u8>(), core::mem::size_of_val(data))
+        };
+        Self {
+            bytes,
+            native: true,
+            _pd: PhantomData,
+        }
+    }
+
+    /// Create a `LeSlice` from the little endian bytes of its elements.
+    ///
+    /// Returns `None` if the number of bytes is not a multiple of the size of `T`.
+    pub fn from_le_bytes(bytes: &'a [u8]) -> Option<Self> {
+        if !bytes.chunks_exact(size_of::<T>()).remainder().is_empty() {
+            return None;
+        }
+        Some(Self {
+            bytes,
+            native: false,
+            _pd: PhantomData,
+        })
+    }
+
+    /// The number of elements in the slice.
+    pub fn len(&self) -> usize {
+        self.bytes.len() / size_of::<T>()
+    }
+
+    /// Is the slice empty?
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Borrow the elements without copying them.
+    ///
+    /// Returns `None` on big endian platforms, or if the underlying bytes are not
+    /// aligned for `T`. In that case, use [`LeSlice::iter()`] instead.
+    pub fn as_slice(&self) -> Option<&'a [T]> {
+        let native = self.native || cfg!(target_endian = "little");
+        if !native || self.bytes.as_ptr().align_offset(align_of::<T>()) != 0 {
+            return None;
+        }
+        // SAFETY: The pointer is aligned for `T` (checked above), covers exactly `len()`
+        // elements (checked on construction), every bit pattern is a valid `T` (ensured
+        // by the sealed `LePrimitive` trait), and the byte order is native (checked above).
+        Some(unsafe { core::slice::from_raw_parts(self.bytes.as_ptr().cast::<T>(), self.len()) })
+    }
+
+    /// Decode the elements one by one. This works on every platform, regardless of alignment.
+    pub fn iter(&self) -> impl Iterator<Item = T> + 'a {
+        let native = self.native;
+        self.bytes.chunks_exact(size_of::<T>()).map(move |chunk| {
+            if native {
+                T::from_ne_chunk(chunk)
+            } else {
+                T::from_le_chunk(chunk)
+            }
+        })
+    }
+}
+
+impl<'a, T: LePrimitive> Serialize for LeSlice<'a, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if !self.native || cfg!(target_endian = "little") {
+            return serializer.serialize_bytes(self.bytes);
+        }
+
+        // The in-memory byte order doesn't match, so swap each element. In postcard,
+        // a sequence of `u8`s is encoded identically to a byte slice.
+        use serde::ser::SerializeSeq;
+        let mut seq = serializer.serialize_seq(Some(self.bytes.len()))?;
+        for chunk in self.bytes.chunks_exact(size_of::<T>()) {
+            for byte in chunk.iter().rev() {
+                seq.serialize_element(byte)?;
+            }
+        }
+        seq.end()
+    }
+}
+
+impl<'de: 'a, 'a, T: LePrimitive> Deserialize<'de> for LeSlice<'a, T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let bytes = <&'a [u8]>::deserialize(deserializer)?;
+        LeSlice::from_le_bytes(bytes).ok_or_else(|| {
+            serde::de::Error::invalid_length(bytes.len(), &"a multiple of the element size")
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serde::{Deserialize, Serialize};
@@ -186,4 +488,99 @@ mod tests {
         let deserialized: DefinitelyBE = crate::from_bytes(serialized).unwrap();
         assert_eq!(deserialized, input);
     }
+
+    #[test]
+    fn test_le_array() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Frame {
+            id: u8,
+            #[serde(with = "crate::fixint::le_array")]
+            samples: [f32; 256],
+        }
+
+        let input = Frame {
+            id: 3,
+            samples: core::array::from_fn(|i| i as f32 * -0.25),
+        };
+        let mut buf = [0u8; 1 + 256 * 4];
+        let serialized = crate::to_slice(&input, &mut buf).unwrap();
+        assert_eq!(serialized.len(), 1 + 256 * 4);
+        for (i, chunk) in serialized[1..].chunks_exact(4).enumerate() {
+            assert_eq!(chunk, &(i as f32 * -0.25).to_le_bytes());
+        }
+
+        // Try every offset, as the copy doesn't depend on the alignment of the input
+        let mut storage = [0u8; 4 + 1 + 256 * 4];
+        for offset in 0..4 {
+            let shifted = &mut storage[offset..][..serialized.len()];
+            shifted.copy_from_slice(serialized);
+            let deserialized: Frame = crate::from_bytes(shifted).unwrap();
+            assert_eq!(deserialized, input);
+        }
+
+        // The same wire format as a regular array
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Small {
+            #[serde(with = "crate::fixint::le_array")]
+            vals: [u16; 3],
+        }
+        let vals = [1, 0x0203, u16::MAX];
+        let mut plain = [0u8; 8];
+        let plain = crate::to_slice(&vals.map(crate::fixint::LE), &mut plain).unwrap();
+        let serialized = crate::to_slice(&Small { vals }, &mut buf).unwrap();
+        assert_eq!(serialized, plain);
+        assert_eq!(crate::from_bytes(serialized), Ok(Small { vals }));
+
+        // Too short
+        let res = crate::from_bytes::<Small>(&serialized[..5]);
+        assert_eq!(res, Err(crate::Error::DeserializeUnexpectedEnd));
+    }
+
+    #[test]
+    fn test_le_slice() {
+        use super::LeSlice;
+
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Samples<'a> {
+            id: u8,
+            #[serde(borrow)]
+            vals: LeSlice<'a, u32>,
+        }
+
+        let vals: &[u32] = &[1, 0x0203_0405, u32::MAX];
+        let input = Samples {
+            id: 7,
+            vals: LeSlice::new(vals),
+        };
+
+        // Try every offset, so that the elements are borrowable for (at most) one in four
+        #[repr(align(4))]
+        struct Aligned([u8; 32]);
+        let mut buf = Aligned([0; 32]);
+        for offset in 0..4 {
+            let serialized = crate::to_slice(&input, &mut buf.0[offset..]).unwrap();
+            assert_eq!(
+                serialized,
+                &[7, 12, 1, 0, 0, 0, 0x05, 0x04, 0x03, 0x02, 0xFF, 0xFF, 0xFF, 0xFF]
+            );
+
+            let deserialized: Samples<'_> = crate::from_bytes(serialized).unwrap();
+            assert_eq!(deserialized.id, 7);
+            assert_eq!(deserialized.vals.len(), 3);
+            assert!(deserialized.vals.iter().eq(vals.iter().copied()));
+
+            // The elements start two bytes into the message
+            let aligned = (offset + 2) % 4 == 0;
+            let borrowed = deserialized.vals.as_slice();
+            if cfg!(target_endian = "little") && aligned {
+                assert_eq!(borrowed, Some(vals));
+            } else {
+                assert_eq!(borrowed, None);
+            }
+        }
+
+        // The number of bytes must be a multiple of the element size
+        let res: crate::Result<LeSlice<'_, u32>> = crate::from_bytes(&[3, 1, 2, 3]);
+        assert_eq!(res.unwrap_err(), crate::Error::SerdeDeCustom);
+    }
 }
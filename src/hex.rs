@@ -0,0 +1,115 @@
+//! # Hex Encoding
+//!
+//! Minimal hex encoding and decoding, for logging frames over text-only channels
+//! (like RTT or a debug UART). These don't require `alloc`.
+//!
+//! ```rust
+//! use postcard::hex::{from_hex, to_hex};
+//!
+//! let mut frame = [0u8; 8];
+//! let frame = postcard::to_slice(&(1u8, 300u16), &mut frame).unwrap();
+//!
+//! let mut text = [0u8; 16];
+//! let text = to_hex(frame, &mut text).unwrap();
+//! assert_eq!(text, "01ac02");
+//!
+//! let mut decoded = [0u8; 8];
+//! let decoded = from_hex(text, &mut decoded).unwrap();
+//! assert_eq!(decoded, frame);
+//! ```
+
+use crate::error::{Error, Result};
+
+const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Encode `bytes` as lowercase hex into `out`, which must hold two bytes per input byte.
+///
+/// Returns the used portion of `out` as a string, or [`Error::SerializeBufferFull`]
+/// if `out` is too small.
+pub fn to_hex<'a>(bytes: &[u8], out: &'a mut [u8]) -> Result<&'a str> {
+    let used = bytes
+        .len()
+        .checked_mul(2)
+        .ok_or(Error::SerializeBufferFull)?;
+    let out = out.get_mut(..used).ok_or(Error::SerializeBufferFull)?;
+    for (byte, pair) in bytes.iter().zip(out.chunks_exact_mut(2)) {
+        pair[0] = DIGITS[(byte >> 4) as usize];
+        pair[1] = DIGITS[(byte & 0x0F) as usize];
+    }
+    // Only ASCII hex digits were written
+    core::str::from_utf8(out).map_err(|_| Error::SerializeBufferFull)
+}
+
+/// Decode the hex string `hex` (in upper or lower case) into `out`.
+///
+/// Returns the used portion of `out`. An odd number of digits or a non-hex character
+/// returns [`Error::DeserializeBadEncoding`], and an `out` that is too small returns
+/// [`Error::DeserializeBufferFull`].
+pub fn from_hex<'a>(hex: &str, out: &'a mut [u8]) -> Result<&'a mut [u8]> {
+    let hex = hex.as_bytes();
+    let pairs = hex.chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return Err(Error::DeserializeBadEncoding);
+    }
+    let out = out
+        .get_mut(..hex.len() / 2)
+        .ok_or(Error::DeserializeBufferFull)?;
+    for (pair, byte) in pairs.zip(out.iter_mut()) {
+        *byte = (nibble(pair[0])? << 4) | nibble(pair[1])?;
+    }
+    Ok(out)
+}
+
+fn nibble(digit: u8) -> Result<u8> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(Error::DeserializeBadEncoding),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_round_trip() {
+        let mut text = [0u8; 8];
+        assert_eq!(to_hex(&[0xDE, 0xAD], &mut text), Ok("dead"));
+        assert_eq!(to_hex(&[], &mut text), Ok(""));
+        assert_eq!(to_hex(&[0x00, 0x0F, 0xF0], &mut text), Ok("000ff0"));
+
+        let mut bytes = [0u8; 4];
+        assert_eq!(from_hex("dead", &mut bytes).unwrap(), &[0xDE, 0xAD]);
+        assert_eq!(from_hex("DeAd", &mut bytes).unwrap(), &[0xDE, 0xAD]);
+        assert!(from_hex("", &mut bytes).unwrap().is_empty());
+    }
+
+    #[test]
+    fn hex_errors() {
+        let mut text = [0u8; 3];
+        assert_eq!(
+            to_hex(&[0xDE, 0xAD], &mut text),
+            Err(Error::SerializeBufferFull)
+        );
+
+        let mut bytes = [0u8; 4];
+        assert_eq!(
+            from_hex("dea", &mut bytes),
+            Err(Error::DeserializeBadEncoding)
+        );
+        assert_eq!(
+            from_hex("deag", &mut bytes),
+            Err(Error::DeserializeBadEncoding)
+        );
+        assert_eq!(
+            from_hex("0x12", &mut bytes),
+            Err(Error::DeserializeBadEncoding)
+        );
+        assert_eq!(
+            from_hex("0011223344", &mut bytes),
+            Err(Error::DeserializeBufferFull)
+        );
+    }
+}
@@ -4,15 +4,46 @@
 #![cfg_attr(doc_cfg, feature(doc_cfg))]
 
 pub mod accumulator;
+#[cfg(feature = "use-chrono")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "use-chrono")))]
+pub mod chrono;
+mod config;
 mod de;
+pub mod delta;
+#[cfg(feature = "use-json")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "use-json")))]
+pub mod dynamic;
 mod error;
+pub mod extensions;
 pub mod fixint;
+pub mod hex;
+mod msgpack;
+pub mod navigable;
+pub mod packed;
+pub mod raw;
 mod ser;
+pub mod stable;
+pub mod stream_seq;
+pub mod trailing;
+#[cfg(feature = "use-uuid")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "use-uuid")))]
+pub mod uuid;
 mod varint;
 
 // Still experimental! Don't make pub pub.
+#[cfg_attr(not(feature = "experimental-derive"), allow(dead_code))]
+pub(crate) mod c_layout;
+#[cfg(all(feature = "experimental-derive", feature = "alloc"))]
+pub(crate) mod cbor;
+#[cfg_attr(not(feature = "experimental-derive"), allow(dead_code))]
 pub(crate) mod max_size;
+#[cfg_attr(not(feature = "experimental-derive"), allow(dead_code))]
+pub(crate) mod no_maps;
 pub(crate) mod schema;
+#[cfg(feature = "experimental-derive")]
+pub(crate) mod validate;
+#[cfg(all(feature = "experimental-derive", feature = "alloc"))]
+pub(crate) mod value;
 
 /// # Experimental Postcard Features
 ///
@@ -22,10 +53,14 @@ pub(crate) mod schema;
 ///
 /// ## Derive
 ///
-/// The `experimental-derive` feature enables two experimental features:
+/// The `experimental-derive` feature enables six experimental features:
 ///
 /// * Max size calculation
 /// * Message schema generation
+/// * Fixed width enum discriminants
+/// * Map-free type checking
+/// * Packed `bool` and `Option` fields
+/// * C memory layouts
 ///
 /// ### Max Size Calculation
 ///
@@ -54,7 +89,62 @@ pub(crate) mod schema;
 /// separately.
 ///
 /// Please report any missing types, or any incorrectly calculated schemas.
+///
+/// ### Fixed Width Enum Discriminants
+///
+/// This feature provides a `#[derive(FixedDiscriminant)]` macro, which implements
+/// `Serialize` and `Deserialize` for an enum, with a fixed width discriminant instead of
+/// a varint: a single byte for enums with up to 256 variants, and two little endian
+/// bytes for larger ones, followed by the fields of the variant in order. This is useful
+/// for interop with hardware or other protocols using fixed tag widths.
+///
+/// ### Map-free Type Checking
+///
+/// This feature provides a `NoMaps` marker trait, and a `#[derive(NoMaps)]` macro, for
+/// types that never contain a map. This can be used to reject message types containing
+/// maps at compile time, for builds that must not rely on map support.
+///
+/// ### Packed `bool` and `Option` Fields
+///
+/// This feature provides a `#[derive(PackedSerialize)]` macro, which implements
+/// `Serialize` and `Deserialize` for a struct with named fields, packing all of its
+/// `bool` fields into a leading bitmap (like a [`PackedBools`](crate::packed::PackedBools)),
+/// followed by the other fields in order. This is a different wire format than the one
+/// of serde's own derives.
+///
+/// Similarly, the `#[derive(BitmapOptional)]` macro collects the presence of all of the
+/// `Option` fields of a struct into a leading bitmap, followed by the other fields and
+/// only the values of the present `Option` fields, in order. This is smaller than the
+/// one byte tag per `Option` of serde's own derives, for structs with many optional
+/// fields that are usually absent.
+///
+/// ### C Memory Layouts
+///
+/// This feature provides a `CLayout` trait, and a `#[derive(CLayout)]` macro, for
+/// `#[repr(C)]` structs. These are serialized exactly like their memory on a little
+/// endian target, with fixed width integers and zeroed padding between fields, for
+/// interop with C code reinterpreting the bytes in place, e.g. in shared memory.
 pub mod experimental {
+    /// Serialization in the memory layout of `#[repr(C)]` structs
+    #[cfg(feature = "experimental-derive")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "experimental-derive")))]
+    pub mod c_layout {
+        // NOTE: This is the trait...
+        pub use crate::c_layout::CLayout;
+        #[doc(hidden)]
+        pub use crate::c_layout::{__pad, __skip};
+        // NOTE: ...and this is the derive macro
+        pub use postcard_derive::CLayout;
+    }
+
+    /// Enums with fixed width discriminants
+    #[cfg(feature = "experimental-derive")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "experimental-derive")))]
+    pub mod fixed_discriminant {
+        // NOTE: This is the derive macro, implementing `Serialize` and `Deserialize`
+        pub use postcard_derive::FixedDiscriminant;
+    }
+
     /// Compile time max-serialization size calculation
     #[cfg(feature = "experimental-derive")]
     #[cfg_attr(doc_cfg, doc(cfg(feature = "experimental-derive")))]
@@ -65,6 +155,24 @@ pub mod experimental {
         pub use postcard_derive::MaxSize;
     }
 
+    /// Compile time checking for types without maps
+    #[cfg(feature = "experimental-derive")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "experimental-derive")))]
+    pub mod no_maps {
+        // NOTE: This is the trait...
+        pub use crate::no_maps::NoMaps;
+        // NOTE: ...and this is the derive macro
+        pub use postcard_derive::NoMaps;
+    }
+
+    /// Structs with packed `bool` or `Option` fields
+    #[cfg(feature = "experimental-derive")]
+    #[cfg_attr(doc_cfg, doc(cfg(feature = "experimental-derive")))]
+    pub mod packed {
+        // NOTE: These are the derive macros, implementing `Serialize` and `Deserialize`
+        pub use postcard_derive::{BitmapOptional, PackedSerialize};
+    }
+
     pub use crate::ser::serialized_size;
 
     /// Compile time Schema generation
@@ -72,21 +180,45 @@ pub mod experimental {
     #[cfg_attr(doc_cfg, doc(cfg(feature = "experimental-derive")))]
     pub mod schema {
         // NOTE: This is the trait...
-        pub use crate::schema::{NamedType, NamedValue, NamedVariant, Schema, SdmTy, Varint};
+        #[cfg(feature = "alloc")]
+        pub use crate::cbor::to_cbor_via_postcard;
+        #[cfg(feature = "heapless")]
+        pub use crate::schema::to_vec_versioned;
+        pub use crate::schema::{
+            assert_same_wire_format, from_bytes_versioned, schema_hash, wire_compatible, NamedType,
+            NamedValue, NamedVariant, Schema, SchemaHash, SdmTy, Varint,
+        };
+        pub use crate::validate::{from_bytes_field, from_bytes_validated, validate};
+        #[cfg(feature = "alloc")]
+        pub use crate::value::{from_bytes_as_value, PostcardValue};
         // NOTE: ...and this is the derive macro
         pub use postcard_derive::Schema;
     }
 }
 
-pub use de::deserializer::Deserializer;
+pub use config::{CharEncoding, Config, EnumEncoding, IntEncoding, IntOverflow, LengthEncoding};
+pub use de::deserializer::{Breadcrumb, Deserializer};
 pub use de::flavors as de_flavors;
-pub use de::{from_bytes, from_bytes_cobs, take_from_bytes, take_from_bytes_cobs};
+pub use de::{
+    from_bytes, from_bytes_allowed_variants, from_bytes_cobs, from_bytes_cobs_to,
+    from_bytes_field_sorted, from_bytes_footer_len, from_bytes_into, from_bytes_min_version,
+    from_bytes_named, from_bytes_owned, from_bytes_padded, from_bytes_sentinel, from_bytes_typed,
+    from_bytes_with_config, from_frame, take_from_bytes, take_from_bytes_cobs,
+    take_from_bytes_with_config, take_until_zero, DeserializeInto, Framing,
+};
+pub use delta::{from_bytes_delta, to_slice_delta};
 pub use error::{Error, Result};
 pub use ser::flavors as ser_flavors;
-pub use ser::{serialize_with_flavor, serializer::Serializer, to_extend, to_slice, to_slice_cobs};
+pub use ser::{
+    serialize_with_flavor, serializer::Serializer, to_chunks, to_extend, to_slice, to_slice_cobs,
+    to_slice_partial, to_slice_with_config,
+};
 
 #[cfg(feature = "heapless")]
-pub use ser::{to_vec, to_vec_cobs};
+pub use ser::{
+    to_spsc, to_vec, to_vec_cobs, to_vec_footer_len, to_vec_interned, to_vec_named, to_vec_padded,
+    to_vec_with_config, MultiSerializer,
+};
 
 #[cfg(feature = "embedded-io")]
 pub use ser::to_eio;
@@ -101,7 +233,22 @@ pub use ser::{to_io, to_stdvec, to_stdvec_cobs};
 pub use de::from_io;
 
 #[cfg(feature = "alloc")]
-pub use ser::{to_allocvec, to_allocvec_cobs};
+pub use ser::{to_allocvec, to_allocvec_cobs, to_allocvec_field_sorted, to_allocvec_with_config};
+
+#[cfg(feature = "alloc")]
+pub use de::{from_bytes_batch, from_bytes_partial_seq};
+
+#[cfg(feature = "heapless")]
+pub use de::{from_bytes_interned, from_spsc};
+
+#[cfg(feature = "use-bytemuck")]
+pub use de::from_bytes_ref;
+
+#[cfg(feature = "compare")]
+pub use ser::compare_sizes;
+
+#[cfg(feature = "use-json")]
+pub use dynamic::{from_bytes_dynamic, to_vec_dynamic};
 
 #[cfg(feature = "use-crc")]
 pub use {
@@ -129,7 +276,7 @@ mod test {
         assert_eq!(deser, u32::MAX);
         assert_eq!(used, &mut [0xFF, 0xFF, 0xFF, 0xFF, 0x0F]);
         let deser: Result<u32, crate::Error> = crate::from_bytes(&[0xFF, 0xFF, 0xFF, 0xFF, 0x1F]);
-        assert_eq!(deser, Err(crate::Error::DeserializeBadVarint));
+        assert_eq!(deser, Err(crate::Error::DeserializeIntegerOverflow));
     }
 
     #[test]
@@ -140,4 +287,49 @@ mod test {
         let deser: i128 = crate::from_bytes(used).unwrap();
         assert_eq!(deser, x);
     }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn seq_len_beyond_u32() {
+        use serde::de::{Deserialize, Deserializer, SeqAccess, Visitor};
+
+        // Reads only the length of a sequence, rather than its elements
+        struct SeqLen(Option<usize>);
+
+        impl<'de> Deserialize<'de> for SeqLen {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct LenVisitor;
+
+                impl<'de> Visitor<'de> for LenVisitor {
+                    type Value = SeqLen;
+
+                    fn expecting(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+                        f.write_str("a sequence")
+                    }
+
+                    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<SeqLen, A::Error> {
+                        Ok(SeqLen(seq.size_hint()))
+                    }
+                }
+
+                deserializer.deserialize_seq(LenVisitor)
+            }
+        }
+
+        // Lengths are varint `usize`s, which are not limited to `u32` on 64-bit hosts
+        let len = 5_000_000_000usize;
+        let mut buf = [0u8; 16];
+        let mut serializer =
+            crate::Serializer::from_flavor(crate::ser_flavors::Slice::new(&mut buf));
+        serializer.serialize_seq_header(len).unwrap();
+        let used = crate::ser_flavors::Flavor::finalize(serializer.output).unwrap();
+        assert_eq!(used, &[0x80, 0xE4, 0x97, 0xD0, 0x12]);
+
+        let deser: SeqLen = crate::from_bytes(used).unwrap();
+        assert_eq!(deser.0, Some(len));
+
+        // The same length is not representable on a 32-bit host
+        let deser: Result<u32, crate::Error> = crate::from_bytes(used);
+        assert_eq!(deser, Err(crate::Error::DeserializeIntegerOverflow));
+    }
 }
@@ -0,0 +1,70 @@
+//! MessagePack style integers, see [`IntEncoding::MsgPack`](crate::IntEncoding::MsgPack)
+
+/// The largest value of a positive fixint, which is its own tag byte
+pub const POSITIVE_FIXINT_MAX: u8 = 0x7F;
+/// The tag byte of the smallest negative fixint, `-32`, which is its own tag byte
+pub const NEGATIVE_FIXINT_MIN: u8 = 0xE0;
+
+pub const UINT8: u8 = 0xCC;
+pub const UINT16: u8 = 0xCD;
+pub const UINT32: u8 = 0xCE;
+pub const UINT64: u8 = 0xCF;
+pub const INT8: u8 = 0xD0;
+pub const INT16: u8 = 0xD1;
+pub const INT32: u8 = 0xD2;
+pub const INT64: u8 = 0xD3;
+/// An extension value of 16 bytes, used for 128-bit integers that don't fit in 64 bits
+pub const FIXEXT16: u8 = 0xD8;
+
+/// The extension type of a `FIXEXT16` holding an unsigned 128-bit integer
+pub const EXT_UINT128: u8 = 0x00;
+/// The extension type of a `FIXEXT16` holding a signed 128-bit integer
+pub const EXT_INT128: u8 = 0x01;
+
+/// The tag byte, and up to eight big endian bytes, of an unsigned integer
+#[inline]
+pub fn encode_uint(v: u64, out: &mut [u8; 9]) -> &[u8] {
+    let (tag, len) = if v <= POSITIVE_FIXINT_MAX.into() {
+        return encode_fixint(v as u8, out);
+    } else if v <= u8::MAX.into() {
+        (UINT8, 1)
+    } else if v <= u16::MAX.into() {
+        (UINT16, 2)
+    } else if v <= u32::MAX.into() {
+        (UINT32, 4)
+    } else {
+        (UINT64, 8)
+    };
+    out[0] = tag;
+    out[1..=len].copy_from_slice(&v.to_be_bytes()[8 - len..]);
+    &out[..=len]
+}
+
+/// The tag byte, and up to eight big endian bytes, of a signed integer
+#[inline]
+pub fn encode_int(v: i64, out: &mut [u8; 9]) -> &[u8] {
+    // Positive values use the same (smaller) tags as unsigned ones
+    if v >= 0 {
+        return encode_uint(v as u64, out);
+    }
+    let (tag, len) = if v >= i64::from(NEGATIVE_FIXINT_MIN as i8) {
+        return encode_fixint(v as u8, out);
+    } else if v >= i8::MIN.into() {
+        (INT8, 1)
+    } else if v >= i16::MIN.into() {
+        (INT16, 2)
+    } else if v >= i32::MIN.into() {
+        (INT32, 4)
+    } else {
+        (INT64, 8)
+    };
+    out[0] = tag;
+    out[1..=len].copy_from_slice(&v.to_be_bytes()[8 - len..]);
+    &out[..=len]
+}
+
+#[inline]
+fn encode_fixint(tag: u8, out: &mut [u8; 9]) -> &[u8] {
+    out[0] = tag;
+    &out[..1]
+}
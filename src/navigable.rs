@@ -0,0 +1,321 @@
+//! # Navigable Sequences
+//!
+//! Sequences are normally prefixed with their number of elements. To find the end of a
+//! sequence, a reader has to decode every element. [`Navigable`] additionally prefixes a
+//! sequence with its length in bytes, so that a reader can skip over it without
+//! decoding it, using [`Skipped`], and decode it lazily later on.
+//!
+//! On the wire, a `Navigable` sequence is encoded as `[element count][byte length]`,
+//! both as varints, followed by the elements as usual. Nesting `Navigable` sequences
+//! allows skipping any subtree of a large, nested message.
+//!
+//! ```rust
+//! use postcard::navigable::{Navigable, Skipped};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize)]
+//! struct Log<'a> {
+//!     entries: Navigable<[&'a str; 2]>,
+//!     end: u8,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct LogSummary<'a> {
+//!     #[serde(borrow)]
+//!     entries: Skipped<'a>,
+//!     end: u8,
+//! }
+//!
+//! let mut buf = [0u8; 32];
+//! let log = Log { entries: Navigable(["boot", "ok"]), end: 0xFF };
+//! let used = postcard::to_slice(&log, &mut buf).unwrap();
+//! assert_eq!(&used[..2], &[0x02, 0x08]);
+//!
+//! // The entries are skipped without being decoded
+//! let summary: LogSummary = postcard::from_bytes(used).unwrap();
+//! assert_eq!(summary.entries.count, 2);
+//! assert_eq!(summary.end, 0xFF);
+//! ```
+//!
+//! NOTE: Navigable sequences are only supported with the default [`Config`](crate::Config).
+
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::value::SeqAccessDeserializer;
+use serde::de::{DeserializeSeed, Error as _, SeqAccess, Visitor};
+use serde::ser::{Error as _, SerializeSeq, SerializeTuple};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::raw::BYTE_ARRAY;
+
+/// A sequence, prefixed with its length in bytes as well as its number of elements.
+///
+/// See the [`navigable`](crate::navigable) module for details. This can wrap anything
+/// that can be iterated by reference, with a known number of elements, such as slices,
+/// arrays, or `Vec`s. When deserializing, `T` must be deserializable from a sequence.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Navigable<T>(pub T);
+
+/// A [`Navigable`] sequence that was skipped without decoding its elements.
+///
+/// The elements can be decoded lazily later on, one at a time, from `bytes`, e.g. with
+/// [`take_from_bytes()`](crate::take_from_bytes).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Skipped<'a> {
+    /// The number of elements in the sequence
+    pub count: usize,
+    /// The encoded elements of the sequence, without any prefix
+    pub bytes: &'a [u8],
+}
+
+/// Writes a varint `usize`, using the same encoding as a length prefix
+struct Prefix(usize);
+
+impl Serialize for Prefix {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // A sequence without elements is only its length prefix
+        serializer.serialize_seq(Some(self.0))?.end()
+    }
+}
+
+impl<T, E> Serialize for Navigable<T>
+where
+    for<'a> &'a T: IntoIterator<Item = &'a E>,
+    for<'a> <&'a T as IntoIterator>::IntoIter: ExactSizeIterator,
+    E: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let count = (&self.0).into_iter().len();
+        let mut bytes = 0usize;
+        for elem in &self.0 {
+            bytes += crate::ser::serialized_size(elem).map_err(S::Error::custom)?;
+        }
+
+        let mut tup = serializer.serialize_tuple(2 + count)?;
+        tup.serialize_element(&Prefix(count))?;
+        tup.serialize_element(&Prefix(bytes))?;
+        for elem in &self.0 {
+            tup.serialize_element(elem)?;
+        }
+        tup.end()
+    }
+}
+
+/// Reads the two prefixes of a navigable sequence, then its contents using `seed`
+fn deserialize_prefixed<'de, D, S>(deserializer: D, seed: S) -> Result<S::Value, D::Error>
+where
+    D: Deserializer<'de>,
+    S: PrefixedSeed<'de>,
+{
+    struct PrefixedVisitor<S>(S);
+
+    impl<'de, S: PrefixedSeed<'de>> Visitor<'de> for PrefixedVisitor<S> {
+        type Value = S::Value;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("a navigable sequence")
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let count: usize = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+            let bytes: usize = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+            seq.next_element_seed(Contents {
+                seed: self.0,
+                count,
+                bytes,
+            })?
+            .ok_or_else(|| A::Error::invalid_length(2, &"a navigable sequence"))
+        }
+    }
+
+    deserializer.deserialize_tuple(3, PrefixedVisitor(seed))
+}
+
+/// Deserializes the contents of a navigable sequence, given its prefixes
+trait PrefixedSeed<'de>: Sized {
+    type Value;
+
+    fn deserialize<D>(
+        self,
+        deserializer: D,
+        count: usize,
+        bytes: usize,
+    ) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+struct Contents<S> {
+    seed: S,
+    count: usize,
+    bytes: usize,
+}
+
+impl<'de, S: PrefixedSeed<'de>> DeserializeSeed<'de> for Contents<S> {
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.seed.deserialize(deserializer, self.count, self.bytes)
+    }
+}
+
+/// Decodes the elements into a `T`
+struct Elements<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> PrefixedSeed<'de> for Elements<T> {
+    type Value = T;
+
+    fn deserialize<D>(self, deserializer: D, count: usize, _bytes: usize) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ElementsVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for ElementsVisitor<T> {
+            type Value = T;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("the elements of a navigable sequence")
+            }
+
+            fn visit_seq<A>(self, seq: A) -> Result<T, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                T::deserialize(SeqAccessDeserializer::new(seq))
+            }
+        }
+
+        deserializer.deserialize_tuple(count, ElementsVisitor(PhantomData))
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Navigable<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_prefixed(deserializer, Elements(PhantomData)).map(Navigable)
+    }
+}
+
+/// Borrows the encoded elements, without decoding them
+struct Borrowed;
+
+impl<'de> PrefixedSeed<'de> for Borrowed {
+    type Value = Skipped<'de>;
+
+    fn deserialize<D>(
+        self,
+        deserializer: D,
+        count: usize,
+        bytes: usize,
+    ) -> Result<Skipped<'de>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct BytesVisitor(usize);
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = Skipped<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("the encoded elements of a navigable sequence")
+            }
+
+            fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+                Ok(Skipped {
+                    count: self.0,
+                    bytes: v,
+                })
+            }
+        }
+
+        deserializer.deserialize_tuple_struct(BYTE_ARRAY, bytes, BytesVisitor(count))
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for Skipped<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_prefixed(deserializer, Borrowed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Navigable, Skipped};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Tree {
+        id: u8,
+        branches: Navigable<[Navigable<[u16; 3]>; 2]>,
+        checksum: u16,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct TreeHeader<'a> {
+        id: u8,
+        #[serde(borrow)]
+        branches: Skipped<'a>,
+        checksum: u16,
+    }
+
+    #[test]
+    fn skip_nested_seq() {
+        let mut buf = [0u8; 32];
+        let input = Tree {
+            id: 7,
+            branches: Navigable([Navigable([1, 2, 3]), Navigable([300, 0, 0x4000])]),
+            checksum: 0xBEEF,
+        };
+        let used = crate::to_slice(&input, &mut buf).unwrap();
+        assert_eq!(
+            used,
+            &[
+                0x07, // id
+                0x02, 0x0D, // two branches, in 13 bytes
+                0x03, 0x03, 0x01, 0x02, 0x03, // three elements, in 3 bytes
+                0x03, 0x06, 0xAC, 0x02, 0x00, 0x80, 0x80, 0x01, // ...in 6 bytes
+                0xEF, 0xFD, 0x02, // checksum
+            ]
+        );
+        assert_eq!(crate::from_bytes::<Tree>(used), Ok(input));
+
+        // The branches are skipped, and decoding resumes right after them
+        let header: TreeHeader<'_> = crate::from_bytes(used).unwrap();
+        assert_eq!(header.id, 7);
+        assert_eq!(header.checksum, 0xBEEF);
+        assert_eq!(header.branches.count, 2);
+        assert_eq!(header.branches.bytes, &used[3..16]);
+
+        // ...and can be decoded lazily, skipping the first branch as well
+        let (first, rest) = crate::take_from_bytes::<Skipped<'_>>(header.branches.bytes).unwrap();
+        assert_eq!(first.count, 3);
+        let second: Navigable<[u16; 3]> = crate::from_bytes(rest).unwrap();
+        assert_eq!(second, Navigable([300, 0, 0x4000]));
+
+        // A byte length beyond the end of the input
+        let res = crate::from_bytes::<Skipped<'_>>(&[0x01, 0x05, 0x00]);
+        assert_eq!(res, Err(crate::Error::DeserializeUnexpectedEnd));
+    }
+}
@@ -0,0 +1,119 @@
+use core::{
+    marker::PhantomData,
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+    },
+};
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+/// A marker trait for types that never contain a map, and so never require
+/// `serialize_map` or `deserialize_map` support.
+///
+/// This is not implemented for any map types, so requiring `T: NoMaps` (for example in
+/// a wrapper around [`from_bytes()`](crate::from_bytes)) rejects message types containing
+/// maps at compile time. Deriving `NoMaps` for a type containing a map fails to compile:
+///
+/// ```rust,compile_fail
+/// use postcard::experimental::no_maps::NoMaps;
+/// use std::collections::BTreeMap;
+///
+/// #[derive(NoMaps)]
+/// struct Settings {
+///     values: BTreeMap<u8, u16>,
+/// }
+/// ```
+pub trait NoMaps {}
+
+macro_rules! impl_no_maps {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl NoMaps for $ty {}
+        )*
+    };
+}
+
+impl_no_maps!(
+    bool,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    f32,
+    f64,
+    char,
+    str,
+    (),
+);
+
+impl_no_maps!(
+    NonZeroI8,
+    NonZeroI16,
+    NonZeroI32,
+    NonZeroI64,
+    NonZeroI128,
+    NonZeroIsize,
+    NonZeroU8,
+    NonZeroU16,
+    NonZeroU32,
+    NonZeroU64,
+    NonZeroU128,
+    NonZeroUsize,
+);
+
+impl<T: NoMaps> NoMaps for Option<T> {}
+
+impl<T: NoMaps, E: NoMaps> NoMaps for Result<T, E> {}
+
+impl<T: NoMaps, const N: usize> NoMaps for [T; N] {}
+
+impl<T: NoMaps> NoMaps for [T] {}
+
+impl<T: NoMaps + ?Sized> NoMaps for &'_ T {}
+
+impl<T: NoMaps + ?Sized> NoMaps for &'_ mut T {}
+
+impl<T: ?Sized> NoMaps for PhantomData<T> {}
+
+macro_rules! impl_no_maps_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: NoMaps),+> NoMaps for ($($name,)+) {}
+    };
+}
+
+impl_no_maps_tuple!(A);
+impl_no_maps_tuple!(A, B);
+impl_no_maps_tuple!(A, B, C);
+impl_no_maps_tuple!(A, B, C, D);
+impl_no_maps_tuple!(A, B, C, D, E);
+impl_no_maps_tuple!(A, B, C, D, E, F);
+
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+impl<T: NoMaps, const N: usize> NoMaps for heapless::Vec<T, N> {}
+
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+impl<const N: usize> NoMaps for heapless::String<N> {}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<T: NoMaps> NoMaps for alloc::vec::Vec<T> {}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl NoMaps for alloc::string::String {}
+
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+impl<T: NoMaps + ?Sized> NoMaps for alloc::boxed::Box<T> {}
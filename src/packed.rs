@@ -0,0 +1,291 @@
+//! # Packed Representations
+//!
+//! Opt-in wrappers that trade a little processing for a smaller size on the wire.
+//!
+//! By default, every `bool` is encoded as a full byte, so a `[bool; N]` takes `N` bytes
+//! on the wire. [`PackedBools`] opts in to packing eight of them into each byte.
+//!
+//! Floating point numbers are always encoded with their full width. [`CompactF32`] and
+//! [`CompactF64`] opt in to omitting low order zero bytes, which is a (lossless) win for
+//! values with short mantissas, such as `0.0`, `1.0`, or `-0.75`.
+
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use serde::de::{Error as _, SeqAccess, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A fixed size array of `bool`s, packed eight to a byte on the wire.
+///
+/// On the wire, this is encoded like a `[u8; (N + 7) / 8]` array (without a length
+/// prefix). Element `i` is stored in byte `i / 8`, at bit `i % 8`, counting from
+/// the least significant bit. Unused bits of the last byte are always zero, and are
+/// rejected when deserializing otherwise.
+///
+/// ```rust
+/// use postcard::packed::PackedBools;
+///
+/// let mut flags = PackedBools([false; 10]);
+/// flags[0] = true;
+/// flags[3] = true;
+/// flags[9] = true;
+///
+/// let mut buf = [0u8; 8];
+/// let used = postcard::to_slice(&flags, &mut buf).unwrap();
+/// assert_eq!(used, &[0b0000_1001, 0b0000_0010]);
+///
+/// let out: PackedBools<10> = postcard::from_bytes(used).unwrap();
+/// assert_eq!(out, flags);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PackedBools<const N: usize>(pub [bool; N]);
+
+impl<const N: usize> PackedBools<N> {
+    /// The number of bytes used on the wire
+    pub const PACKED_LEN: usize = N.div_ceil(8);
+}
+
+impl<const N: usize> Default for PackedBools<N> {
+    fn default() -> Self {
+        PackedBools([false; N])
+    }
+}
+
+impl<const N: usize> From<[bool; N]> for PackedBools<N> {
+    fn from(bools: [bool; N]) -> Self {
+        PackedBools(bools)
+    }
+}
+
+impl<const N: usize> From<PackedBools<N>> for [bool; N] {
+    fn from(packed: PackedBools<N>) -> Self {
+        packed.0
+    }
+}
+
+impl<const N: usize> Deref for PackedBools<N> {
+    type Target = [bool; N];
+
+    fn deref(&self) -> &[bool; N] {
+        &self.0
+    }
+}
+
+impl<const N: usize> DerefMut for PackedBools<N> {
+    fn deref_mut(&mut self) -> &mut [bool; N] {
+        &mut self.0
+    }
+}
+
+impl<const N: usize> Serialize for PackedBools<N> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut tup = serializer.serialize_tuple(Self::PACKED_LEN)?;
+        for chunk in self.0.chunks(8) {
+            let byte = chunk
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (i, b)| byte | ((*b as u8) << i));
+            tup.serialize_element(&byte)?;
+        }
+        tup.end()
+    }
+}
+
+impl<'de, const N: usize> Deserialize<'de> for PackedBools<N> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct PackedVisitor<const N: usize>;
+
+        impl<'de, const N: usize> Visitor<'de> for PackedVisitor<N> {
+            type Value = PackedBools<N>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "{} packed bools", N)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut bools = [false; N];
+                for (idx, chunk) in bools.chunks_mut(8).enumerate() {
+                    let byte: u8 = seq
+                        .next_element()?
+                        .ok_or_else(|| A::Error::invalid_length(idx, &self))?;
+                    if (byte as u16) >> chunk.len() != 0 {
+                        return Err(A::Error::custom("unused packed bits are not zero"));
+                    }
+                    for (i, b) in chunk.iter_mut().enumerate() {
+                        *b = (byte >> i) & 1 != 0;
+                    }
+                }
+                Ok(PackedBools(bools))
+            }
+        }
+
+        deserializer.deserialize_tuple(Self::PACKED_LEN, PackedVisitor::<N>)
+    }
+}
+
+macro_rules! impl_compact_float {
+    ($name:ident, $ty:ty, $bits:ty, $example:literal, [$($example_bytes:literal),*]) => {
+        #[doc = concat!("An `", stringify!($ty), "` with its low order zero bytes omitted on the wire.")]
+        ///
+        /// On the wire, this is encoded like a byte slice (`&[u8]`), containing the little
+        /// endian bytes of the number, without any low order bytes that are zero. The number
+        /// of bytes is at most the size of the number, so the length prefix is always one
+        /// byte. The bit pattern is preserved exactly, including for `-0.0` and NaNs.
+        ///
+        /// ```rust
+        #[doc = concat!("use postcard::packed::", stringify!($name), ";")]
+        ///
+        /// let mut buf = [0u8; 16];
+        #[doc = concat!("let used = postcard::to_slice(&", stringify!($name), "(0.0), &mut buf).unwrap();")]
+        /// assert_eq!(used, &[0x00]);
+        ///
+        #[doc = concat!("let used = postcard::to_slice(&", stringify!($name), "(", $example, "), &mut buf).unwrap();")]
+        #[doc = concat!("assert_eq!(used, &[", $(stringify!($example_bytes), ", ",)* "]);")]
+        /// ```
+        #[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+        pub struct $name(pub $ty);
+
+        impl From<$ty> for $name {
+            fn from(val: $ty) -> Self {
+                $name(val)
+            }
+        }
+
+        impl From<$name> for $ty {
+            fn from(val: $name) -> Self {
+                val.0
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                let bytes = self.0.to_bits().to_le_bytes();
+                let zeros = (self.0.to_bits().trailing_zeros() / 8) as usize;
+                serializer.serialize_bytes(&bytes[zeros.min(bytes.len())..])
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                struct CompactVisitor;
+
+                impl<'de> Visitor<'de> for CompactVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                        write!(formatter, "at most {} bytes", core::mem::size_of::<$ty>())
+                    }
+
+                    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+                    where
+                        E: serde::de::Error,
+                    {
+                        let mut buf = [0u8; core::mem::size_of::<$ty>()];
+                        let zeros = buf
+                            .len()
+                            .checked_sub(v.len())
+                            .ok_or_else(|| E::invalid_length(v.len(), &self))?;
+                        buf[zeros..].copy_from_slice(v);
+                        Ok($name(<$ty>::from_bits(<$bits>::from_le_bytes(buf))))
+                    }
+                }
+
+                deserializer.deserialize_bytes(CompactVisitor)
+            }
+        }
+    };
+}
+
+impl_compact_float!(CompactF32, f32, u32, "1.0", [0x02, 0x80, 0x3F]);
+impl_compact_float!(CompactF64, f64, u64, "-0.75", [0x02, 0xE8, 0xBF]);
+
+#[cfg(test)]
+mod tests {
+    use super::PackedBools;
+
+    #[test]
+    fn packed_bools() {
+        let mut buf = [0u8; 16];
+
+        let mut input = PackedBools([false; 10]);
+        input[0] = true;
+        input[7] = true;
+        input[8] = true;
+        let used = crate::to_slice(&input, &mut buf).unwrap();
+        assert_eq!(used, &[0b1000_0001, 0b0000_0001]);
+        assert_eq!(crate::from_bytes::<PackedBools<10>>(used).unwrap(), input);
+
+        let input = PackedBools([true; 10]);
+        let used = crate::to_slice(&input, &mut buf).unwrap();
+        assert_eq!(used, &[0xFF, 0b0000_0011]);
+        assert_eq!(crate::from_bytes::<PackedBools<10>>(used).unwrap(), input);
+
+        // Exactly full bytes, and no bytes at all
+        let input = PackedBools([true; 16]);
+        let used = crate::to_slice(&input, &mut buf).unwrap();
+        assert_eq!(used, &[0xFF, 0xFF]);
+        assert_eq!(crate::from_bytes::<PackedBools<16>>(used).unwrap(), input);
+
+        let used = crate::to_slice(&PackedBools([false; 0]), &mut buf).unwrap();
+        assert!(used.is_empty());
+
+        // Unused bits must be zero
+        let res = crate::from_bytes::<PackedBools<10>>(&[0x00, 0b0000_0100]);
+        assert_eq!(res, Err(crate::Error::SerdeDeCustom));
+
+        let res = crate::from_bytes::<PackedBools<10>>(&[0x00]);
+        assert_eq!(res, Err(crate::Error::DeserializeUnexpectedEnd));
+    }
+
+    #[test]
+    fn compact_floats() {
+        use super::{CompactF32, CompactF64};
+        let mut buf = [0u8; 16];
+
+        let cases: [(f32, &[u8]); 5] = [
+            (0.0, &[0x00]),
+            (1.0, &[0x02, 0x80, 0x3F]),
+            (-0.0, &[0x01, 0x80]),
+            (0.1, &[0x04, 0xCD, 0xCC, 0xCC, 0x3D]),
+            (f32::MIN_POSITIVE, &[0x02, 0x80, 0x00]),
+        ];
+        for (data, ser_rep) in cases {
+            let used = crate::to_slice(&CompactF32(data), &mut buf).unwrap();
+            assert_eq!(used, ser_rep);
+            let out = crate::from_bytes::<CompactF32>(used).unwrap();
+            assert_eq!(out.0.to_bits(), data.to_bits());
+        }
+
+        let cases: [f64; 6] = [0.0, 1.0, -0.75, 0.1, f64::MAX, f64::NAN];
+        for data in cases {
+            let used = crate::to_slice(&CompactF64(data), &mut buf).unwrap();
+            assert!(used.len() <= 9);
+            let out = crate::from_bytes::<CompactF64>(used).unwrap();
+            assert_eq!(out.0.to_bits(), data.to_bits());
+        }
+        let used = crate::to_slice(&CompactF64(0.1), &mut buf).unwrap();
+        assert_eq!(
+            used,
+            &[0x08, 0x9A, 0x99, 0x99, 0x99, 0x99, 0x99, 0xB9, 0x3F]
+        );
+
+        // More bytes than the number has are rejected
+        let res = crate::from_bytes::<CompactF32>(&[0x05, 0x00, 0x00, 0x00, 0x80, 0x3F]);
+        assert_eq!(res, Err(crate::Error::SerdeDeCustom));
+    }
+}
@@ -0,0 +1,225 @@
+//! # Raw Bytes
+//!
+//! Byte slices are normally prefixed with their length on the wire. When the length is
+//! implied by the surrounding context instead, such as a payload that is framed
+//! externally, [`RawTail`] omits the prefix, and takes all of the remaining input.
+//!
+//! Byte arrays (`[u8; N]`) are never prefixed, but serde deserializes them element by
+//! element, into a copy. [`deserialize_borrowed_byte_array()`] borrows them from the
+//! input instead, as a `&[u8; N]`.
+
+use core::convert::TryFrom;
+use core::fmt;
+use serde::de::Visitor;
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The newtype struct name used to recognize a [`RawTail`] when deserializing
+pub(crate) const RAW_TAIL: &str = "$postcard::RawTail";
+
+/// The tuple struct name used to recognize a borrowed byte array when deserializing
+pub(crate) const BYTE_ARRAY: &str = "$postcard::ByteArray";
+
+/// A byte slice without a length prefix, containing all of the remaining input.
+///
+/// On the wire, the bytes are written as-is. When deserializing, a `RawTail` consumes
+/// the input up to the end, so it must be the last field of a message. Any fields
+/// following it find no input left, and fail with [`Error::DeserializeUnexpectedEnd`].
+///
+/// When deserializing from a flavor that doesn't know where the input ends, such as a
+/// reader, [`Error::WontImplement`] is returned.
+///
+/// [`Error::DeserializeUnexpectedEnd`]: crate::Error::DeserializeUnexpectedEnd
+/// [`Error::WontImplement`]: crate::Error::WontImplement
+///
+/// ```rust
+/// use postcard::raw::RawTail;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// struct Frame<'a> {
+///     channel: u8,
+///     #[serde(borrow)]
+///     payload: RawTail<'a>,
+/// }
+///
+/// let mut buf = [0u8; 8];
+/// let frame = Frame { channel: 2, payload: RawTail(&[0xAA, 0xBB]) };
+/// let used = postcard::to_slice(&frame, &mut buf).unwrap();
+/// assert_eq!(used, &[0x02, 0xAA, 0xBB]);
+///
+/// let out: Frame = postcard::from_bytes(used).unwrap();
+/// assert_eq!(out, frame);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RawTail<'a>(pub &'a [u8]);
+
+/// Writes bytes as a tuple, which has no length prefix
+struct Unprefixed<'a>(&'a [u8]);
+
+impl Serialize for Unprefixed<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut tup = serializer.serialize_tuple(self.0.len())?;
+        for byte in self.0 {
+            tup.serialize_element(byte)?;
+        }
+        tup.end()
+    }
+}
+
+impl Serialize for RawTail<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_newtype_struct(RAW_TAIL, &Unprefixed(self.0))
+    }
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for RawTail<'a> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TailVisitor;
+
+        impl<'de> Visitor<'de> for TailVisitor {
+            type Value = RawTail<'de>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("the remaining bytes")
+            }
+
+            fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+                Ok(RawTail(v))
+            }
+
+            // Other formats see a regular newtype struct of bytes
+            fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                <&'de [u8]>::deserialize(deserializer).map(RawTail)
+            }
+        }
+
+        deserializer.deserialize_newtype_struct(RAW_TAIL, TailVisitor)
+    }
+}
+
+/// Deserialize a byte array (`[u8; N]`), borrowing it from the input instead of copying.
+///
+/// This is intended to be used with `#[serde(deserialize_with = "...")]`, for fixed
+/// size fields like keys or nonces. The wire format is the same as for `[u8; N]`, so
+/// the field is serialized as usual.
+///
+/// With formats other than postcard, which can't lend the array from their input,
+/// deserializing fails with an "invalid type" error.
+///
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// struct Header<'a> {
+///     version: u8,
+///     #[serde(borrow, deserialize_with = "postcard::raw::deserialize_borrowed_byte_array")]
+///     nonce: &'a [u8; 4],
+/// }
+///
+/// let input = [0x01, 0xAA, 0xBB, 0xCC, 0xDD];
+/// let out: Header = postcard::from_bytes(&input).unwrap();
+/// assert_eq!(out.nonce, &[0xAA, 0xBB, 0xCC, 0xDD]);
+/// assert_eq!(out.nonce.as_ptr(), input[1..].as_ptr());
+/// ```
+pub fn deserialize_borrowed_byte_array<'de, D, const N: usize>(
+    deserializer: D,
+) -> Result<&'de [u8; N], D::Error>
+where
+    D: Deserializer<'de>,
+{
+    struct ArrayVisitor<const N: usize>;
+
+    impl<'de, const N: usize> Visitor<'de> for ArrayVisitor<N> {
+        type Value = &'de [u8; N];
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            write!(formatter, "{} borrowed bytes", N)
+        }
+
+        fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+        where
+            E: serde::de::Error,
+        {
+            <&[u8; N]>::try_from(v).map_err(|_| E::invalid_length(v.len(), &self))
+        }
+    }
+
+    deserializer.deserialize_tuple_struct(BYTE_ARRAY, N, ArrayVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RawTail;
+    use crate::Error;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Packet<'a> {
+        kind: u16,
+        #[serde(borrow)]
+        rest: RawTail<'a>,
+    }
+
+    #[test]
+    fn raw_tail() {
+        let mut buf = [0u8; 16];
+        let input = Packet {
+            kind: 300,
+            rest: RawTail(&[0x00, 0x01, 0x80, 0xFF]),
+        };
+        let used = crate::to_slice(&input, &mut buf).unwrap();
+        assert_eq!(used, &[0xAC, 0x02, 0x00, 0x01, 0x80, 0xFF]);
+        assert_eq!(crate::from_bytes::<Packet<'_>>(used), Ok(input));
+
+        // Everything after the known fields ends up in the tail, including nothing at all
+        let (out, rest) = crate::take_from_bytes::<Packet<'_>>(&[0x01]).unwrap();
+        assert!(out.rest.0.is_empty());
+        assert!(rest.is_empty());
+
+        // A field following the tail finds no input left
+        let res = crate::from_bytes::<(RawTail<'_>, u8)>(&[0x01, 0x02]);
+        assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Keyed<'a> {
+        #[serde(borrow, deserialize_with = "super::deserialize_borrowed_byte_array")]
+        key: &'a [u8; 16],
+        counter: u32,
+    }
+
+    #[test]
+    fn borrowed_byte_array() {
+        let mut buf = [0u8; 32];
+        let key = [0x5A; 16];
+        let input = Keyed {
+            key: &key,
+            counter: 300,
+        };
+        let used = crate::to_slice(&input, &mut buf).unwrap();
+        assert_eq!(used.len(), 16 + 2);
+        assert_eq!(&used[..16], &key);
+
+        let used: &[u8] = used;
+        let out: Keyed<'_> = crate::from_bytes(used).unwrap();
+        assert_eq!(out, input);
+        // The array points into the input, rather than being a copy
+        assert_eq!(out.key.as_ptr(), used.as_ptr());
+
+        let res = crate::from_bytes::<Keyed<'_>>(&used[..15]);
+        assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+    }
+}
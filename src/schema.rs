@@ -280,3 +280,344 @@ impl Schema for alloc::string::String {
         ty: &SdmTy::String,
     };
 }
+
+/// Assert that two types share the same wire format.
+///
+/// This compares the [`Schema`] of `A` and `B` structurally, ignoring the
+/// names of types, fields, and variants (which never appear on the wire), and
+/// panics if the two could be encoded differently. As this is a `const fn`,
+/// it may be used to check compatibility at compile time:
+///
+/// ```rust
+/// use postcard::experimental::schema::{assert_same_wire_format, Schema};
+///
+/// #[derive(Schema)]
+/// struct Old {
+///     a: u32,
+///     b: bool,
+/// }
+///
+/// #[derive(Schema)]
+/// struct New {
+///     first: u32,
+///     second: bool,
+/// }
+///
+/// const _: () = assert_same_wire_format::<Old, New>();
+/// ```
+///
+/// NOTE: The comparison is conservative. Structs, tuples, and tuple structs are
+/// compared field by field, so two types which nest their fields differently are
+/// reported as incompatible, even if they happen to produce the same bytes.
+#[cfg(feature = "experimental-derive")]
+pub const fn assert_same_wire_format<A: Schema + ?Sized, B: Schema + ?Sized>() {
+    if !wire_compatible(A::SCHEMA, B::SCHEMA) {
+        panic!("types do not share the same postcard wire format");
+    }
+}
+
+/// Returns whether two schemas share the same wire format.
+///
+/// See [`assert_same_wire_format`] for the rules used when comparing.
+pub const fn wire_compatible(a: &'static NamedType, b: &'static NamedType) -> bool {
+    match (Fields::of_type(a), Fields::of_type(b)) {
+        (Fields::One(a), Fields::One(b)) => leaf_compatible(a.ty, b.ty),
+        (a, b) => fields_compatible(a, b),
+    }
+}
+
+/// The fields of a product type, regardless of how they are named.
+///
+/// Types that are not products are treated as a single field, and products with a
+/// single field are treated as that field, as they share its wire format.
+#[derive(Clone, Copy)]
+enum Fields {
+    One(&'static NamedType),
+    Types(&'static [&'static NamedType]),
+    Values(&'static [&'static NamedValue]),
+}
+
+impl Fields {
+    const fn of_type(ty: &'static NamedType) -> Self {
+        match ty.ty {
+            SdmTy::Unit | SdmTy::UnitStruct => Fields::Types(&[]),
+            SdmTy::NewtypeStruct(inner) => Fields::of_type(inner),
+            SdmTy::Tuple([inner]) | SdmTy::TupleStruct([inner]) => Fields::of_type(inner),
+            SdmTy::Struct([inner]) => Fields::of_type(inner.ty),
+            SdmTy::Tuple(tys) | SdmTy::TupleStruct(tys) => Fields::Types(tys),
+            SdmTy::Struct(vals) => Fields::Values(vals),
+            _ => Fields::One(ty),
+        }
+    }
+
+    /// Returns `None` if `ty` is not a variant.
+    const fn of_variant(ty: &'static SdmTy) -> Option<Self> {
+        match ty {
+            SdmTy::UnitVariant => Some(Fields::Types(&[])),
+            SdmTy::NewtypeVariant(inner) => Some(Fields::of_type(inner)),
+            SdmTy::TupleVariant(tys) => Some(Fields::Types(tys)),
+            SdmTy::StructVariant(vals) => Some(Fields::Values(vals)),
+            _ => None,
+        }
+    }
+
+    const fn len(&self) -> usize {
+        match self {
+            Fields::One(_) => 1,
+            Fields::Types(tys) => tys.len(),
+            Fields::Values(vals) => vals.len(),
+        }
+    }
+
+    const fn get(&self, idx: usize) -> &'static NamedType {
+        match self {
+            Fields::One(ty) => ty,
+            Fields::Types(tys) => tys[idx],
+            Fields::Values(vals) => vals[idx].ty,
+        }
+    }
+}
+
+const fn fields_compatible(a: Fields, b: Fields) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut idx = 0;
+    while idx < a.len() {
+        if !wire_compatible(a.get(idx), b.get(idx)) {
+            return false;
+        }
+        idx += 1;
+    }
+    true
+}
+
+const fn leaf_compatible(a: &'static SdmTy, b: &'static SdmTy) -> bool {
+    match (a, b) {
+        (SdmTy::Bool, SdmTy::Bool)
+        | (SdmTy::I8, SdmTy::I8)
+        | (SdmTy::U8, SdmTy::U8)
+        | (SdmTy::F32, SdmTy::F32)
+        | (SdmTy::F64, SdmTy::F64)
+        | (SdmTy::Char, SdmTy::Char)
+        | (SdmTy::String, SdmTy::String)
+        | (SdmTy::ByteArray, SdmTy::ByteArray) => true,
+        (SdmTy::Varint(a), SdmTy::Varint(b)) => varint_compatible(a, b),
+        (SdmTy::Option(a), SdmTy::Option(b)) | (SdmTy::Seq(a), SdmTy::Seq(b)) => {
+            wire_compatible(a, b)
+        }
+        (SdmTy::Map { key: ak, val: av }, SdmTy::Map { key: bk, val: bv }) => {
+            wire_compatible(ak, bk) && wire_compatible(av, bv)
+        }
+        (SdmTy::Enum(a), SdmTy::Enum(b)) => {
+            if a.len() != b.len() {
+                return false;
+            }
+            let mut idx = 0;
+            while idx < a.len() {
+                match (Fields::of_variant(a[idx].ty), Fields::of_variant(b[idx].ty)) {
+                    (Some(a), Some(b)) if fields_compatible(a, b) => {}
+                    _ => return false,
+                }
+                idx += 1;
+            }
+            true
+        }
+        _ => false,
+    }
+}
+
+const fn varint_compatible(a: &Varint, b: &Varint) -> bool {
+    matches!(
+        (a, b),
+        (Varint::I16, Varint::I16)
+            | (Varint::I32, Varint::I32)
+            | (Varint::I64, Varint::I64)
+            | (Varint::I128, Varint::I128)
+            | (Varint::U16, Varint::U16)
+            | (Varint::U32, Varint::U32)
+            | (Varint::U64, Varint::U64)
+            | (Varint::U128, Varint::U128)
+            | (Varint::Usize, Varint::Usize)
+            | (Varint::Isize, Varint::Isize)
+    )
+}
+
+/// A type with a 64-bit hash of its [`Schema`], implemented for all types with a schema.
+///
+/// Two types with the same wire format (see [`assert_same_wire_format`]) have the
+/// same hash. This is used by [`to_vec_versioned()`] and [`from_bytes_versioned()`] to
+/// reject messages created from a different version of a type.
+pub trait SchemaHash {
+    /// The hash of the schema of this type
+    const SCHEMA_HASH: u64;
+}
+
+impl<T: Schema + ?Sized> SchemaHash for T {
+    const SCHEMA_HASH: u64 = schema_hash(T::SCHEMA);
+}
+
+/// Returns a 64-bit hash of the wire format of a schema.
+///
+/// Like [`wire_compatible`], this ignores the names of types, fields, and variants,
+/// so that schemas which are wire compatible have the same hash.
+pub const fn schema_hash(ty: &'static NamedType) -> u64 {
+    hash_type(FNV_OFFSET, ty)
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Mix a single byte into a FNV-1a hash
+const fn mix(hash: u64, byte: u8) -> u64 {
+    (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+}
+
+const fn mix_len(mut hash: u64, len: usize) -> u64 {
+    let bytes = (len as u64).to_le_bytes();
+    let mut idx = 0;
+    while idx < bytes.len() {
+        hash = mix(hash, bytes[idx]);
+        idx += 1;
+    }
+    hash
+}
+
+const fn hash_type(hash: u64, ty: &'static NamedType) -> u64 {
+    match Fields::of_type(ty) {
+        Fields::One(ty) => hash_leaf(hash, ty.ty),
+        fields => hash_fields(hash, fields),
+    }
+}
+
+const fn hash_fields(hash: u64, fields: Fields) -> u64 {
+    let mut hash = mix_len(mix(hash, 0x80), fields.len());
+    let mut idx = 0;
+    while idx < fields.len() {
+        hash = hash_type(hash, fields.get(idx));
+        idx += 1;
+    }
+    hash
+}
+
+const fn hash_leaf(hash: u64, ty: &'static SdmTy) -> u64 {
+    match ty {
+        SdmTy::Bool => mix(hash, 0x01),
+        SdmTy::I8 => mix(hash, 0x02),
+        SdmTy::U8 => mix(hash, 0x03),
+        SdmTy::F32 => mix(hash, 0x04),
+        SdmTy::F64 => mix(hash, 0x05),
+        SdmTy::Char => mix(hash, 0x06),
+        SdmTy::String => mix(hash, 0x07),
+        SdmTy::ByteArray => mix(hash, 0x08),
+        SdmTy::Varint(v) => mix(mix(hash, 0x09), varint_tag(v)),
+        SdmTy::Option(inner) => hash_type(mix(hash, 0x0A), inner),
+        SdmTy::Seq(inner) => hash_type(mix(hash, 0x0B), inner),
+        SdmTy::Map { key, val } => hash_type(hash_type(mix(hash, 0x0C), key), val),
+        SdmTy::Enum(variants) => {
+            let mut hash = mix_len(mix(hash, 0x0D), variants.len());
+            let mut idx = 0;
+            while idx < variants.len() {
+                hash = match Fields::of_variant(variants[idx].ty) {
+                    Some(fields) => hash_fields(hash, fields),
+                    // Not a valid variant, which is never wire compatible with anything
+                    None => hash_leaf(mix(hash, 0xFF), variants[idx].ty),
+                };
+                idx += 1;
+            }
+            hash
+        }
+        // Product types are handled by `hash_fields`, and variants are only
+        // valid inside of an enum
+        SdmTy::Unit
+        | SdmTy::UnitStruct
+        | SdmTy::NewtypeStruct(_)
+        | SdmTy::Tuple(_)
+        | SdmTy::TupleStruct(_)
+        | SdmTy::Struct(_) => hash,
+        SdmTy::UnitVariant
+        | SdmTy::NewtypeVariant(_)
+        | SdmTy::TupleVariant(_)
+        | SdmTy::StructVariant(_) => mix(hash, 0xFE),
+    }
+}
+
+const fn varint_tag(v: &Varint) -> u8 {
+    match v {
+        Varint::I16 => 0,
+        Varint::I32 => 1,
+        Varint::I64 => 2,
+        Varint::I128 => 3,
+        Varint::U16 => 4,
+        Varint::U32 => 5,
+        Varint::U64 => 6,
+        Varint::U128 => 7,
+        Varint::Usize => 8,
+        Varint::Isize => 9,
+    }
+}
+
+/// Serialize a `T` to a `heapless::Vec<u8>`, prefixed with the 8 byte
+/// [`SchemaHash`] of `T`, in little endian order.
+///
+/// Use [`from_bytes_versioned()`] to check the hash before deserializing the message.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::experimental::schema::{from_bytes_versioned, to_vec_versioned, Schema};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, Schema, Debug, PartialEq)]
+/// struct Reading {
+///     id: u8,
+///     value: u16,
+/// }
+///
+/// let msg = Reading { id: 1, value: 300 };
+/// let ser = to_vec_versioned::<_, 16>(&msg).unwrap();
+/// assert_eq!(ser.len(), 8 + 3);
+/// assert_eq!(from_bytes_versioned(&ser), Ok(msg));
+/// ```
+#[cfg(all(feature = "experimental-derive", feature = "heapless"))]
+#[cfg_attr(
+    doc_cfg,
+    doc(cfg(all(feature = "experimental-derive", feature = "heapless")))
+)]
+pub fn to_vec_versioned<T, const B: usize>(value: &T) -> crate::Result<heapless::Vec<u8, B>>
+where
+    T: Serialize + SchemaHash + ?Sized,
+{
+    use crate::ser_flavors::{Flavor, HVec};
+
+    let mut serializer = crate::Serializer::from_flavor(HVec::<B>::default());
+    serializer
+        .output
+        .try_extend(&T::SCHEMA_HASH.to_le_bytes())?;
+    value.serialize(&mut serializer)?;
+    serializer.output.finalize()
+}
+
+/// Deserialize a message of type `T` from a byte slice, which is prefixed with the
+/// 8 byte [`SchemaHash`] of the type it was serialized from, as created by
+/// [`to_vec_versioned()`]. The unused portion (if any) of the byte slice is not returned.
+///
+/// If the hash does not match the hash of `T`, [`Error::DeserializeSchemaMismatch`] is
+/// returned, and the rest of the message is not deserialized.
+///
+/// [`Error::DeserializeSchemaMismatch`]: crate::Error::DeserializeSchemaMismatch
+#[cfg(feature = "experimental-derive")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "experimental-derive")))]
+pub fn from_bytes_versioned<'a, T>(s: &'a [u8]) -> crate::Result<T>
+where
+    T: serde::Deserialize<'a> + SchemaHash,
+{
+    if s.len() < 8 {
+        return Err(crate::Error::DeserializeUnexpectedEnd);
+    }
+    let (hash, msg) = s.split_at(8);
+    if hash != T::SCHEMA_HASH.to_le_bytes() {
+        return Err(crate::Error::DeserializeSchemaMismatch);
+    }
+    crate::from_bytes(msg)
+}
@@ -88,6 +88,7 @@
 
 use crate::error::{Error, Result};
 use cobs::{EncoderState, PushResult};
+use core::convert::TryFrom;
 use core::marker::PhantomData;
 use core::ops::Index;
 use core::ops::IndexMut;
@@ -128,6 +129,21 @@ pub trait Flavor {
 
     /// Finalize the serialization process
     fn finalize(self) -> Result<Self::Output>;
+
+    /// Write the string table reference in front of a string, for flavors that intern
+    /// repeated strings, such as [`Interned`].
+    ///
+    /// This is called with the string before serializing it, or with `None` for strings
+    /// that can't be interned. Returns `Ok(true)` if the string was written as a
+    /// reference to an earlier string, and `Ok(false)` if the string should be written
+    /// as usual. By default, nothing is written, and `Ok(false)` is returned.
+    ///
+    /// Modification flavors should NOT forward this, as the flavor interning strings
+    /// must see the same bytes as the deserializer.
+    #[inline(always)]
+    fn try_push_interned(&mut self, _s: Option<&str>) -> Result<bool> {
+        Ok(false)
+    }
 }
 
 ////////////////////////////////////////
@@ -154,6 +170,11 @@ impl<'a> Slice<'a> {
             _pl: PhantomData,
         }
     }
+
+    /// The number of bytes written so far
+    pub(crate) fn written(&self) -> usize {
+        (self.cursor as usize) - (self.start as usize)
+    }
 }
 
 impl<'a> Flavor for Slice<'a> {
@@ -188,7 +209,7 @@ impl<'a> Flavor for Slice<'a> {
     }
 
     fn finalize(self) -> Result<Self::Output> {
-        let used = (self.cursor as usize) - (self.start as usize);
+        let used = self.written();
         let sli = unsafe { core::slice::from_raw_parts_mut(self.start, used) };
         Ok(sli)
     }
@@ -250,6 +271,68 @@ where
     }
 }
 
+/// The `Chunked` flavor is a storage flavor, collecting the serialized bytes in a
+/// (small) caller-supplied buffer, and handing them to a sink each time it is full.
+///
+/// Serialization pauses at every chunk boundary while the sink runs, e.g. to start a
+/// DMA transfer and wait for it to finish, so a message can be sent without a buffer
+/// large enough to hold all of it. Every chunk except the last one fills the whole
+/// buffer. Errors returned by the sink stop serialization. See
+/// [`to_chunks()`](crate::to_chunks).
+pub struct Chunked<'a, S> {
+    buf: &'a mut [u8],
+    len: usize,
+    sink: S,
+}
+
+impl<'a, S> Chunked<'a, S>
+where
+    S: FnMut(&[u8]) -> Result<()>,
+{
+    /// Create a new `Chunked` flavor, with chunks of `buf.len()` bytes
+    pub fn new(buf: &'a mut [u8], sink: S) -> Self {
+        Self { buf, len: 0, sink }
+    }
+}
+
+impl<'a, S> Flavor for Chunked<'a, S>
+where
+    S: FnMut(&[u8]) -> Result<()>,
+{
+    type Output = ();
+
+    #[inline]
+    fn try_push(&mut self, data: u8) -> Result<()> {
+        self.try_extend(&[data])
+    }
+
+    fn try_extend(&mut self, mut data: &[u8]) -> Result<()> {
+        if self.buf.is_empty() && !data.is_empty() {
+            return Err(Error::SerializeBufferFull);
+        }
+        while !data.is_empty() {
+            // A full chunk is only handed out once more bytes follow, so that the
+            // last chunk is never empty
+            if self.len == self.buf.len() {
+                (self.sink)(self.buf)?;
+                self.len = 0;
+            }
+            let take = data.len().min(self.buf.len() - self.len);
+            self.buf[self.len..][..take].copy_from_slice(&data[..take]);
+            self.len += take;
+            data = &data[take..];
+        }
+        Ok(())
+    }
+
+    fn finalize(mut self) -> Result<()> {
+        if self.len > 0 {
+            (self.sink)(&self.buf[..self.len])?;
+        }
+        Ok(())
+    }
+}
+
 /// Support for the [embedded-io] traits
 #[cfg(feature = "embedded-io")]
 pub mod eio {
@@ -361,7 +444,9 @@ mod heapless_vec {
     use super::Flavor;
     use super::Index;
     use super::IndexMut;
+    use crate::varint::{varint_max, varint_usize};
     use crate::{Error, Result};
+    use heapless::spsc::Producer;
     use heapless::Vec;
 
     ////////////////////////////////////////
@@ -382,6 +467,16 @@ mod heapless_vec {
         pub fn new() -> Self {
             Self::default()
         }
+
+        /// The number of bytes written so far
+        pub(crate) fn len(&self) -> usize {
+            self.vec.len()
+        }
+
+        /// Discard any bytes written after the first `len` bytes
+        pub(crate) fn truncate(&mut self, len: usize) {
+            self.vec.truncate(len)
+        }
     }
 
     impl<const B: usize> Flavor for HVec<B> {
@@ -417,6 +512,126 @@ mod heapless_vec {
             &mut self.vec[idx]
         }
     }
+
+    ////////////////////////////////////////
+    // Interned
+    ////////////////////////////////////////
+
+    /// The `Interned` flavor is a storage flavor like [`HVec`], which deduplicates
+    /// repeated strings. Only the first occurrence of a string is written, and later
+    /// occurrences refer back to it. See [`to_vec_interned()`](crate::to_vec_interned).
+    ///
+    /// Each string is prefixed with a varint tag: `0` for a new string that was added
+    /// to the string table, `1` for a new string that was not (e.g. as the table of
+    /// `N` strings is full), followed by the string as usual. Any other tag `n` refers
+    /// to the string at index `n - 2` of the table. Only the default
+    /// [`Config`](crate::Config) is supported.
+    #[derive(Default)]
+    pub struct Interned<const B: usize, const N: usize> {
+        vec: Vec<u8, B>,
+        /// The position and length of the strings in the table, within `vec`
+        table: Vec<(usize, usize), N>,
+    }
+
+    impl<const B: usize, const N: usize> Interned<B, N> {
+        /// Create a new, currently empty, `Interned` flavor
+        pub fn new() -> Self {
+            Self::default()
+        }
+    }
+
+    impl<const B: usize, const N: usize> Flavor for Interned<B, N> {
+        type Output = Vec<u8, B>;
+
+        #[inline(always)]
+        fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+            self.vec
+                .extend_from_slice(data)
+                .map_err(|_| Error::SerializeBufferFull)
+        }
+
+        #[inline(always)]
+        fn try_push(&mut self, data: u8) -> Result<()> {
+            self.vec.push(data).map_err(|_| Error::SerializeBufferFull)
+        }
+
+        fn finalize(self) -> Result<Vec<u8, B>> {
+            Ok(self.vec)
+        }
+
+        fn try_push_interned(&mut self, s: Option<&str>) -> Result<bool> {
+            let mut buf = [0u8; varint_max::<usize>()];
+            let s = match s {
+                Some(s) => s,
+                None => return self.try_push(1).map(|_| false),
+            };
+
+            let vec = &self.vec;
+            let found = self
+                .table
+                .iter()
+                .position(|&(pos, len)| &vec[pos..pos + len] == s.as_bytes());
+            if let Some(idx) = found {
+                self.try_extend(varint_usize(idx + 2, &mut buf))?;
+                return Ok(true);
+            }
+
+            // The string follows after the tag and its length
+            let pos = self.vec.len() + 1 + varint_usize(s.len(), &mut buf).len();
+            if self.table.push((pos, s.len())).is_ok() {
+                self.try_push(0)?;
+            } else {
+                self.try_push(1)?;
+            }
+            Ok(false)
+        }
+    }
+
+    ////////////////////////////////////////
+    // SpscProducer
+    ////////////////////////////////////////
+
+    /// The `SpscProducer` flavor is a storage flavor, writing the serialized bytes directly
+    /// into a lock-free `heapless::spsc::Queue`, through its `Producer`. This avoids
+    /// serializing to an intermediate buffer before copying into the queue.
+    ///
+    /// The queue handles wrapping around its end. When the queue is full, serialization
+    /// stops with [`Error::SerializeBufferFull`]. The bytes enqueued before that point
+    /// are NOT removed again, and may already have been read by the consumer. Use
+    /// [`to_spsc()`](crate::to_spsc) to check for enough free space up front instead.
+    ///
+    /// The output of this flavor is the number of bytes that were enqueued.
+    pub struct SpscProducer<'a, 'q, const N: usize> {
+        producer: &'a mut Producer<'q, u8, N>,
+        written: usize,
+    }
+
+    impl<'a, 'q, const N: usize> SpscProducer<'a, 'q, N> {
+        /// Create a new `SpscProducer` flavor, enqueueing bytes into the given producer
+        pub fn new(producer: &'a mut Producer<'q, u8, N>) -> Self {
+            Self {
+                producer,
+                written: 0,
+            }
+        }
+    }
+
+    impl<'a, 'q, const N: usize> Flavor for SpscProducer<'a, 'q, N> {
+        type Output = usize;
+
+        #[inline(always)]
+        fn try_push(&mut self, data: u8) -> Result<()> {
+            self.producer
+                .enqueue(data)
+                .map_err(|_| Error::SerializeBufferFull)?;
+            self.written += 1;
+            Ok(())
+        }
+
+        fn finalize(self) -> Result<usize> {
+            Ok(self.written)
+        }
+    }
 }
 
 #[cfg(feature = "use-std")]
@@ -559,6 +774,156 @@ where
     }
 }
 
+////////////////////////////////////////
+// PKCS#7
+////////////////////////////////////////
+
+/// The `Pkcs7` flavor pads the serialized data to a multiple of `BLOCK` bytes, using
+/// [PKCS#7] padding, e.g. before encrypting it with a block cipher such as AES.
+///
+/// The padding consists of `n` bytes with the value `n`, where `n` is between `1` and
+/// `BLOCK`, chosen so that the total length is a multiple of `BLOCK`. Data which is
+/// already a multiple of `BLOCK` bytes is padded with a full block, so that the padding
+/// can always be removed unambiguously. `BLOCK` must be between `1` and `255`.
+///
+/// [PKCS#7]: https://www.rfc-editor.org/rfc/rfc5652#section-6.3
+///
+/// ```rust
+/// use postcard::{
+///     serialize_with_flavor,
+///     ser_flavors::{Pkcs7, Slice},
+/// };
+///
+/// let mut buf = [0u8; 32];
+/// let res = serialize_with_flavor(
+///     "Hi!",
+///     Pkcs7::<_, 8>::new(Slice::new(&mut buf)),
+/// ).unwrap();
+///
+/// assert_eq!(res, &[0x03, b'H', b'i', b'!', 0x04, 0x04, 0x04, 0x04]);
+/// ```
+pub struct Pkcs7<B, const BLOCK: usize>
+where
+    B: Flavor,
+{
+    flav: B,
+    len: usize,
+}
+
+impl<B, const BLOCK: usize> Pkcs7<B, BLOCK>
+where
+    B: Flavor,
+{
+    const VALID_BLOCK: () = assert!(BLOCK > 0 && BLOCK < 256, "BLOCK must be 1..=255");
+
+    /// Create a new `Pkcs7` modifier Flavor.
+    pub fn new(bee: B) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::VALID_BLOCK;
+        Self { flav: bee, len: 0 }
+    }
+}
+
+impl<B, const BLOCK: usize> Flavor for Pkcs7<B, BLOCK>
+where
+    B: Flavor,
+{
+    type Output = <B as Flavor>::Output;
+
+    #[inline(always)]
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        self.flav.try_extend(data)?;
+        self.len += data.len();
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn try_push(&mut self, data: u8) -> Result<()> {
+        self.flav.try_push(data)?;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn finalize(mut self) -> Result<Self::Output> {
+        let pad = BLOCK - (self.len % BLOCK);
+        for _ in 0..pad {
+            self.flav.try_push(pad as u8)?;
+        }
+        self.flav.finalize()
+    }
+}
+
+////////////////////////////////////////
+// Length Footer
+////////////////////////////////////////
+
+/// The `LengthFooter` flavor appends the length of the serialized data, as a fixed
+/// four byte little endian `u32`, after the data.
+///
+/// This lets a reader that starts from the end of the frame, e.g. one reading an
+/// append-only log backwards, find where the frame starts. Use
+/// [`from_bytes_footer_len()`](crate::from_bytes_footer_len) to read it back. Data
+/// longer than `u32::MAX` bytes fails with [`Error::SerializeBufferFull`].
+///
+/// ```rust
+/// use postcard::{
+///     serialize_with_flavor,
+///     ser_flavors::{LengthFooter, Slice},
+/// };
+///
+/// let mut buf = [0u8; 32];
+/// let res = serialize_with_flavor(
+///     "Hi!",
+///     LengthFooter::new(Slice::new(&mut buf)),
+/// ).unwrap();
+///
+/// assert_eq!(res, &[0x03, b'H', b'i', b'!', 0x04, 0x00, 0x00, 0x00]);
+/// ```
+pub struct LengthFooter<B>
+where
+    B: Flavor,
+{
+    flav: B,
+    len: usize,
+}
+
+impl<B> LengthFooter<B>
+where
+    B: Flavor,
+{
+    /// Create a new `LengthFooter` modifier Flavor.
+    pub fn new(bee: B) -> Self {
+        Self { flav: bee, len: 0 }
+    }
+}
+
+impl<B> Flavor for LengthFooter<B>
+where
+    B: Flavor,
+{
+    type Output = <B as Flavor>::Output;
+
+    #[inline(always)]
+    fn try_extend(&mut self, data: &[u8]) -> Result<()> {
+        self.flav.try_extend(data)?;
+        self.len += data.len();
+        Ok(())
+    }
+
+    #[inline(always)]
+    fn try_push(&mut self, data: u8) -> Result<()> {
+        self.flav.try_push(data)?;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn finalize(mut self) -> Result<Self::Output> {
+        let len = u32::try_from(self.len).map_err(|_| Error::SerializeBufferFull)?;
+        self.flav.try_extend(&len.to_le_bytes())?;
+        self.flav.finalize()
+    }
+}
+
 ////////////////////////////////////////
 // CRC
 ////////////////////////////////////////
@@ -638,8 +1003,8 @@ pub mod crc {
                     ///
                     /// When successful, this function returns the slice containing the
                     /// serialized and encoded message.
-                    pub fn [<to_slice_ $int>]<'a, 'b, T>(
-                        value: &'b T,
+                    pub fn [<to_slice_ $int>]<'a, T>(
+                        value: &T,
                         buf: &'a mut [u8],
                         digest: Digest<'a, $int>,
                     ) -> Result<&'a mut [u8]>
@@ -1,9 +1,14 @@
+use crate::config::Config;
+#[cfg(feature = "heapless")]
+use crate::config::EnumEncoding;
 use crate::error::{Error, Result};
-use crate::ser::flavors::{Cobs, Flavor, Slice};
+use crate::ser::flavors::{Chunked, Cobs, Flavor, Slice};
+#[cfg(feature = "heapless")]
+use crate::ser::flavors::{LengthFooter, Pkcs7};
 use serde::Serialize;
 
 #[cfg(feature = "heapless")]
-use crate::ser::flavors::HVec;
+use crate::ser::flavors::{HVec, Interned, SpscProducer};
 
 #[cfg(feature = "heapless")]
 use heapless::Vec;
@@ -18,6 +23,8 @@ use crate::ser::serializer::Serializer;
 
 pub mod flavors;
 pub(crate) mod serializer;
+#[cfg(feature = "alloc")]
+mod sorted;
 
 /// Serialize a `T` to the given slice, with the resulting slice containing
 /// data in a serialized then COBS encoded format. The terminating sentinel
@@ -89,6 +96,42 @@ where
     serialize_with_flavor::<T, Slice<'a>, &'a mut [u8]>(value, Slice::new(buf))
 }
 
+/// Serialize a `T` to the given slice, reporting how much was written if the
+/// slice is too small.
+///
+/// This behaves like [`to_slice()`], except that when the slice fills up,
+/// [`Error::SerializeBufferFullAt`] is returned instead of [`Error::SerializeBufferFull`],
+/// containing the number of bytes at the start of the slice that were written. These
+/// bytes are the correct start of the message, so they may be flushed to the transport
+/// before the rest of the message is produced.
+///
+/// NOTE: Serialization stops at the first item (such as an integer or string) that does
+/// not fit, so some space may remain unused at the end of the slice.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{to_slice_partial, Error};
+/// let mut buf = [0u8; 4];
+///
+/// let res = to_slice_partial(&(1u8, "Hi!"), &mut buf);
+/// assert_eq!(res, Err(Error::SerializeBufferFullAt { written: 2 }));
+/// assert_eq!(&buf[..2], &[0x01, 0x03]);
+/// ```
+pub fn to_slice_partial<'a, T>(value: &T, buf: &'a mut [u8]) -> Result<&'a mut [u8]>
+where
+    T: Serialize + ?Sized,
+{
+    let mut serializer = Serializer::from_flavor(Slice::new(buf));
+    match value.serialize(&mut serializer) {
+        Ok(()) => serializer.output.finalize(),
+        Err(Error::SerializeBufferFull) => Err(Error::SerializeBufferFullAt {
+            written: serializer.output.written(),
+        }),
+        Err(e) => Err(e),
+    }
+}
+
 /// Serialize a `T` to a `heapless::Vec<u8>`, with the `Vec` containing
 /// data in a serialized then COBS encoded format. The terminating sentinel
 /// `0x00` byte is included in the output `Vec`.
@@ -158,6 +201,101 @@ where
     serialize_with_flavor::<T, HVec<B>, Vec<u8, B>>(value, HVec::default())
 }
 
+/// Serialize a `T` directly into a `heapless::spsc::Queue`, through its `Producer`,
+/// returning the number of bytes that were enqueued.
+///
+/// The size of the message is checked against the free space of the queue first. If
+/// the queue can't accept the whole message, [`Error::SerializeBufferFull`] is returned,
+/// and nothing is enqueued, so the consumer never sees a partial message.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::to_spsc;
+/// use heapless::spsc::Queue;
+///
+/// let mut queue: Queue<u8, 8> = Queue::new();
+/// let (mut producer, mut consumer) = queue.split();
+///
+/// assert_eq!(to_spsc(&(1u8, 300u16), &mut producer), Ok(3));
+/// assert_eq!(consumer.dequeue(), Some(0x01));
+/// assert_eq!(consumer.dequeue(), Some(0xAC));
+/// assert_eq!(consumer.dequeue(), Some(0x02));
+/// ```
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub fn to_spsc<T, const N: usize>(
+    value: &T,
+    producer: &mut heapless::spsc::Producer<'_, u8, N>,
+) -> Result<usize>
+where
+    T: Serialize + ?Sized,
+{
+    if serialized_size(value)? > producer.capacity() - producer.len() {
+        return Err(Error::SerializeBufferFull);
+    }
+    serialize_with_flavor::<T, SpscProducer<'_, '_, N>, usize>(value, SpscProducer::new(producer))
+}
+
+/// Serialize a `T` to a `heapless::Vec<u8>`, padded to a multiple of `BLOCK` bytes
+/// with PKCS#7 padding.
+///
+/// See the [`Pkcs7`](crate::ser_flavors::Pkcs7) flavor for a description of the padding,
+/// and [`from_bytes_padded()`](crate::from_bytes_padded) for removing it again.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::to_vec_padded;
+/// use heapless::Vec;
+/// use core::ops::Deref;
+///
+/// let ser: Vec<u8, 32> = to_vec_padded::<_, 32, 4>(&(1u8, 2u8)).unwrap();
+/// assert_eq!(ser.deref(), &[0x01, 0x02, 0x02, 0x02]);
+///
+/// let ser: Vec<u8, 32> = to_vec_padded::<_, 32, 4>(&(1u8, 2u8, 3u8, 4u8)).unwrap();
+/// assert_eq!(ser.deref(), &[0x01, 0x02, 0x03, 0x04, 0x04, 0x04, 0x04, 0x04]);
+/// ```
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub fn to_vec_padded<T, const B: usize, const BLOCK: usize>(value: &T) -> Result<Vec<u8, B>>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_with_flavor::<T, Pkcs7<HVec<B>, BLOCK>, Vec<u8, B>>(
+        value,
+        Pkcs7::new(HVec::default()),
+    )
+}
+
+/// Serialize a `T` to a `heapless::Vec<u8>`, followed by its length as a four byte
+/// little endian `u32` footer.
+///
+/// See the [`LengthFooter`](crate::ser_flavors::LengthFooter) flavor for details, and
+/// [`from_bytes_footer_len()`](crate::from_bytes_footer_len) for reading it back.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::to_vec_footer_len;
+/// use heapless::Vec;
+/// use core::ops::Deref;
+///
+/// let ser: Vec<u8, 32> = to_vec_footer_len(&(1u8, 300u16)).unwrap();
+/// assert_eq!(ser.deref(), &[0x01, 0xAC, 0x02, 0x03, 0x00, 0x00, 0x00]);
+/// ```
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub fn to_vec_footer_len<T, const B: usize>(value: &T) -> Result<Vec<u8, B>>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_with_flavor::<T, LengthFooter<HVec<B>>, Vec<u8, B>>(
+        value,
+        LengthFooter::new(HVec::default()),
+    )
+}
+
 /// Serialize a `T` to a `std::vec::Vec<u8>`.
 ///
 /// ## Example
@@ -266,7 +404,7 @@ where
 /// let vec = to_extend("Hi!", ser).unwrap();
 /// assert_eq!(&vec[0..5], &[0x01, 0x03, b'H', b'i', b'!']);
 /// ```
-pub fn to_extend<'a, T, W>(value: &'a T, writer: W) -> Result<W>
+pub fn to_extend<T, W>(value: &T, writer: W) -> Result<W>
 where
     T: Serialize + ?Sized,
     W: core::iter::Extend<u8>,
@@ -287,7 +425,7 @@ where
 /// assert_eq!(&buf[0..5], &[0x01, 0x03, b'H', b'i', b'!']);
 /// ```
 #[cfg(feature = "embedded-io")]
-pub fn to_eio<'b, T, W>(value: &'b T, writer: W) -> Result<W>
+pub fn to_eio<T, W>(value: &T, writer: W) -> Result<W>
 where
     T: Serialize + ?Sized,
     W: embedded_io::blocking::Write,
@@ -308,7 +446,7 @@ where
 /// assert_eq!(&buf[0..5], &[0x01, 0x03, b'H', b'i', b'!']);
 /// ```
 #[cfg(feature = "use-std")]
-pub fn to_io<'b, T, W>(value: &'b T, writer: W) -> Result<W>
+pub fn to_io<T, W>(value: &T, writer: W) -> Result<W>
 where
     T: Serialize + ?Sized,
     W: std::io::Write,
@@ -339,8 +477,8 @@ where
 #[cfg(feature = "use-crc")]
 #[cfg_attr(doc_cfg, doc(cfg(feature = "use-crc")))]
 #[inline]
-pub fn to_slice_crc32<'a, 'b, T>(
-    value: &'b T,
+pub fn to_slice_crc32<'a, T>(
+    value: &T,
     buf: &'a mut [u8],
     digest: crc::Digest<'a, u32>,
 ) -> Result<&'a mut [u8]>
@@ -450,6 +588,303 @@ where
     flavors::crc::to_allocvec_u32(value, digest)
 }
 
+/// Serialize a `T` to the given slice, using a non-default [`Config`].
+///
+/// When successful, this function returns the slice containing the
+/// serialized message
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{to_slice_with_config, Config, LengthEncoding};
+/// let mut buf = [0u8; 32];
+///
+/// let config = Config::new().length_encoding(LengthEncoding::Fixed);
+/// let used = to_slice_with_config("Hi!", &mut buf, config).unwrap();
+/// assert_eq!(used, &[0x03, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, b'H', b'i', b'!']);
+/// ```
+pub fn to_slice_with_config<'a, 'b, T>(
+    value: &'b T,
+    buf: &'a mut [u8],
+    config: Config,
+) -> Result<&'a mut [u8]>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_with_config::<T, Slice<'a>, &'a mut [u8]>(value, Slice::new(buf), config)
+}
+
+/// Serialize a `T` to a `heapless::Vec<u8>`, using a non-default [`Config`].
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{to_vec_with_config, Config, IntEncoding};
+/// use heapless::Vec;
+/// use core::ops::Deref;
+///
+/// let config = Config::new().int_encoding(IntEncoding::Fixed);
+/// let ser: Vec<u8, 32> = to_vec_with_config(&-2i32, config).unwrap();
+/// assert_eq!(ser.deref(), &[0xFE, 0xFF, 0xFF, 0xFF]);
+/// ```
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub fn to_vec_with_config<T, const B: usize>(value: &T, config: Config) -> Result<Vec<u8, B>>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_with_config::<T, HVec<B>, Vec<u8, B>>(value, HVec::default(), config)
+}
+
+/// Serialize a `T` to a `heapless::Vec<u8>`, identifying enum variants by their name
+/// rather than their index.
+///
+/// This is a shorthand for [`to_vec_with_config()`] with [`EnumEncoding::Name`], which
+/// makes captured messages easier to read at the cost of size. Use
+/// [`from_bytes_named()`](crate::from_bytes_named) to deserialize the message.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::to_vec_named;
+/// use heapless::Vec;
+/// use core::ops::Deref;
+///
+/// let ser: Vec<u8, 32> = to_vec_named(&Some(true)).unwrap();
+/// assert_eq!(ser.deref(), &[0x01, 0x01]);
+///
+/// let ser: Vec<u8, 32> = to_vec_named(&Ok::<u8, ()>(5)).unwrap();
+/// assert_eq!(ser.deref(), &[0x02, b'O', b'k', 0x05]);
+/// ```
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub fn to_vec_named<T, const B: usize>(value: &T) -> Result<Vec<u8, B>>
+where
+    T: Serialize + ?Sized,
+{
+    to_vec_with_config(value, Config::new().enum_encoding(EnumEncoding::Name))
+}
+
+/// Serialize a `T` to a `heapless::Vec<u8>`, deduplicating repeated strings.
+///
+/// The first occurrence of each string is written as usual, and later occurrences
+/// are written as a reference back to it, which is typically a single byte. This is
+/// useful for messages with many repeated strings, such as enum-like status values.
+/// Up to `N` distinct strings are interned, any further strings are written as usual.
+///
+/// See the [`Interned`](crate::ser_flavors::Interned) flavor for a description of the
+/// wire format, and use [`from_bytes_interned()`](crate::from_bytes_interned) to
+/// deserialize the message.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{from_bytes_interned, to_vec_interned};
+/// use heapless::Vec;
+/// use core::ops::Deref;
+///
+/// let ser: Vec<u8, 32> = to_vec_interned::<_, 32, 8>(&["ok", "ok", "fail", "ok"]).unwrap();
+/// assert_eq!(
+///     ser.deref(),
+///     &[0x00, 0x02, b'o', b'k', 0x02, 0x00, 0x04, b'f', b'a', b'i', b'l', 0x02]
+/// );
+///
+/// let out: [&str; 4] = from_bytes_interned::<_, 8>(&ser).unwrap();
+/// assert_eq!(out, ["ok", "ok", "fail", "ok"]);
+/// ```
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub fn to_vec_interned<T, const B: usize, const N: usize>(value: &T) -> Result<Vec<u8, B>>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_with_flavor::<T, Interned<B, N>, Vec<u8, B>>(value, Interned::new())
+}
+
+/// Serialize a `T` to an `alloc::vec::Vec<u8>`, using a non-default [`Config`].
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{to_allocvec_with_config, Config, IntEncoding};
+///
+/// let config = Config::new().int_encoding(IntEncoding::Fixed);
+/// let ser: Vec<u8> = to_allocvec_with_config(&300u16, config).unwrap();
+/// assert_eq!(ser.as_slice(), &[0x2C, 0x01]);
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub fn to_allocvec_with_config<T>(value: &T, config: Config) -> Result<alloc::vec::Vec<u8>>
+where
+    T: Serialize + ?Sized,
+{
+    serialize_with_config::<T, AllocVec, alloc::vec::Vec<u8>>(value, AllocVec::new(), config)
+}
+
+/// Serialize a `T` to an `alloc::vec::Vec<u8>`, with the fields of every struct (and
+/// struct variant) sorted by name, rather than in the order they are declared in.
+///
+/// This makes the output independent of the declaration order of fields, e.g. for
+/// hashing messages across refactors that reorder fields. Names are compared byte by
+/// byte, the same as `str`'s `Ord`. Each struct is buffered while serializing it, so
+/// this is slower than [`to_allocvec()`].
+///
+/// NOTE: This is NOT compatible with the postcard specification, and can only be
+/// deserialized with [`from_bytes_field_sorted()`](crate::from_bytes_field_sorted), or
+/// a [`Deserializer`](crate::Deserializer) using
+/// [`with_sorted_fields()`](crate::Deserializer::with_sorted_fields).
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{from_bytes_field_sorted, to_allocvec_field_sorted};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// struct Point {
+///     y: u8,
+///     x: u8,
+/// }
+///
+/// let ser: Vec<u8> = to_allocvec_field_sorted(&Point { x: 1, y: 2 }).unwrap();
+/// assert_eq!(ser.as_slice(), &[0x01, 0x02]);
+///
+/// let out: Point = from_bytes_field_sorted(&ser).unwrap();
+/// assert_eq!(out, Point { x: 1, y: 2 });
+/// ```
+#[cfg(feature = "alloc")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "alloc")))]
+pub fn to_allocvec_field_sorted<T>(value: &T) -> Result<alloc::vec::Vec<u8>>
+where
+    T: Serialize + ?Sized,
+{
+    let mut serializer = Serializer::from_flavor(AllocVec::new());
+    value.serialize(sorted::SortedSerializer {
+        ser: &mut serializer,
+    })?;
+    serializer.output.finalize()
+}
+
+/// Serialize a `T` in chunks of (at most) `buf.len()` bytes, handing each chunk to
+/// `sink` as soon as it is full.
+///
+/// This is useful when the message must be handed out in pieces (for example, to a
+/// DMA peripheral), and there is no buffer large enough to hold the whole message at
+/// once. The value is serialized once, pausing at every chunk boundary until `sink`
+/// returns. The concatenation of all chunks is identical to the output of
+/// [`to_slice()`], and every chunk except the last one fills the whole of `buf`.
+///
+/// An empty `buf` fails with [`Error::SerializeBufferFull`], unless the message is
+/// empty as well. See the [`Chunked`](crate::ser_flavors::Chunked) flavor for more
+/// details.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::to_chunks;
+///
+/// let mut buf = [0u8; 2];
+/// let mut sent = [[0u8; 2]; 2];
+/// let mut count = 0;
+/// to_chunks("Hi!", &mut buf, |chunk| {
+///     sent[count][..chunk.len()].copy_from_slice(chunk);
+///     count += 1;
+///     Ok(())
+/// }).unwrap();
+///
+/// assert_eq!(count, 2);
+/// assert_eq!(sent, [[0x03, b'H'], [b'i', b'!']]);
+/// ```
+pub fn to_chunks<T, S>(value: &T, buf: &mut [u8], sink: S) -> Result<()>
+where
+    T: Serialize + ?Sized,
+    S: FnMut(&[u8]) -> Result<()>,
+{
+    serialize_with_flavor::<T, Chunked<'_, S>, ()>(value, Chunked::new(buf, sink))
+}
+
+/// Serializes several values back to back into one `heapless::Vec<u8>`, recording
+/// the offset where each of them starts.
+///
+/// This can be used to build seekable logs or indexes of concatenated messages. Each
+/// message can later be decoded from its offset with [`take_from_bytes()`](crate::take_from_bytes).
+/// At most `B` bytes in total, and at most `N` values, can be serialized.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::{take_from_bytes, MultiSerializer};
+///
+/// let mut multi = MultiSerializer::<32, 4>::new();
+/// assert_eq!(multi.push(&300u16), Ok(0));
+/// assert_eq!(multi.push("Hi!"), Ok(2));
+///
+/// let (buf, offsets) = multi.finalize();
+/// assert_eq!(&buf, &[0xAC, 0x02, 0x03, b'H', b'i', b'!']);
+/// let (msg, _rest) = take_from_bytes::<&str>(&buf[offsets[1]..]).unwrap();
+/// assert_eq!(msg, "Hi!");
+/// ```
+#[cfg(feature = "heapless")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "heapless")))]
+pub struct MultiSerializer<const B: usize, const N: usize> {
+    serializer: Serializer<HVec<B>>,
+    offsets: Vec<usize, N>,
+}
+
+#[cfg(feature = "heapless")]
+impl<const B: usize, const N: usize> MultiSerializer<B, N> {
+    /// Create a new, empty `MultiSerializer`
+    pub fn new() -> Self {
+        MultiSerializer {
+            serializer: Serializer::from_flavor(HVec::new()),
+            offsets: Vec::new(),
+        }
+    }
+
+    /// Serialize `value` after the previously serialized values, returning the offset
+    /// where it starts.
+    ///
+    /// If `value` doesn't fit, or `N` values were already serialized,
+    /// [`Error::SerializeBufferFull`] is returned, and the buffer is left unchanged.
+    pub fn push<T>(&mut self, value: &T) -> Result<usize>
+    where
+        T: Serialize + ?Sized,
+    {
+        if self.offsets.is_full() {
+            return Err(Error::SerializeBufferFull);
+        }
+        let start = self.serializer.output.len();
+        if let Err(e) = value.serialize(&mut self.serializer) {
+            self.serializer.output.truncate(start);
+            return Err(e);
+        }
+        // Can't fail, as there is room for another offset
+        let _ = self.offsets.push(start);
+        Ok(start)
+    }
+
+    /// The offsets of the values serialized so far
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
+    /// Return the buffer containing the serialized values, along with their offsets
+    pub fn finalize(self) -> (Vec<u8, B>, Vec<usize, N>) {
+        let buf = match self.serializer.output.finalize() {
+            Ok(buf) => buf,
+            Err(_) => Vec::new(),
+        };
+        (buf, self.offsets)
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const B: usize, const N: usize> Default for MultiSerializer<B, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// `serialize_with_flavor()` has three generic parameters, `T, F, O`.
 ///
 /// * `T`: This is the type that is being serialized
@@ -481,7 +916,16 @@ where
     T: Serialize + ?Sized,
     S: Flavor<Output = O>,
 {
-    let mut serializer = Serializer { output: storage };
+    serialize_with_config(value, storage, Config::new())
+}
+
+/// [`serialize_with_flavor()`], using a non-default [`Config`]
+pub(crate) fn serialize_with_config<T, S, O>(value: &T, storage: S, config: Config) -> Result<O>
+where
+    T: Serialize + ?Sized,
+    S: Flavor<Output = O>,
+{
+    let mut serializer = Serializer::from_flavor(storage).with_config(config);
     value.serialize(&mut serializer)?;
     serializer
         .output
@@ -497,6 +941,39 @@ where
     serialize_with_flavor::<T, flavors::Size, usize>(value, flavors::Size::default())
 }
 
+/// Compute the size of the postcard serialization of `value`, and of its JSON
+/// serialization, returned as `(postcard, json)`.
+///
+/// This is a diagnostic, for quantifying the bandwidth saved by postcard for actual
+/// data shapes, e.g. when evaluating a migration from JSON.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::compare_sizes;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Reading {
+///     sensor: u8,
+///     value: i32,
+/// }
+///
+/// let (postcard, json) = compare_sizes(&Reading { sensor: 3, value: -300 }).unwrap();
+/// assert_eq!(postcard, 3);
+/// assert_eq!(json, r#"{"sensor":3,"value":-300}"#.len());
+/// ```
+#[cfg(feature = "compare")]
+#[cfg_attr(doc_cfg, doc(cfg(feature = "compare")))]
+pub fn compare_sizes<T>(value: &T) -> Result<(usize, usize)>
+where
+    T: Serialize + ?Sized,
+{
+    let postcard = serialized_size(value)?;
+    let json = serde_json::to_vec(value).map_err(|_| Error::SerdeSerCustom)?;
+    Ok((postcard, json.len()))
+}
+
 #[cfg(feature = "heapless")]
 #[cfg(test)]
 mod test {
@@ -511,7 +988,7 @@ mod test {
     #[test]
     fn ser_u8() {
         let output: Vec<u8, 1> = to_vec(&0x05u8).unwrap();
-        assert!(&[5] == output.deref());
+        assert!([5] == output.deref());
         assert!(output.len() == serialized_size(&0x05u8).unwrap());
         assert!(output.len() <= Vec::<u8, 1>::POSTCARD_MAX_SIZE);
     }
@@ -657,7 +1134,7 @@ mod test {
         let mut buf = [0; varint_max::<usize>()];
         let res = varint_usize(1, &mut buf);
 
-        assert!(&[1] == res);
+        assert!([1] == res);
 
         let res = varint_usize(usize::MAX, &mut buf);
 
@@ -705,7 +1182,7 @@ mod test {
         assert_eq!(&[0x01], output.deref());
         assert!(output.len() == serialized_size(&input).unwrap());
 
-        let input = DataEnum::Bim(u64::max_value());
+        let input = DataEnum::Bim(u64::MAX);
         let output: Vec<u8, { 1 + varint_max::<u64>() }> = to_vec(&input).unwrap();
         assert_eq!(
             &[0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01],
@@ -713,12 +1190,12 @@ mod test {
         );
         assert!(output.len() == serialized_size(&input).unwrap());
 
-        let input = DataEnum::Bib(u16::max_value());
+        let input = DataEnum::Bib(u16::MAX);
         let output: Vec<u8, { 1 + varint_max::<u16>() }> = to_vec(&input).unwrap();
         assert_eq!(&[0x00, 0xFF, 0xFF, 0x03], output.deref());
         assert!(output.len() == serialized_size(&input).unwrap());
 
-        let input = DataEnum::Bap(u8::max_value());
+        let input = DataEnum::Bap(u8::MAX);
         let output: Vec<u8, 2> = to_vec(&input).unwrap();
         assert_eq!(&[0x02, 0xFF], output.deref());
         assert!(output.len() == serialized_size(&input).unwrap());
@@ -819,6 +1296,59 @@ mod test {
         assert!(output.len() == serialized_size(&()).unwrap());
     }
 
+    #[test]
+    fn zigzag_signed() {
+        // Signed varints are zigzag encoded, so small magnitudes stay small
+        fn check<T>(val: T, ser_rep: &[u8])
+        where
+            T: Serialize + for<'de> Deserialize<'de> + PartialEq + core::fmt::Debug,
+        {
+            let output: Vec<u8, 19> = to_vec(&val).unwrap();
+            assert_eq!(output.deref(), ser_rep);
+            assert_eq!(crate::from_bytes::<T>(&output).unwrap(), val);
+        }
+
+        check(-1i16, &[0x01]);
+        check(-64i32, &[0x7F]);
+        check(63i32, &[0x7E]);
+        check(64i32, &[0x80, 0x01]);
+        check(-65i64, &[0x81, 0x01]);
+        check(-1i128, &[0x01]);
+        check(i32::MIN, &[0xFF, 0xFF, 0xFF, 0xFF, 0x0F]);
+        check(i32::MAX, &[0xFE, 0xFF, 0xFF, 0xFF, 0x0F]);
+        check(
+            i64::MIN,
+            &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01],
+        );
+    }
+
+    #[test]
+    fn references() {
+        // References are transparent, no layer adds any bytes
+        const SZ: usize = varint_max::<u32>();
+        let value = 0xCDAB3412u32;
+        let plain: Vec<u8, SZ> = to_vec(&value).unwrap();
+        let single: Vec<u8, SZ> = to_vec(&&value).unwrap();
+        let double: Vec<u8, SZ> = to_vec(&&&value).unwrap();
+        let triple: Vec<u8, SZ> = to_vec(&&&&value).unwrap();
+        assert_eq!(&[0x92, 0xE8, 0xAC, 0xED, 0x0C], plain.deref());
+        assert_eq!(plain, single);
+        assert_eq!(plain, double);
+        assert_eq!(plain, triple);
+        assert_eq!(serialized_size(&&&&value).unwrap(), plain.len());
+
+        for output in [single, double, triple] {
+            assert_eq!(crate::from_bytes::<u32>(&output).unwrap(), value);
+        }
+
+        // The same holds for references to unsized values, and inside of structs
+        let by_ref: Vec<u8, 8> = to_vec(&&&"hi").unwrap();
+        assert_eq!(&[0x02, b'h', b'i'], by_ref.deref());
+        let nested: Vec<u8, 16> = to_vec(&(&&value, &"hi")).unwrap();
+        let owned: Vec<u8, 16> = to_vec(&(value, "hi")).unwrap();
+        assert_eq!(nested, owned);
+    }
+
     #[test]
     fn heapless_data() {
         let mut input: Vec<u8, 4> = Vec::new();
@@ -846,6 +1376,126 @@ mod test {
         assert!(output.len() == serialized_size(&input).unwrap());
     }
 
+    #[test]
+    fn flavors_agree() {
+        let input = DataEnum::Kim(EnumStruct {
+            eight: 0xF0,
+            sixt: 0xACAC,
+        });
+        let expected = &[0x03, 0xF0, 0xAC, 0xD9, 0x02];
+
+        let output: Vec<u8, 8> = to_vec(&input).unwrap();
+        assert_eq!(output.deref(), expected);
+
+        let mut buf = [0u8; 8];
+        assert_eq!(to_slice(&input, &mut buf).unwrap(), expected);
+
+        let output = to_extend(&input, std::vec::Vec::new()).unwrap();
+        assert_eq!(output, expected);
+
+        let output = serialize_with_flavor(&input, flavors::Size::default()).unwrap();
+        assert_eq!(output, expected.len());
+
+        #[cfg(feature = "alloc")]
+        assert_eq!(to_allocvec(&input).unwrap(), expected);
+
+        #[cfg(feature = "use-std")]
+        assert_eq!(to_io(&input, std::vec::Vec::new()).unwrap(), expected);
+
+        #[cfg(feature = "embedded-io")]
+        {
+            let mut buf = [0u8; 8];
+            let mut writer: &mut [u8] = &mut buf;
+            to_eio(&input, &mut writer).unwrap();
+            assert_eq!(&buf[..expected.len()], expected);
+        }
+    }
+
+    #[test]
+    fn chunks() {
+        fn collect<T: Serialize + ?Sized, const N: usize>(input: &T) -> Vec<u8, 64> {
+            let mut output: Vec<u8, 64> = Vec::new();
+            let mut buf = [0u8; N];
+            to_chunks(input, &mut buf, |chunk| {
+                assert!(!chunk.is_empty());
+                output.extend_from_slice(chunk).unwrap();
+                Ok(())
+            })
+            .unwrap();
+            output
+        }
+
+        fn check<T: Serialize + ?Sized>(input: &T) {
+            let expected: Vec<u8, 64> = to_vec(input).unwrap();
+            assert_eq!(collect::<_, 1>(input), expected);
+            assert_eq!(collect::<_, 3>(input), expected);
+            assert_eq!(collect::<_, 4>(input), expected);
+            assert_eq!(collect::<_, 8>(input), expected);
+            assert_eq!(collect::<_, 64>(input), expected);
+        }
+
+        struct Display;
+
+        impl Serialize for Display {
+            fn serialize<S: serde::Serializer>(
+                &self,
+                serializer: S,
+            ) -> core::result::Result<S::Ok, S::Error> {
+                serializer.collect_str(&format_args!("hello {}", "world"))
+            }
+        }
+
+        let input = RefStruct {
+            bytes: &[0x01, 0x00, 0x02, 0x20, 0x30, 0x40, 0x50, 0x60, 0x70],
+            str_s: "hElLo, world!",
+        };
+        check(&input);
+        check(&[
+            DataEnum::Kim(EnumStruct {
+                eight: 0xF0,
+                sixt: 0xACAC,
+            }),
+            DataEnum::Chi {
+                a: 0x0F,
+                b: 0xC7C7C7C7,
+            },
+            DataEnum::Sho(0x6969, 0x07),
+        ]);
+        // Serializers that replace the errors of their contents
+        check(&(7u8, Display, 9u8));
+        check(&crate::extensions::ExtensibleEnum::<'_, _>::Known(
+            DataEnum::Chi {
+                a: 0x0F,
+                b: 0xC7C7C7C7,
+            },
+        ));
+
+        // Chunks are full, except (possibly) the last one
+        let mut sizes: Vec<usize, 4> = Vec::new();
+        let mut buf = [0u8; 8];
+        to_chunks(&input, &mut buf, |chunk| {
+            sizes.push(chunk.len()).unwrap();
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(sizes, [8, 8, 8]);
+
+        // Errors of the sink stop serializing
+        let mut calls = 0;
+        let res = to_chunks(&input, &mut buf, |_| {
+            calls += 1;
+            Err(Error::SerializeBufferFull)
+        });
+        assert_eq!((res, calls), (Err(Error::SerializeBufferFull), 1));
+
+        let mut empty = [0u8; 0];
+        assert_eq!(
+            to_chunks(&input, &mut empty, |_| unreachable!()),
+            Err(Error::SerializeBufferFull)
+        );
+        assert_eq!(to_chunks(&(), &mut empty, |_| unreachable!()), Ok(()));
+    }
+
     #[test]
     fn cobs_test() {
         let message = "hElLo";
@@ -863,4 +1513,150 @@ mod test {
 
         assert_eq!(input, x);
     }
+
+    #[test]
+    fn slice_partial() {
+        let input = (
+            BasicU8S {
+                st: 0xFFFF,
+                ei: 0x01,
+                ote: 0x1234,
+                sf: 0x00,
+                tt: 0xAC,
+            },
+            [0x55u8; 16],
+        );
+        let full: Vec<u8, 32> = to_vec(&input).unwrap();
+        assert_eq!(full.len(), 25);
+
+        let mut buf = [0u8; 25];
+        for size in 0..25 {
+            let res = to_slice_partial(&input, &mut buf[..size]);
+            let Err(Error::SerializeBufferFullAt { written }) = res else {
+                panic!("unexpected result {:?}", res);
+            };
+            // Multi-byte items are never split, so up to two bytes may be unused
+            assert!(written <= size && written + 3 > size);
+            assert_eq!(&buf[..written], &full[..written]);
+        }
+
+        let used = to_slice_partial(&input, &mut buf).unwrap();
+        assert_eq!(used, full.deref());
+    }
+
+    #[test]
+    fn seq_header() {
+        let mut serializer = Serializer::from_flavor(HVec::<256>::default());
+        serializer.serialize_seq_header(100).unwrap();
+        for i in 0..100u32 {
+            (i * i).serialize(&mut serializer).unwrap();
+        }
+        let output = serializer.output.finalize().unwrap();
+
+        // Identical to serializing a collected sequence
+        let expected: Vec<u32, 100> = (0..100u32).map(|i| i * i).collect();
+        let collected: Vec<u8, 256> = to_vec(&expected).unwrap();
+        assert_eq!(output, collected);
+
+        let out: Vec<u32, 100> = crate::from_bytes(&output).unwrap();
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn multi_serializer() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Entry<'a> {
+            seq: u32,
+            name: &'a str,
+        }
+
+        let entries = [
+            Entry {
+                seq: 1,
+                name: "boot",
+            },
+            Entry { seq: 300, name: "" },
+            Entry {
+                seq: 70000,
+                name: "shutdown",
+            },
+        ];
+
+        let mut multi = MultiSerializer::<64, 3>::new();
+        for entry in entries.iter() {
+            multi.push(entry).unwrap();
+        }
+        assert_eq!(multi.offsets(), &[0, 6, 9]);
+
+        // The buffer is unchanged by values that don't fit
+        assert_eq!(multi.push(&0u8), Err(Error::SerializeBufferFull));
+        let (buf, offsets) = multi.finalize();
+        assert_eq!(buf.len(), 21);
+
+        for (offset, entry) in offsets.iter().zip(entries.iter()) {
+            let (out, _rest) = crate::take_from_bytes::<Entry<'_>>(&buf[*offset..]).unwrap();
+            assert_eq!(&out, entry);
+        }
+
+        let mut multi = MultiSerializer::<8, 4>::new();
+        assert_eq!(multi.push(&0x1234u16), Ok(0));
+        assert_eq!(multi.push("too long"), Err(Error::SerializeBufferFull));
+        assert_eq!(multi.push(&true), Ok(2));
+        let (buf, offsets) = multi.finalize();
+        assert_eq!(&buf, &[0xB4, 0x24, 0x01]);
+        assert_eq!(&offsets, &[0, 2]);
+    }
+
+    #[test]
+    fn pkcs7_padding() {
+        // 13 bytes are padded with 3 bytes of `0x03`
+        let input: [u8; 12] = [0xAA; 12];
+        let data: &[u8] = &input;
+        let output: Vec<u8, 32> = to_vec_padded::<_, 32, 16>(data).unwrap();
+        assert_eq!(output.len(), 16);
+        assert_eq!(output[0], 12);
+        assert_eq!(&output[1..13], &input);
+        assert_eq!(&output[13..], &[0x03, 0x03, 0x03]);
+        assert_eq!(crate::from_bytes_padded::<&[u8], 16>(&output), Ok(data));
+
+        // Exactly one block is padded with a full extra block
+        let input: [u8; 15] = [0x55; 15];
+        let data: &[u8] = &input;
+        let output: Vec<u8, 32> = to_vec_padded::<_, 32, 16>(data).unwrap();
+        assert_eq!(output.len(), 32);
+        assert_eq!(&output[16..], &[0x10; 16]);
+        assert_eq!(crate::from_bytes_padded::<&[u8], 16>(&output), Ok(data));
+
+        // The padding must fit as well
+        let res = to_vec_padded::<_, 16, 16>(data);
+        assert_eq!(res, Err(Error::SerializeBufferFull));
+    }
+
+    #[cfg(feature = "compare")]
+    #[test]
+    fn compare_to_json() {
+        use alloc::string::String;
+        use serde::Serialize;
+
+        #[derive(Serialize)]
+        struct Telemetry {
+            device_id: u32,
+            uptime_secs: u64,
+            temperature: f32,
+            status: String,
+            samples: [u16; 4],
+        }
+
+        let value = Telemetry {
+            device_id: 0x1234,
+            uptime_secs: 86_400,
+            temperature: 21.5,
+            status: String::from("ok"),
+            samples: [100, 200, 300, 400],
+        };
+        let (postcard, json) = super::compare_sizes(&value).unwrap();
+        assert_eq!(postcard, serialized_size(&value).unwrap());
+        assert_eq!(json, serde_json::to_string(&value).unwrap().len());
+        assert!(postcard * 4 < json, "{} vs {}", postcard, json);
+    }
 }
@@ -1,6 +1,9 @@
+use core::convert::{TryFrom, TryInto};
 use serde::{ser, Serialize};
 
+use crate::config::{CharEncoding, Config, EnumEncoding, IntEncoding, LengthEncoding};
 use crate::error::{Error, Result};
+use crate::msgpack;
 use crate::ser::flavors::Flavor;
 use crate::varint::*;
 
@@ -19,51 +22,189 @@ where
     /// This is the Flavor(s) that will be used to modify or store any bytes generated
     /// by serialization
     pub output: F,
+
+    /// The [`Config`] of the wire format to serialize with
+    pub config: Config,
 }
 
 impl<F: Flavor> Serializer<F> {
+    /// Obtain a Serializer writing into the given flavor, using the standard postcard
+    /// wire format
+    pub fn from_flavor(output: F) -> Self {
+        Serializer {
+            output,
+            config: Config::new(),
+        }
+    }
+
+    /// Use the given [`Config`] rather than the default postcard wire format
+    /// when serializing.
+    ///
+    /// The same [`Config`] must be used when deserializing the data.
+    ///
+    /// ```rust
+    /// use postcard::{ser_flavors::{Cobs, Flavor, Slice}, Config, IntEncoding, Serializer};
+    /// use serde::Serialize;
+    ///
+    /// let mut buf = [0u8; 8];
+    /// let config = Config::new().int_encoding(IntEncoding::Fixed);
+    /// let flavor = Cobs::try_new(Slice::new(&mut buf)).unwrap();
+    /// let mut serializer = Serializer::from_flavor(flavor).with_config(config);
+    /// 0x1234u16.serialize(&mut serializer).unwrap();
+    ///
+    /// let used = serializer.output.finalize().unwrap();
+    /// assert_eq!(used, &[0x03, 0x34, 0x12, 0x00]);
+    /// ```
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Write the header of a sequence with `count` elements, i.e. its length.
+    ///
+    /// This allows producing a sequence one element at a time, without collecting the
+    /// elements first. Exactly `count` elements must be serialized afterwards, e.g. with
+    /// `element.serialize(&mut serializer)`, for the result to be deserializable as a
+    /// sequence (such as a `Vec<T>` or `&[T]`).
+    ///
+    /// ```rust
+    /// use postcard::{ser_flavors::{Flavor, Slice}, Serializer};
+    /// use serde::Serialize;
+    ///
+    /// let mut buf = [0u8; 8];
+    /// let mut serializer = Serializer::from_flavor(Slice::new(&mut buf));
+    /// serializer.serialize_seq_header(3).unwrap();
+    /// for i in 0..3u8 {
+    ///     (i * 2).serialize(&mut serializer).unwrap();
+    /// }
+    ///
+    /// let used = serializer.output.finalize().unwrap();
+    /// assert_eq!(used, &[0x03, 0x00, 0x02, 0x04]);
+    /// ```
+    #[inline]
+    pub fn serialize_seq_header(&mut self, count: usize) -> Result<()> {
+        self.try_push_length(count)
+            .map_err(|_| Error::SerializeBufferFull)
+    }
+
+    /// Attempt to push a varint into the output data stream, using `encode` for values
+    /// that take more than one byte
+    #[inline]
+    fn try_push_varint_with<T, const N: usize>(
+        &mut self,
+        data: T,
+        encode: fn(T, &mut [u8; N]) -> &mut [u8],
+    ) -> Result<()>
+    where
+        T: Copy + TryInto<u8>,
+    {
+        // Small values are a single byte, which skips the encoding loop
+        match data.try_into() {
+            Ok(byte) if byte < 0x80 => self.output.try_push(byte),
+            _ => {
+                let mut buf = [0u8; N];
+                self.output.try_extend(encode(data, &mut buf))
+            }
+        }
+    }
+
     /// Attempt to push a variably encoded [usize] into the output data stream
     #[inline]
     pub(crate) fn try_push_varint_usize(&mut self, data: usize) -> Result<()> {
-        let mut buf = [0u8; varint_max::<usize>()];
-        let used_buf = varint_usize(data, &mut buf);
-        self.output.try_extend(used_buf)
+        self.try_push_varint_with(data, varint_usize)
     }
 
     /// Attempt to push a variably encoded [u128] into the output data stream
     #[inline]
     pub(crate) fn try_push_varint_u128(&mut self, data: u128) -> Result<()> {
-        let mut buf = [0u8; varint_max::<u128>()];
-        let used_buf = varint_u128(data, &mut buf);
-        self.output.try_extend(used_buf)
+        self.try_push_varint_with(data, varint_u128)
     }
 
     /// Attempt to push a variably encoded [u64] into the output data stream
     #[inline]
     pub(crate) fn try_push_varint_u64(&mut self, data: u64) -> Result<()> {
-        let mut buf = [0u8; varint_max::<u64>()];
-        let used_buf = varint_u64(data, &mut buf);
-        self.output.try_extend(used_buf)
+        self.try_push_varint_with(data, varint_u64)
     }
 
     /// Attempt to push a variably encoded [u32] into the output data stream
     #[inline]
     pub(crate) fn try_push_varint_u32(&mut self, data: u32) -> Result<()> {
-        let mut buf = [0u8; varint_max::<u32>()];
-        let used_buf = varint_u32(data, &mut buf);
-        self.output.try_extend(used_buf)
+        self.try_push_varint_with(data, varint_u32)
     }
 
     /// Attempt to push a variably encoded [u16] into the output data stream
     #[inline]
     pub(crate) fn try_push_varint_u16(&mut self, data: u16) -> Result<()> {
-        let mut buf = [0u8; varint_max::<u16>()];
-        let used_buf = varint_u16(data, &mut buf);
-        self.output.try_extend(used_buf)
+        self.try_push_varint_with(data, varint_u16)
+    }
+
+    /// Attempt to push a MessagePack style unsigned integer into the output data stream
+    #[inline]
+    pub(crate) fn try_push_msgpack_uint(&mut self, data: u64) -> Result<()> {
+        let mut buf = [0u8; 9];
+        self.output.try_extend(msgpack::encode_uint(data, &mut buf))
+    }
+
+    /// Attempt to push a MessagePack style signed integer into the output data stream
+    #[inline]
+    pub(crate) fn try_push_msgpack_int(&mut self, data: i64) -> Result<()> {
+        let mut buf = [0u8; 9];
+        self.output.try_extend(msgpack::encode_int(data, &mut buf))
+    }
+
+    /// Attempt to push a MessagePack style [u128] into the output data stream
+    #[inline]
+    pub(crate) fn try_push_msgpack_u128(&mut self, data: u128) -> Result<()> {
+        match u64::try_from(data) {
+            Ok(data) => self.try_push_msgpack_uint(data),
+            Err(_) => {
+                self.output
+                    .try_extend(&[msgpack::FIXEXT16, msgpack::EXT_UINT128])?;
+                self.output.try_extend(&data.to_be_bytes())
+            }
+        }
+    }
+
+    /// Attempt to push a MessagePack style [i128] into the output data stream
+    #[inline]
+    pub(crate) fn try_push_msgpack_i128(&mut self, data: i128) -> Result<()> {
+        match i64::try_from(data) {
+            Ok(data) => self.try_push_msgpack_int(data),
+            // Positive values use the same (smaller) encoding as unsigned ones
+            Err(_) if data > 0 => self.try_push_msgpack_u128(data as u128),
+            Err(_) => {
+                self.output
+                    .try_extend(&[msgpack::FIXEXT16, msgpack::EXT_INT128])?;
+                self.output.try_extend(&data.to_be_bytes())
+            }
+        }
+    }
+
+    /// Attempt to push a length (of a sequence, map, string, or byte slice) into the
+    /// output data stream, using the configured [`LengthEncoding`]
+    #[inline]
+    pub(crate) fn try_push_length(&mut self, len: usize) -> Result<()> {
+        match self.config.length_encoding {
+            LengthEncoding::Varint => self.try_push_varint_usize(len),
+            LengthEncoding::Fixed => self.output.try_extend(&(len as u64).to_le_bytes()),
+        }
+    }
+
+    /// Attempt to push an enum variant into the output data stream, using the
+    /// configured [`EnumEncoding`]
+    #[inline]
+    pub(crate) fn try_push_variant(&mut self, variant_index: u32, variant: &str) -> Result<()> {
+        match self.config.enum_encoding {
+            EnumEncoding::Index => self.try_push_varint_u32(variant_index),
+            EnumEncoding::Name => {
+                self.try_push_length(variant.len())?;
+                self.output.try_extend(variant.as_bytes())
+            }
+        }
     }
 }
 
-impl<'a, F> ser::Serializer for &'a mut Serializer<F>
+impl<F> ser::Serializer for &mut Serializer<F>
 where
     F: Flavor,
 {
@@ -100,30 +241,42 @@ where
 
     #[inline]
     fn serialize_i16(self, v: i16) -> Result<()> {
-        let zzv = zig_zag_i16(v);
-        self.try_push_varint_u16(zzv)
-            .map_err(|_| Error::SerializeBufferFull)
+        match self.config.int_encoding {
+            IntEncoding::Varint => self.try_push_varint_u16(zig_zag_i16(v)),
+            IntEncoding::Fixed => self.output.try_extend(&v.to_le_bytes()),
+            IntEncoding::MsgPack => self.try_push_msgpack_int(v.into()),
+        }
+        .map_err(|_| Error::SerializeBufferFull)
     }
 
     #[inline]
     fn serialize_i32(self, v: i32) -> Result<()> {
-        let zzv = zig_zag_i32(v);
-        self.try_push_varint_u32(zzv)
-            .map_err(|_| Error::SerializeBufferFull)
+        match self.config.int_encoding {
+            IntEncoding::Varint => self.try_push_varint_u32(zig_zag_i32(v)),
+            IntEncoding::Fixed => self.output.try_extend(&v.to_le_bytes()),
+            IntEncoding::MsgPack => self.try_push_msgpack_int(v.into()),
+        }
+        .map_err(|_| Error::SerializeBufferFull)
     }
 
     #[inline]
     fn serialize_i64(self, v: i64) -> Result<()> {
-        let zzv = zig_zag_i64(v);
-        self.try_push_varint_u64(zzv)
-            .map_err(|_| Error::SerializeBufferFull)
+        match self.config.int_encoding {
+            IntEncoding::Varint => self.try_push_varint_u64(zig_zag_i64(v)),
+            IntEncoding::Fixed => self.output.try_extend(&v.to_le_bytes()),
+            IntEncoding::MsgPack => self.try_push_msgpack_int(v),
+        }
+        .map_err(|_| Error::SerializeBufferFull)
     }
 
     #[inline]
     fn serialize_i128(self, v: i128) -> Result<()> {
-        let zzv = zig_zag_i128(v);
-        self.try_push_varint_u128(zzv)
-            .map_err(|_| Error::SerializeBufferFull)
+        match self.config.int_encoding {
+            IntEncoding::Varint => self.try_push_varint_u128(zig_zag_i128(v)),
+            IntEncoding::Fixed => self.output.try_extend(&v.to_le_bytes()),
+            IntEncoding::MsgPack => self.try_push_msgpack_i128(v),
+        }
+        .map_err(|_| Error::SerializeBufferFull)
     }
 
     #[inline]
@@ -135,26 +288,42 @@ where
 
     #[inline]
     fn serialize_u16(self, v: u16) -> Result<()> {
-        self.try_push_varint_u16(v)
-            .map_err(|_| Error::SerializeBufferFull)
+        match self.config.int_encoding {
+            IntEncoding::Varint => self.try_push_varint_u16(v),
+            IntEncoding::Fixed => self.output.try_extend(&v.to_le_bytes()),
+            IntEncoding::MsgPack => self.try_push_msgpack_uint(v.into()),
+        }
+        .map_err(|_| Error::SerializeBufferFull)
     }
 
     #[inline]
     fn serialize_u32(self, v: u32) -> Result<()> {
-        self.try_push_varint_u32(v)
-            .map_err(|_| Error::SerializeBufferFull)
+        match self.config.int_encoding {
+            IntEncoding::Varint => self.try_push_varint_u32(v),
+            IntEncoding::Fixed => self.output.try_extend(&v.to_le_bytes()),
+            IntEncoding::MsgPack => self.try_push_msgpack_uint(v.into()),
+        }
+        .map_err(|_| Error::SerializeBufferFull)
     }
 
     #[inline]
     fn serialize_u64(self, v: u64) -> Result<()> {
-        self.try_push_varint_u64(v)
-            .map_err(|_| Error::SerializeBufferFull)
+        match self.config.int_encoding {
+            IntEncoding::Varint => self.try_push_varint_u64(v),
+            IntEncoding::Fixed => self.output.try_extend(&v.to_le_bytes()),
+            IntEncoding::MsgPack => self.try_push_msgpack_uint(v),
+        }
+        .map_err(|_| Error::SerializeBufferFull)
     }
 
     #[inline]
     fn serialize_u128(self, v: u128) -> Result<()> {
-        self.try_push_varint_u128(v)
-            .map_err(|_| Error::SerializeBufferFull)
+        match self.config.int_encoding {
+            IntEncoding::Varint => self.try_push_varint_u128(v),
+            IntEncoding::Fixed => self.output.try_extend(&v.to_le_bytes()),
+            IntEncoding::MsgPack => self.try_push_msgpack_u128(v),
+        }
+        .map_err(|_| Error::SerializeBufferFull)
     }
 
     #[inline]
@@ -175,14 +344,30 @@ where
 
     #[inline]
     fn serialize_char(self, v: char) -> Result<()> {
-        let mut buf = [0u8; 4];
-        let strsl = v.encode_utf8(&mut buf);
-        strsl.serialize(self)
+        match self.config.char_encoding {
+            CharEncoding::Utf8 => {
+                // Written like a string, but never interned, as chars are read back
+                // without looking at the string table
+                let mut buf = [0u8; 4];
+                let strsl = v.encode_utf8(&mut buf);
+                self.try_push_length(strsl.len())
+                    .map_err(|_| Error::SerializeBufferFull)?;
+                self.output
+                    .try_extend(strsl.as_bytes())
+                    .map_err(|_| Error::SerializeBufferFull)
+            }
+            CharEncoding::Varint => self
+                .try_push_varint_u32(u32::from(v))
+                .map_err(|_| Error::SerializeBufferFull),
+        }
     }
 
     #[inline]
     fn serialize_str(self, v: &str) -> Result<()> {
-        self.try_push_varint_usize(v.len())
+        if self.output.try_push_interned(Some(v))? {
+            return Ok(());
+        }
+        self.try_push_length(v.len())
             .map_err(|_| Error::SerializeBufferFull)?;
         self.output
             .try_extend(v.as_bytes())
@@ -192,7 +377,7 @@ where
 
     #[inline]
     fn serialize_bytes(self, v: &[u8]) -> Result<()> {
-        self.try_push_varint_usize(v.len())
+        self.try_push_length(v.len())
             .map_err(|_| Error::SerializeBufferFull)?;
         self.output
             .try_extend(v)
@@ -228,9 +413,9 @@ where
         self,
         _name: &'static str,
         variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
     ) -> Result<()> {
-        self.try_push_varint_u32(variant_index)
+        self.try_push_variant(variant_index, variant)
             .map_err(|_| Error::SerializeBufferFull)
     }
 
@@ -247,20 +432,20 @@ where
         self,
         _name: &'static str,
         variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         value: &T,
     ) -> Result<()>
     where
         T: ?Sized + Serialize,
     {
-        self.try_push_varint_u32(variant_index)
+        self.try_push_variant(variant_index, variant)
             .map_err(|_| Error::SerializeBufferFull)?;
         value.serialize(self)
     }
 
     #[inline]
     fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq> {
-        self.try_push_varint_usize(len.ok_or(Error::SerializeSeqLengthUnknown)?)
+        self.try_push_length(len.ok_or(Error::SerializeSeqLengthUnknown)?)
             .map_err(|_| Error::SerializeBufferFull)?;
         Ok(self)
     }
@@ -284,17 +469,17 @@ where
         self,
         _name: &'static str,
         variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeTupleVariant> {
-        self.try_push_varint_u32(variant_index)
+        self.try_push_variant(variant_index, variant)
             .map_err(|_| Error::SerializeBufferFull)?;
         Ok(self)
     }
 
     #[inline]
     fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap> {
-        self.try_push_varint_usize(len.ok_or(Error::SerializeSeqLengthUnknown)?)
+        self.try_push_length(len.ok_or(Error::SerializeSeqLengthUnknown)?)
             .map_err(|_| Error::SerializeBufferFull)?;
         Ok(self)
     }
@@ -309,18 +494,18 @@ where
         self,
         _name: &'static str,
         variant_index: u32,
-        _variant: &'static str,
+        variant: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant> {
-        self.try_push_varint_u32(variant_index)
+        self.try_push_variant(variant_index, variant)
             .map_err(|_| Error::SerializeBufferFull)?;
         Ok(self)
     }
 
     #[inline]
-    fn collect_str<T: ?Sized>(self, value: &T) -> Result<Self::Ok>
+    fn collect_str<T>(self, value: &T) -> Result<Self::Ok>
     where
-        T: core::fmt::Display,
+        T: core::fmt::Display + ?Sized,
     {
         use core::fmt::Write;
 
@@ -347,7 +532,7 @@ where
         }
         impl Write for CountWriter {
             fn write_str(&mut self, s: &str) -> core::result::Result<(), core::fmt::Error> {
-                self.ct += s.as_bytes().len();
+                self.ct += s.len();
                 Ok(())
             }
         }
@@ -358,7 +543,8 @@ where
         // data that we are given
         write!(&mut ctr, "{}", value).map_err(|_| Error::CollectStrError)?;
         let len = ctr.ct;
-        self.try_push_varint_usize(len)
+        self.output.try_push_interned(None)?;
+        self.try_push_length(len)
             .map_err(|_| Error::SerializeBufferFull)?;
 
         struct FmtWriter<'a, IF>
@@ -374,7 +560,7 @@ where
             fn write_str(&mut self, s: &str) -> core::result::Result<(), core::fmt::Error> {
                 self.output
                     .try_extend(s.as_bytes())
-                    .map_err(|_| core::fmt::Error::default())
+                    .map_err(|_| core::fmt::Error)
             }
         }
 
@@ -388,7 +574,7 @@ where
     }
 }
 
-impl<'a, F> ser::SerializeSeq for &'a mut Serializer<F>
+impl<F> ser::SerializeSeq for &mut Serializer<F>
 where
     F: Flavor,
 {
@@ -413,7 +599,7 @@ where
     }
 }
 
-impl<'a, F> ser::SerializeTuple for &'a mut Serializer<F>
+impl<F> ser::SerializeTuple for &mut Serializer<F>
 where
     F: Flavor,
 {
@@ -434,7 +620,7 @@ where
     }
 }
 
-impl<'a, F> ser::SerializeTupleStruct for &'a mut Serializer<F>
+impl<F> ser::SerializeTupleStruct for &mut Serializer<F>
 where
     F: Flavor,
 {
@@ -455,7 +641,7 @@ where
     }
 }
 
-impl<'a, F> ser::SerializeTupleVariant for &'a mut Serializer<F>
+impl<F> ser::SerializeTupleVariant for &mut Serializer<F>
 where
     F: Flavor,
 {
@@ -476,7 +662,7 @@ where
     }
 }
 
-impl<'a, F> ser::SerializeMap for &'a mut Serializer<F>
+impl<F> ser::SerializeMap for &mut Serializer<F>
 where
     F: Flavor,
 {
@@ -505,7 +691,7 @@ where
     }
 }
 
-impl<'a, F> ser::SerializeStruct for &'a mut Serializer<F>
+impl<F> ser::SerializeStruct for &mut Serializer<F>
 where
     F: Flavor,
 {
@@ -526,7 +712,7 @@ where
     }
 }
 
-impl<'a, F> ser::SerializeStructVariant for &'a mut Serializer<F>
+impl<F> ser::SerializeStructVariant for &mut Serializer<F>
 where
     F: Flavor,
 {
@@ -0,0 +1,380 @@
+//! Serialization with the fields of every struct sorted by name
+
+use serde::ser::{self, Serialize};
+
+use crate::error::{Error, Result};
+use crate::ser::flavors::{AllocVec, Flavor};
+use crate::ser::serializer::Serializer;
+
+extern crate alloc;
+use alloc::vec::Vec;
+
+/// A serializer writing into a postcard [`Serializer`], except for the fields of
+/// structs (and struct variants), which are sorted by name.
+///
+/// Every compound type wraps its elements in a `SortedSerializer` again, so that
+/// structs nested anywhere in the message are sorted too.
+pub(crate) struct SortedSerializer<'a, F: Flavor> {
+    pub(crate) ser: &'a mut Serializer<F>,
+}
+
+/// The elements of a sequence, tuple, or map, which are written in order
+pub(crate) struct Compound<'a, F: Flavor> {
+    ser: &'a mut Serializer<F>,
+}
+
+/// The fields of a struct, which are buffered until all of them are known
+pub(crate) struct SortedFields<'a, F: Flavor> {
+    ser: &'a mut Serializer<F>,
+    fields: Vec<(&'static str, Vec<u8>)>,
+}
+
+impl<'a, F: Flavor> SortedSerializer<'a, F> {
+    fn compound(self) -> Compound<'a, F> {
+        Compound { ser: self.ser }
+    }
+
+    fn sorted_fields(self, len: usize) -> SortedFields<'a, F> {
+        SortedFields {
+            ser: self.ser,
+            fields: Vec::with_capacity(len),
+        }
+    }
+}
+
+impl<'a, F: Flavor> ser::Serializer for SortedSerializer<'a, F> {
+    type Ok = ();
+    type Error = Error;
+
+    type SerializeSeq = Compound<'a, F>;
+    type SerializeTuple = Compound<'a, F>;
+    type SerializeTupleStruct = Compound<'a, F>;
+    type SerializeTupleVariant = Compound<'a, F>;
+    type SerializeMap = Compound<'a, F>;
+    type SerializeStruct = SortedFields<'a, F>;
+    type SerializeStructVariant = SortedFields<'a, F>;
+
+    #[inline]
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.ser.serialize_bool(v)
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.ser.serialize_i8(v)
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.ser.serialize_i16(v)
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.ser.serialize_i32(v)
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.ser.serialize_i64(v)
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<()> {
+        self.ser.serialize_i128(v)
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.ser.serialize_u8(v)
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.ser.serialize_u16(v)
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.ser.serialize_u32(v)
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.ser.serialize_u64(v)
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<()> {
+        self.ser.serialize_u128(v)
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        self.ser.serialize_f32(v)
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        self.ser.serialize_f64(v)
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.ser.serialize_char(v)
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        self.ser.serialize_str(v)
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        self.ser.serialize_bytes(v)
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        self.ser.serialize_none()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.ser.serialize_u8(1)?;
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        self.ser.serialize_unit()
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<()> {
+        self.ser.serialize_unit_struct(name)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        self.ser
+            .serialize_unit_variant(name, variant_index, variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, _name: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        // The discriminant of a newtype variant is the same as the one of a unit variant
+        self.ser
+            .serialize_unit_variant(name, variant_index, variant)?;
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Compound<'a, F>> {
+        self.ser.serialize_seq(len)?;
+        Ok(self.compound())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Compound<'a, F>> {
+        Ok(self.compound())
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize) -> Result<Compound<'a, F>> {
+        Ok(self.compound())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Compound<'a, F>> {
+        self.ser
+            .serialize_unit_variant(name, variant_index, variant)?;
+        Ok(self.compound())
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Compound<'a, F>> {
+        self.ser.serialize_map(len)?;
+        Ok(self.compound())
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<SortedFields<'a, F>> {
+        Ok(self.sorted_fields(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SortedFields<'a, F>> {
+        self.ser
+            .serialize_unit_variant(name, variant_index, variant)?;
+        Ok(self.sorted_fields(len))
+    }
+
+    fn collect_str<T>(self, value: &T) -> Result<()>
+    where
+        T: core::fmt::Display + ?Sized,
+    {
+        self.ser.collect_str(value)
+    }
+}
+
+impl<'a, F: Flavor> Compound<'a, F> {
+    fn element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(SortedSerializer { ser: self.ser })
+    }
+}
+
+impl<'a, F: Flavor> ser::SerializeSeq for Compound<'a, F> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, F: Flavor> ser::SerializeTuple for Compound<'a, F> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, F: Flavor> ser::SerializeTupleStruct for Compound<'a, F> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, F: Flavor> ser::SerializeTupleVariant for Compound<'a, F> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, F: Flavor> ser::SerializeMap for Compound<'a, F> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(key)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a, F: Flavor> SortedFields<'a, F> {
+    fn field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        let mut field = Serializer::from_flavor(AllocVec::new()).with_config(self.ser.config);
+        value.serialize(SortedSerializer { ser: &mut field })?;
+        self.fields.push((key, field.output.finalize()?));
+        Ok(())
+    }
+
+    fn end(mut self) -> Result<()> {
+        // Field names are unique, so the order of equal names doesn't matter
+        self.fields.sort_unstable_by_key(|(key, _)| *key);
+        let output = &mut self.ser.output;
+        self.fields
+            .iter()
+            .try_for_each(|(_, bytes)| output.try_extend(bytes))
+    }
+}
+
+impl<'a, F: Flavor> ser::SerializeStruct for SortedFields<'a, F> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        SortedFields::end(self)
+    }
+}
+
+impl<'a, F: Flavor> ser::SerializeStructVariant for SortedFields<'a, F> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        SortedFields::end(self)
+    }
+}
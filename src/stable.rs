@@ -0,0 +1,76 @@
+//! # Wire Format Stability
+//!
+//! Postcard encodes struct fields by position, not by name, so reordering (or retyping)
+//! the fields of a struct silently changes its wire format. For long lived protocols,
+//! the [`postcard_stable!`](crate::postcard_stable) macro records the field order and
+//! types of a struct, so that a test can compare them against a snapshot with
+//! [`assert_layout()`], and fail if the layout changes.
+//!
+//! ```rust
+//! use postcard::{postcard_stable, stable::assert_layout};
+//! use serde::{Deserialize, Serialize};
+//!
+//! postcard_stable! {
+//!     #[derive(Serialize, Deserialize)]
+//!     pub struct Reading {
+//!         pub id: u16,
+//!         pub value: i32,
+//!     }
+//! }
+//!
+//! // Typically inside of a `#[test]`
+//! assert_layout::<Reading>("id: u16; value: i32;");
+//! ```
+
+/// A type with a recorded field layout. Implemented by [`postcard_stable!`](crate::postcard_stable).
+pub trait StableLayout {
+    /// The fields of the type, in order, as `"name: type;"` pairs
+    const LAYOUT: &'static str;
+}
+
+/// Panic if the recorded field layout of `T` does not match the given snapshot.
+///
+/// Whitespace is ignored when comparing the layouts.
+#[track_caller]
+pub fn assert_layout<T: StableLayout>(snapshot: &str) {
+    let normalized = |s: &'static str| s.chars().filter(|c| !c.is_whitespace());
+    let snapshot_chars = snapshot.chars().filter(|c| !c.is_whitespace());
+    if !normalized(T::LAYOUT).eq(snapshot_chars) {
+        panic!(
+            "wire format layout changed!\n  snapshot: {}\n  current:  {}",
+            snapshot,
+            T::LAYOUT
+        );
+    }
+}
+
+/// Define a struct, recording its field order and types for [`assert_layout()`].
+///
+/// The struct definition is passed through unchanged, along with an implementation of
+/// [`StableLayout`]. Only structs with named fields and without generics are supported.
+///
+/// See the [`stable`](crate::stable) module for an example.
+#[macro_export]
+macro_rules! postcard_stable {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident {
+            $(
+                $(#[$fmeta:meta])*
+                $fvis:vis $field:ident : $ty:ty
+            ),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis struct $name {
+            $(
+                $(#[$fmeta])*
+                $fvis $field : $ty,
+            )*
+        }
+
+        impl $crate::stable::StableLayout for $name {
+            const LAYOUT: &'static str = concat!($(stringify!($field), ": ", stringify!($ty), "; ",)*);
+        }
+    };
+}
@@ -0,0 +1,211 @@
+//! # Streamed Sequences
+//!
+//! Sequences are normally prefixed with their number of elements, so a serializer has
+//! to know the count before writing the first element. [`StreamSeq`] instead precedes
+//! every element with a `0x01` continuation byte, and ends the sequence with a `0x00`
+//! byte, so that elements can be written as they are produced, e.g. by an iterator
+//! that doesn't know its length.
+//!
+//! ```rust
+//! use postcard::stream_seq::StreamSeq;
+//!
+//! let mut buf = [0u8; 16];
+//! let used = postcard::to_slice(&StreamSeq([10u8, 20, 30]), &mut buf).unwrap();
+//! assert_eq!(used, &[0x01, 10, 0x01, 20, 0x01, 30, 0x00]);
+//!
+//! let out: StreamSeq<[u8; 3]> = postcard::from_bytes(used).unwrap();
+//! assert_eq!(out, StreamSeq([10, 20, 30]));
+//! ```
+//!
+//! This costs one byte per element, rather than the (usually) single byte of the
+//! length prefix. Streamed sequences are only supported with the default
+//! [`Config`](crate::Config).
+
+use core::fmt;
+use core::marker::PhantomData;
+use serde::de::value::SeqAccessDeserializer;
+use serde::de::{DeserializeSeed, Error as _, SeqAccess, Unexpected, Visitor};
+use serde::ser::SerializeTuple;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+const CONTINUE: u8 = 0x01;
+const END: u8 = 0x00;
+
+/// A sequence, with a continuation byte before every element and an end marker,
+/// instead of a length prefix.
+///
+/// See the [`stream_seq`](crate::stream_seq) module for details. This can wrap anything
+/// that can be iterated by reference, such as slices, arrays, or `Vec`s, as well as
+/// custom types producing their elements on demand. When deserializing, `T` must be
+/// deserializable from a sequence.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct StreamSeq<T>(pub T);
+
+impl<T, E> Serialize for StreamSeq<T>
+where
+    for<'a> &'a T: IntoIterator<Item = &'a E>,
+    E: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // The number of elements is unknown, and postcard doesn't need it for tuples
+        let mut tup = serializer.serialize_tuple(0)?;
+        for elem in &self.0 {
+            tup.serialize_element(&CONTINUE)?;
+            tup.serialize_element(elem)?;
+        }
+        tup.serialize_element(&END)?;
+        tup.end()
+    }
+}
+
+/// Reads the elements of a streamed sequence, up to its end marker
+struct Continued<A> {
+    seq: A,
+    done: bool,
+}
+
+impl<A> Continued<A> {
+    fn next_marker<'de>(&mut self) -> Result<bool, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let marker: u8 = self
+            .seq
+            .next_element()?
+            .ok_or_else(|| A::Error::custom("missing end of streamed sequence"))?;
+        match marker {
+            CONTINUE => Ok(true),
+            END => {
+                self.done = true;
+                Ok(false)
+            }
+            other => Err(A::Error::invalid_value(
+                Unexpected::Unsigned(other.into()),
+                &"a continuation byte or end marker",
+            )),
+        }
+    }
+}
+
+impl<'de, A: SeqAccess<'de>> SeqAccess<'de> for Continued<A> {
+    type Error = A::Error;
+
+    fn next_element_seed<S>(&mut self, seed: S) -> Result<Option<S::Value>, A::Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        if self.done || !self.next_marker()? {
+            return Ok(None);
+        }
+        self.seq
+            .next_element_seed(seed)?
+            .map(Some)
+            .ok_or_else(|| A::Error::custom("missing element of streamed sequence"))
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for StreamSeq<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct StreamVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for StreamVisitor<T> {
+            type Value = StreamSeq<T>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a streamed sequence")
+            }
+
+            fn visit_seq<A>(self, seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut elems = Continued { seq, done: false };
+                let value = T::deserialize(SeqAccessDeserializer::new(&mut elems))?;
+
+                // Types with a fixed number of elements stop before the end marker
+                if !elems.done && elems.next_marker()? {
+                    return Err(A::Error::custom("too many elements in streamed sequence"));
+                }
+                Ok(StreamSeq(value))
+            }
+        }
+
+        // The number of elements is unknown, and postcard doesn't need it for tuples
+        deserializer.deserialize_tuple(usize::MAX, StreamVisitor(PhantomData))
+    }
+}
+
+#[cfg(all(test, feature = "heapless"))]
+mod tests {
+    use super::StreamSeq;
+    use crate::Error;
+    use core::ops::Range;
+    use serde::{Deserialize, Serialize};
+
+    /// Produces its elements on demand, without knowing how many there are up front
+    struct Squares(Range<u16>);
+
+    impl<'a> IntoIterator for &'a Squares {
+        type Item = &'a u16;
+        type IntoIter = core::iter::Map<Range<usize>, fn(usize) -> &'a u16>;
+
+        fn into_iter(self) -> Self::IntoIter {
+            const SQUARES: [u16; 8] = [0, 1, 4, 9, 16, 25, 36, 49];
+            let range = usize::from(self.0.start)..usize::from(self.0.end);
+            range.map(|i| &SQUARES[i])
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Report {
+        id: u8,
+        readings: StreamSeq<heapless::Vec<u16, 8>>,
+        end: u8,
+    }
+
+    #[test]
+    fn stream_seq() {
+        let mut buf = [0u8; 32];
+        let used = crate::to_slice(&StreamSeq(Squares(3..8)), &mut buf).unwrap();
+        assert_eq!(
+            used,
+            &[0x01, 9, 0x01, 16, 0x01, 25, 0x01, 36, 0x01, 49, 0x00]
+        );
+
+        let report: Report = crate::from_bytes(&[0x07, 0x01, 9, 0x01, 16, 0x00, 0xFF]).unwrap();
+        assert_eq!(report.id, 7);
+        assert_eq!(report.readings.0.as_slice(), &[9, 16]);
+        assert_eq!(report.end, 0xFF);
+
+        let mut out = heapless::Vec::<u16, 8>::new();
+        for square in &Squares(3..8) {
+            out.push(*square).unwrap();
+        }
+        let input = Report {
+            id: 1,
+            readings: StreamSeq(out),
+            end: 2,
+        };
+        let used = crate::to_slice(&input, &mut buf).unwrap();
+        assert_eq!(used.len(), 1 + 5 * 2 + 1 + 1);
+        assert_eq!(crate::from_bytes::<Report>(used), Ok(input));
+
+        // Empty sequences are only the end marker
+        let used = crate::to_slice(&StreamSeq::<[u8; 0]>([]), &mut buf).unwrap();
+        assert_eq!(used, &[0x00]);
+
+        // Fixed size arrays must end right after their last element
+        let res = crate::from_bytes::<StreamSeq<[u8; 2]>>(&[0x01, 1, 0x01, 2, 0x01, 3, 0x00]);
+        assert_eq!(res, Err(Error::SerdeDeCustom));
+        let res = crate::from_bytes::<StreamSeq<[u8; 2]>>(&[0x01, 1, 0x02, 2, 0x00]);
+        assert_eq!(res, Err(Error::SerdeDeCustom));
+        let res = crate::from_bytes::<StreamSeq<[u8; 2]>>(&[0x01, 1, 0x01, 2]);
+        assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+    }
+}
@@ -0,0 +1,439 @@
+//! # Trailing Discriminants and Optional Elements
+//!
+//! Enums are normally encoded with their discriminant first, followed by the data of
+//! the variant. Some hardware expects the opposite: the data first, followed by a
+//! trailing type byte. [`TrailingDiscriminant`] opts in to this layout.
+//!
+//! As the deserializer can't know the variant before reading its data, this only works
+//! for enums where the data of every variant has the same, fixed size on the wire.
+//!
+//! Every `Option` in a tuple takes at least one byte, even when it is `None`. For tuples
+//! ending in optional elements that are usually `None`, such as extensible trailing
+//! parameters, [`TrailingOptTuple`] opts in to omitting the trailing `None`s.
+
+use core::fmt;
+use serde::de::{DeserializeOwned, Error as _, SeqAccess, Visitor};
+use serde::ser::{Error as _, SerializeTuple};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::de::flavors::Flavor as DeFlavor;
+use crate::error::{Error, Result};
+use crate::ser::flavors::Flavor as SerFlavor;
+use crate::varint::varint_max;
+
+/// An enum, encoded with the data of its variant first, followed by its discriminant.
+///
+/// The data of every variant of `T` must be exactly `N` bytes on the wire. Variants
+/// with data of any other size, including unit variants when `N` is not zero, fail to
+/// serialize with [`Error::SerdeSerCustom`]. Using [`fixint`](crate::fixint) for
+/// integer fields avoids sizes that depend on the value, as with varints.
+///
+/// The discriminant itself is encoded as usual, as a varint. As the data is buffered
+/// in order to reorder it, `T` can't borrow from the input. Only the default
+/// [`Config`](crate::Config) is supported.
+///
+/// ```rust
+/// use postcard::trailing::TrailingDiscriminant;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize, Debug, PartialEq)]
+/// enum Command {
+///     SetLevel(#[serde(with = "postcard::fixint::le")] u16),
+///     Move { x: u8, y: u8 },
+/// }
+///
+/// let mut buf = [0u8; 8];
+/// let cmd = TrailingDiscriminant::<_, 2>(Command::Move { x: 3, y: 4 });
+/// let used = postcard::to_slice(&cmd, &mut buf).unwrap();
+/// assert_eq!(used, &[0x03, 0x04, 0x01]);
+///
+/// let out: TrailingDiscriminant<Command, 2> = postcard::from_bytes(used).unwrap();
+/// assert_eq!(out, cmd);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TrailingDiscriminant<T, const N: usize>(pub T);
+
+/// A flavor that splits a serialized enum into its discriminant and its data
+struct Split<const N: usize> {
+    disc: [u8; varint_max::<u32>()],
+    disc_len: usize,
+    disc_done: bool,
+    data: [u8; N],
+    data_len: usize,
+}
+
+impl<const N: usize> SerFlavor for Split<N> {
+    type Output = Self;
+
+    #[inline]
+    fn try_push(&mut self, data: u8) -> Result<()> {
+        if !self.disc_done {
+            let slot = self
+                .disc
+                .get_mut(self.disc_len)
+                .ok_or(Error::SerializeBufferFull)?;
+            *slot = data;
+            self.disc_len += 1;
+            self.disc_done = (data & 0x80) == 0;
+        } else {
+            let slot = self
+                .data
+                .get_mut(self.data_len)
+                .ok_or(Error::SerializeBufferFull)?;
+            *slot = data;
+            self.data_len += 1;
+        }
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self> {
+        Ok(self)
+    }
+}
+
+impl<T: Serialize, const N: usize> Serialize for TrailingDiscriminant<T, N> {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let split = Split::<N> {
+            disc: [0; varint_max::<u32>()],
+            disc_len: 0,
+            disc_done: false,
+            data: [0; N],
+            data_len: 0,
+        };
+        let split = crate::serialize_with_flavor(&self.0, split)
+            .map_err(|_| S::Error::custom("enum variant data is not N bytes"))?;
+        if split.data_len != N {
+            return Err(S::Error::custom("enum variant data is not N bytes"));
+        }
+        let disc: u32 =
+            crate::from_bytes(&split.disc[..split.disc_len]).map_err(S::Error::custom)?;
+
+        let mut tup = serializer.serialize_tuple(N + 1)?;
+        for byte in split.data.iter() {
+            tup.serialize_element(byte)?;
+        }
+        tup.serialize_element(&disc)?;
+        tup.end()
+    }
+}
+
+/// A flavor reading from two slices, one after the other
+struct Chain<'a> {
+    head: &'a [u8],
+    tail: &'a [u8],
+}
+
+impl<'a> DeFlavor<'a> for Chain<'a> {
+    type Remainder = usize;
+    type Source = &'a [u8];
+
+    #[inline]
+    fn pop(&mut self) -> Result<u8> {
+        let part = if self.head.is_empty() {
+            &mut self.tail
+        } else {
+            &mut self.head
+        };
+        let (first, rest) = part.split_first().ok_or(Error::DeserializeUnexpectedEnd)?;
+        *part = rest;
+        Ok(*first)
+    }
+
+    #[inline]
+    fn try_take_n(&mut self, ct: usize) -> Result<&'a [u8]> {
+        let part = if self.head.is_empty() {
+            &mut self.tail
+        } else {
+            &mut self.head
+        };
+        if part.len() < ct {
+            return Err(Error::DeserializeUnexpectedEnd);
+        }
+        let (taken, rest) = part.split_at(ct);
+        *part = rest;
+        Ok(taken)
+    }
+
+    /// Return the number of unused bytes
+    fn finalize(self) -> Result<usize> {
+        Ok(self.head.len() + self.tail.len())
+    }
+}
+
+impl<'de, T: DeserializeOwned, const N: usize> Deserialize<'de> for TrailingDiscriminant<T, N> {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TrailingVisitor<T, const N: usize>(core::marker::PhantomData<T>);
+
+        impl<'de, T: DeserializeOwned, const N: usize> Visitor<'de> for TrailingVisitor<T, N> {
+            type Value = TrailingDiscriminant<T, N>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "{} bytes of data, followed by a discriminant", N)
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> core::result::Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'de>,
+            {
+                let mut data = [0u8; N];
+                for (idx, byte) in data.iter_mut().enumerate() {
+                    *byte = seq
+                        .next_element()?
+                        .ok_or_else(|| A::Error::invalid_length(idx, &self))?;
+                }
+                let disc: u32 = seq
+                    .next_element()?
+                    .ok_or_else(|| A::Error::invalid_length(N, &self))?;
+
+                // Restore the usual layout, with the discriminant first
+                let mut disc_buf = [0u8; varint_max::<u32>()];
+                let disc = crate::to_slice(&disc, &mut disc_buf).map_err(A::Error::custom)?;
+                let mut deserializer = crate::Deserializer::from_flavor(Chain {
+                    head: disc,
+                    tail: &data,
+                });
+                let value = T::deserialize(&mut deserializer).map_err(A::Error::custom)?;
+                if deserializer.finalize().map_err(A::Error::custom)? != 0 {
+                    return Err(A::Error::custom("enum variant data is not N bytes"));
+                }
+                Ok(TrailingDiscriminant(value))
+            }
+        }
+
+        deserializer.deserialize_tuple(N + 1, TrailingVisitor(core::marker::PhantomData))
+    }
+}
+
+/// A tuple of a value followed by optional elements, with its trailing `None`s omitted
+/// on the wire.
+///
+/// On the wire, this is encoded as a `u8` count of the optional elements up to and
+/// including the last `Some`, followed by the first element, and that many optional
+/// elements. These are encoded as usual, except for the last one, which is always
+/// `Some` and is encoded without its tag. This is never larger than the plain tuple,
+/// and is smaller whenever its last element is `None`.
+///
+/// This is implemented for tuples of a value followed by one to eight `Option`s.
+///
+/// ```rust
+/// use postcard::trailing::TrailingOptTuple;
+///
+/// let mut buf = [0u8; 8];
+/// let params = TrailingOptTuple((5u32, None::<u32>, None::<u32>));
+/// let used = postcard::to_slice(&params, &mut buf).unwrap();
+/// assert_eq!(used, &[0x00, 0x05]);
+///
+/// let params = TrailingOptTuple((5u32, Some(1u32), None::<u32>));
+/// let used = postcard::to_slice(&params, &mut buf).unwrap();
+/// assert_eq!(used, &[0x01, 0x05, 0x01]);
+///
+/// let out: TrailingOptTuple<(u32, Option<u32>, Option<u32>)> =
+///     postcard::from_bytes(used).unwrap();
+/// assert_eq!(out, params);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TrailingOptTuple<T>(pub T);
+
+macro_rules! impl_trailing_opt_tuple {
+    ($len:literal => $($idx:tt $ty:ident),+) => {
+        impl<H: Serialize, $($ty: Serialize),+> Serialize for TrailingOptTuple<(H, $(Option<$ty>,)+)> {
+            fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                let present = [$((self.0).$idx.is_some()),+];
+                let count = present.iter().rposition(|p| *p).map_or(0, |last| last + 1);
+
+                let mut tup = serializer.serialize_tuple(2 + count)?;
+                tup.serialize_element(&(count as u8))?;
+                tup.serialize_element(&(self.0).0)?;
+                $(
+                    match (self.0).$idx {
+                        Some(ref value) if $idx == count => tup.serialize_element(value)?,
+                        ref opt if $idx < count => tup.serialize_element(opt)?,
+                        _ => {}
+                    }
+                )+
+                tup.end()
+            }
+        }
+
+        impl<'de, H, $($ty),+> Deserialize<'de> for TrailingOptTuple<(H, $(Option<$ty>,)+)>
+        where
+            H: Deserialize<'de>,
+            $($ty: Deserialize<'de>,)+
+        {
+            fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                struct TupleVisitor<H, $($ty),+>(core::marker::PhantomData<(H, $($ty),+)>);
+
+                impl<'de, H, $($ty),+> Visitor<'de> for TupleVisitor<H, $($ty),+>
+                where
+                    H: Deserialize<'de>,
+                    $($ty: Deserialize<'de>,)+
+                {
+                    type Value = TrailingOptTuple<(H, $(Option<$ty>,)+)>;
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                        write!(formatter, "a tuple with up to {} trailing optional elements", $len)
+                    }
+
+                    fn visit_seq<A>(self, mut seq: A) -> core::result::Result<Self::Value, A::Error>
+                    where
+                        A: SeqAccess<'de>,
+                    {
+                        let count: u8 = seq
+                            .next_element()?
+                            .ok_or_else(|| A::Error::invalid_length(0, &self))?;
+                        let count = usize::from(count);
+                        if count > $len {
+                            return Err(A::Error::invalid_length(count, &self));
+                        }
+                        let head = seq
+                            .next_element()?
+                            .ok_or_else(|| A::Error::invalid_length(1, &self))?;
+                        Ok(TrailingOptTuple((
+                            head,
+                            $(
+                                if $idx < count {
+                                    seq.next_element()?
+                                        .ok_or_else(|| A::Error::invalid_length($idx + 1, &self))?
+                                } else if $idx == count {
+                                    Some(
+                                        seq.next_element()?
+                                            .ok_or_else(|| A::Error::invalid_length($idx + 1, &self))?,
+                                    )
+                                } else {
+                                    None
+                                },
+                            )+
+                        )))
+                    }
+                }
+
+                deserializer.deserialize_tuple(1 + $len + 1, TupleVisitor(core::marker::PhantomData))
+            }
+        }
+    };
+}
+
+impl_trailing_opt_tuple!(1 => 1 T1);
+impl_trailing_opt_tuple!(2 => 1 T1, 2 T2);
+impl_trailing_opt_tuple!(3 => 1 T1, 2 T2, 3 T3);
+impl_trailing_opt_tuple!(4 => 1 T1, 2 T2, 3 T3, 4 T4);
+impl_trailing_opt_tuple!(5 => 1 T1, 2 T2, 3 T3, 4 T4, 5 T5);
+impl_trailing_opt_tuple!(6 => 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6);
+impl_trailing_opt_tuple!(7 => 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7);
+impl_trailing_opt_tuple!(8 => 1 T1, 2 T2, 3 T3, 4 T4, 5 T5, 6 T6, 7 T7, 8 T8);
+
+#[cfg(test)]
+mod tests {
+    use super::{TrailingDiscriminant, TrailingOptTuple};
+    use crate::Error;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Frame {
+        Level(#[serde(with = "crate::fixint::le")] u32),
+        Color { r: u8, g: u8, b: u8, a: u8 },
+        Count(u32),
+        Stop,
+    }
+
+    type Trailing = TrailingDiscriminant<Frame, 4>;
+
+    #[test]
+    fn trailing_discriminant() {
+        let mut buf = [0u8; 16];
+        let cases: [(Frame, &[u8]); 3] = [
+            (Frame::Level(0x1234_5678), &[0x78, 0x56, 0x34, 0x12, 0x00]),
+            (
+                Frame::Color {
+                    r: 1,
+                    g: 2,
+                    b: 3,
+                    a: 4,
+                },
+                &[0x01, 0x02, 0x03, 0x04, 0x01],
+            ),
+            // A varint that happens to be four bytes long
+            (Frame::Count(0x0FFF_FFFF), &[0xFF, 0xFF, 0xFF, 0x7F, 0x02]),
+        ];
+
+        for (data, ser_rep) in cases {
+            let input = TrailingDiscriminant(data);
+            let used = crate::to_slice(&input, &mut buf).unwrap();
+            assert_eq!(used, ser_rep);
+            assert_eq!(crate::from_bytes::<Trailing>(used), Ok(input));
+        }
+
+        // Unknown discriminants are rejected as usual
+        let res = crate::from_bytes::<Trailing>(&[0x00, 0x00, 0x00, 0x00, 0x09]);
+        assert_eq!(res, Err(Error::SerdeDeCustom));
+    }
+
+    #[test]
+    fn trailing_discriminant_variable_length() {
+        let mut buf = [0u8; 16];
+
+        // Variants with data of any other size fail to serialize
+        let res = crate::to_slice(&TrailingDiscriminant::<_, 4>(Frame::Count(300)), &mut buf);
+        assert_eq!(res, Err(Error::SerdeSerCustom));
+        let res = crate::to_slice(&TrailingDiscriminant::<_, 4>(Frame::Stop), &mut buf);
+        assert_eq!(res, Err(Error::SerdeSerCustom));
+        let res = crate::to_slice(&TrailingDiscriminant::<_, 2>(Frame::Level(0)), &mut buf);
+        assert_eq!(res, Err(Error::SerdeSerCustom));
+
+        // ...and data that doesn't match the size of the variant fails to deserialize
+        let res = crate::from_bytes::<Trailing>(&[0x05, 0x00, 0x00, 0x00, 0x02]);
+        assert_eq!(res, Err(Error::SerdeDeCustom));
+    }
+
+    #[test]
+    fn trailing_opt_tuple() {
+        type Params = TrailingOptTuple<(u32, Option<u32>, Option<u32>)>;
+
+        let mut buf = [0u8; 16];
+        let mut plain = [0u8; 16];
+        let cases: [(Params, &[u8]); 4] = [
+            (TrailingOptTuple((5, None, None)), &[0x00, 0x05]),
+            (TrailingOptTuple((5, Some(1), None)), &[0x01, 0x05, 0x01]),
+            (
+                TrailingOptTuple((5, None, Some(2))),
+                &[0x02, 0x05, 0x00, 0x02],
+            ),
+            (
+                TrailingOptTuple((5, Some(1), Some(2))),
+                &[0x02, 0x05, 0x01, 0x01, 0x02],
+            ),
+        ];
+
+        for (input, ser_rep) in cases {
+            let used = crate::to_slice(&input, &mut buf).unwrap();
+            assert_eq!(used, ser_rep);
+            assert_eq!(crate::from_bytes::<Params>(used), Ok(input));
+
+            // Never larger than the plain tuple, and smaller when it ends with a `None`
+            let plain = crate::to_slice(&input.0, &mut plain).unwrap();
+            if input.0 .2.is_none() {
+                assert!(used.len() < plain.len());
+            } else {
+                assert_eq!(used.len(), plain.len());
+            }
+        }
+
+        // More optional elements than the tuple has are rejected
+        let res = crate::from_bytes::<Params>(&[0x03, 0x05, 0x01, 0x01, 0x01, 0x02]);
+        assert_eq!(res, Err(Error::SerdeDeCustom));
+        let res = crate::from_bytes::<Params>(&[0x02, 0x05, 0x01]);
+        assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+    }
+}
@@ -0,0 +1,85 @@
+//! # UUIDs
+//!
+//! Use with the `#[serde(with = "postcard::uuid")]` field attribute, to encode a
+//! `uuid::Uuid` as exactly 16 bytes on the wire, in the same way as a `[u8; 16]`.
+//!
+//! The `serde` implementation of `uuid::Uuid` itself depends on the format: for
+//! postcard (which is not human readable), it is encoded like a byte slice, with a
+//! length prefix, taking 17 bytes. Formats that are human readable (such as JSON)
+//! use the 36 character hyphenated string instead. This module always uses the
+//! 16 raw bytes, and doesn't require the `serde` feature of the `uuid` crate.
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use uuid::Uuid;
+//!
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct Device {
+//!     #[serde(with = "postcard::uuid")]
+//!     id: Uuid,
+//! }
+//!
+//! let dev = Device { id: Uuid::from_u128(0x936DA01F_9ABD_4D9D_80C7_02AF85C822A8) };
+//!
+//! let mut buf = [0u8; 32];
+//! let used = postcard::to_slice(&dev, &mut buf).unwrap();
+//! assert_eq!(used.len(), 16);
+//! assert_eq!(used, dev.id.as_bytes());
+//!
+//! let out: Device = postcard::from_bytes(used).unwrap();
+//! assert_eq!(out, dev);
+//! ```
+
+use ::uuid::Uuid;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serialize the UUID as 16 raw bytes, in the same way as a `[u8; 16]`.
+pub fn serialize<S>(val: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    val.as_bytes().serialize(serializer)
+}
+
+/// Deserialize the UUID from 16 raw bytes, in the same way as a `[u8; 16]`.
+pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    <[u8; 16]>::deserialize(deserializer).map(Uuid::from_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use ::uuid::Uuid;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Tagged {
+        #[serde(with = "crate::uuid")]
+        id: Uuid,
+        seq: u16,
+    }
+
+    #[test]
+    fn uuid_raw_bytes() {
+        let id = Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+        let input = Tagged { id, seq: 300 };
+
+        let mut buf = [0u8; 32];
+        let used = crate::to_slice(&input, &mut buf).unwrap();
+        assert_eq!(used.len(), 18);
+        assert_eq!(
+            &used[..16],
+            &[
+                0x67, 0xE5, 0x50, 0x44, 0x10, 0xB1, 0x42, 0x6F, 0x92, 0x47, 0xBB, 0x68, 0x0E, 0x5F,
+                0xE0, 0xC8
+            ]
+        );
+        assert_eq!(&used[16..], &[0xAC, 0x02]);
+        assert_eq!(crate::from_bytes::<Tagged>(used), Ok(input));
+
+        let res = crate::from_bytes::<Tagged>(&used[..15]);
+        assert_eq!(res, Err(crate::Error::DeserializeUnexpectedEnd));
+    }
+}
@@ -0,0 +1,188 @@
+//! Structural validation and partial reads of a message, using its schema
+
+use serde::Deserialize;
+
+use crate::de::deserializer::Deserializer;
+use crate::de_flavors::Slice;
+use crate::error::{Error, Result};
+use crate::schema::{NamedType, Schema, SdmTy, Varint};
+
+/// Check that a byte slice holds a well formed message of type `T`, without
+/// deserializing it.
+///
+/// This walks the bytes using the [`Schema`] of `T`, checking every tag, length,
+/// varint, `char`, and string along the way, but never constructs a `T`, and
+/// never allocates. The unused portion (if any) of the byte slice is ignored,
+/// the same as [`from_bytes()`](crate::from_bytes).
+///
+/// Any error found is the same one that deserializing `T` would have returned,
+/// so this can be used as a first pass before deserializing, to avoid partially
+/// building a value from malformed input. See also [`from_bytes_validated()`].
+///
+/// NOTE: Checks done by custom `Deserialize` impls (e.g. rejecting some values
+/// of a field) can not be seen in the schema, and are not performed here.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::experimental::schema::{validate, Schema};
+/// use postcard::Error;
+///
+/// #[derive(Schema)]
+/// struct Reading {
+///     sensor: Option<u8>,
+///     value: u16,
+/// }
+///
+/// assert_eq!(validate::<Reading>(&[0x01, 0x03, 0xAC, 0x02]), Ok(()));
+/// assert_eq!(
+///     validate::<Reading>(&[0x02, 0x03, 0xAC, 0x02]),
+///     Err(Error::DeserializeBadOption)
+/// );
+/// ```
+pub fn validate<T>(s: &[u8]) -> Result<()>
+where
+    T: Schema + ?Sized,
+{
+    let mut deserializer = Deserializer::from_bytes(s);
+    walk(&mut deserializer, T::SCHEMA.ty)
+}
+
+/// Deserialize a message of type `T` from a byte slice, only after checking it
+/// with [`validate()`]. The unused portion (if any) of the byte slice is not
+/// returned.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::experimental::schema::{from_bytes_validated, Schema};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, Schema, Debug, PartialEq)]
+/// struct Reading {
+///     sensor: u8,
+///     value: i32,
+/// }
+///
+/// let msg: Reading = from_bytes_validated(&[0x03, 0xD7, 0x04]).unwrap();
+/// assert_eq!(msg, Reading { sensor: 3, value: -300 });
+/// ```
+pub fn from_bytes_validated<'a, T>(s: &'a [u8]) -> Result<T>
+where
+    T: Deserialize<'a> + Schema,
+{
+    validate::<T>(s)?;
+    crate::from_bytes(s)
+}
+
+/// Deserialize only the field named `field` of a message of the struct type `T`,
+/// from a byte slice.
+///
+/// The fields before it are skipped using the [`Schema`] of `T`, without deserializing
+/// them, and the fields after it are not read at all. Skipping a field still walks
+/// its bytes, as postcard doesn't record the size of variable length items, but
+/// never constructs a value or allocates. The unused portion (if any) of the byte
+/// slice is ignored.
+///
+/// If `T` is not a struct with named fields, or has no field named `field`,
+/// [`Error::SerdeDeCustom`] is returned, the same as for a missing field.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::experimental::schema::{from_bytes_field, Schema};
+///
+/// #[derive(Schema)]
+/// struct Reading {
+///     sensor: Option<u8>,
+///     value: u16,
+///     label: u8,
+/// }
+///
+/// let value: u16 = from_bytes_field::<Reading, _>(&[0x01, 0x03, 0xAC, 0x02, 0x07], "value").unwrap();
+/// assert_eq!(value, 300);
+/// ```
+pub fn from_bytes_field<'a, T, F>(s: &'a [u8], field: &str) -> Result<F>
+where
+    T: Schema + ?Sized,
+    F: Deserialize<'a>,
+{
+    let fields = match T::SCHEMA.ty {
+        SdmTy::Struct(fields) => fields,
+        _ => return Err(Error::SerdeDeCustom),
+    };
+    let index = fields
+        .iter()
+        .position(|f| f.name == field)
+        .ok_or(Error::SerdeDeCustom)?;
+
+    let mut deserializer = Deserializer::from_bytes(s);
+    for earlier in &fields[..index] {
+        walk(&mut deserializer, earlier.ty.ty)?;
+    }
+    F::deserialize(&mut deserializer)
+}
+
+fn walk<'a>(de: &mut Deserializer<'a, Slice<'a>>, ty: &SdmTy) -> Result<()> {
+    match ty {
+        SdmTy::Bool => skip::<bool>(de)?,
+        SdmTy::I8 | SdmTy::U8 => skip::<u8>(de)?,
+        SdmTy::Varint(varint) => match varint {
+            Varint::I16 => skip::<i16>(de)?,
+            Varint::I32 => skip::<i32>(de)?,
+            Varint::I64 | Varint::Isize => skip::<i64>(de)?,
+            Varint::I128 => skip::<i128>(de)?,
+            Varint::U16 => skip::<u16>(de)?,
+            Varint::U32 => skip::<u32>(de)?,
+            Varint::U64 | Varint::Usize => skip::<u64>(de)?,
+            Varint::U128 => skip::<u128>(de)?,
+        },
+        SdmTy::F32 => skip::<f32>(de)?,
+        SdmTy::F64 => skip::<f64>(de)?,
+        SdmTy::Char => skip::<char>(de)?,
+        SdmTy::String => skip::<&str>(de)?,
+        SdmTy::ByteArray => skip::<&[u8]>(de)?,
+        SdmTy::Option(inner) => match u8::deserialize(&mut *de)? {
+            0 => {}
+            1 => walk(de, inner.ty)?,
+            _ => return Err(Error::DeserializeBadOption),
+        },
+        SdmTy::Unit | SdmTy::UnitStruct | SdmTy::UnitVariant => {}
+        SdmTy::NewtypeStruct(inner) | SdmTy::NewtypeVariant(inner) => walk(de, inner.ty)?,
+        SdmTy::Seq(inner) => {
+            for _ in 0..de.try_take_length()? {
+                walk(de, inner.ty)?;
+            }
+        }
+        SdmTy::Tuple(items) | SdmTy::TupleStruct(items) | SdmTy::TupleVariant(items) => {
+            walk_all(de, items)?
+        }
+        SdmTy::Map { key, val } => {
+            for _ in 0..de.try_take_length()? {
+                walk(de, key.ty)?;
+                walk(de, val.ty)?;
+            }
+        }
+        SdmTy::Struct(fields) | SdmTy::StructVariant(fields) => {
+            for field in fields.iter() {
+                walk(de, field.ty.ty)?;
+            }
+        }
+        SdmTy::Enum(variants) => {
+            let index = u32::deserialize(&mut *de)?;
+            let variant = variants
+                .get(index as usize)
+                .ok_or(Error::DeserializeBadEnum)?;
+            walk(de, variant.ty)?;
+        }
+    }
+    Ok(())
+}
+
+fn skip<'a, T: Deserialize<'a>>(de: &mut Deserializer<'a, Slice<'a>>) -> Result<()> {
+    T::deserialize(de).map(|_| ())
+}
+
+fn walk_all<'a>(de: &mut Deserializer<'a, Slice<'a>>, items: &[&NamedType]) -> Result<()> {
+    items.iter().try_for_each(|item| walk(de, item.ty))
+}
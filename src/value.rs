@@ -0,0 +1,191 @@
+//! A generic representation of a message, decoded using its schema
+
+use serde::Deserialize;
+
+use crate::de::deserializer::Deserializer;
+use crate::error::{Error, Result};
+use crate::schema::{NamedType, NamedValue, Schema, SdmTy, Varint};
+
+extern crate alloc;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+/// A generic, dynamically typed representation of a postcard message.
+///
+/// As postcard messages are not self-describing, this can't be decoded on its own,
+/// and is produced by [`from_bytes_as_value()`] using the [`Schema`] of the message.
+/// This is intended for inspecting messages, e.g. in protocol analyzers or while
+/// debugging, without access to the Rust types.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PostcardValue<'a> {
+    /// A `bool`
+    Bool(bool),
+    /// An `i8`
+    I8(i8),
+    /// An `i16`
+    I16(i16),
+    /// An `i32`
+    I32(i32),
+    /// An `i64`, or an `isize`
+    I64(i64),
+    /// An `i128`
+    I128(i128),
+    /// A `u8`
+    U8(u8),
+    /// A `u16`
+    U16(u16),
+    /// A `u32`
+    U32(u32),
+    /// A `u64`, or a `usize`
+    U64(u64),
+    /// A `u128`
+    U128(u128),
+    /// An `f32`
+    F32(f32),
+    /// An `f64`
+    F64(f64),
+    /// A `char`
+    Char(char),
+    /// A string, borrowed from the input
+    String(&'a str),
+    /// A byte array, borrowed from the input
+    Bytes(&'a [u8]),
+    /// An `Option`, which may contain a value
+    Option(Option<Box<PostcardValue<'a>>>),
+    /// The unit type `()`, or a unit struct
+    Unit,
+    /// A sequence of values of the same type
+    Seq(Vec<PostcardValue<'a>>),
+    /// A tuple, or a tuple struct
+    Tuple(Vec<PostcardValue<'a>>),
+    /// A map, as a list of key-value pairs
+    Map(Vec<(PostcardValue<'a>, PostcardValue<'a>)>),
+    /// A struct, as a list of its fields
+    Struct(Vec<(&'static str, PostcardValue<'a>)>),
+    /// A variant of an enum
+    Enum {
+        /// The name of the variant
+        variant: &'static str,
+        /// The contents of the variant, using [`PostcardValue::Unit`] for unit variants
+        value: Box<PostcardValue<'a>>,
+    },
+}
+
+/// Deserialize a message of type `T` from a byte slice into a [`PostcardValue`], using
+/// the [`Schema`] of `T` to drive decoding.
+///
+/// The message is expected to use the default [`Config`](crate::Config). The unused
+/// portion (if any) of the byte slice is not returned.
+///
+/// ## Example
+///
+/// ```rust
+/// use postcard::experimental::schema::{from_bytes_as_value, PostcardValue, Schema};
+///
+/// #[derive(Schema)]
+/// struct Reading {
+///     sensor: u8,
+///     value: i32,
+/// }
+///
+/// let value = from_bytes_as_value::<Reading>(&[0x03, 0xD7, 0x04]).unwrap();
+/// assert_eq!(
+///     value,
+///     PostcardValue::Struct(vec![
+///         ("sensor", PostcardValue::U8(3)),
+///         ("value", PostcardValue::I32(-300)),
+///     ])
+/// );
+/// ```
+pub fn from_bytes_as_value<T>(s: &[u8]) -> Result<PostcardValue<'_>>
+where
+    T: Schema + ?Sized,
+{
+    let mut deserializer = Deserializer::from_bytes(s);
+    decode(&mut deserializer, T::SCHEMA.ty)
+}
+
+fn decode<'a>(
+    de: &mut Deserializer<'a, crate::de_flavors::Slice<'a>>,
+    ty: &SdmTy,
+) -> Result<PostcardValue<'a>> {
+    Ok(match ty {
+        SdmTy::Bool => PostcardValue::Bool(bool::deserialize(&mut *de)?),
+        SdmTy::I8 => PostcardValue::I8(i8::deserialize(&mut *de)?),
+        SdmTy::U8 => PostcardValue::U8(u8::deserialize(&mut *de)?),
+        SdmTy::Varint(varint) => match varint {
+            Varint::I16 => PostcardValue::I16(i16::deserialize(&mut *de)?),
+            Varint::I32 => PostcardValue::I32(i32::deserialize(&mut *de)?),
+            Varint::I64 | Varint::Isize => PostcardValue::I64(i64::deserialize(&mut *de)?),
+            Varint::I128 => PostcardValue::I128(i128::deserialize(&mut *de)?),
+            Varint::U16 => PostcardValue::U16(u16::deserialize(&mut *de)?),
+            Varint::U32 => PostcardValue::U32(u32::deserialize(&mut *de)?),
+            Varint::U64 | Varint::Usize => PostcardValue::U64(u64::deserialize(&mut *de)?),
+            Varint::U128 => PostcardValue::U128(u128::deserialize(&mut *de)?),
+        },
+        SdmTy::F32 => PostcardValue::F32(f32::deserialize(&mut *de)?),
+        SdmTy::F64 => PostcardValue::F64(f64::deserialize(&mut *de)?),
+        SdmTy::Char => PostcardValue::Char(char::deserialize(&mut *de)?),
+        SdmTy::String => PostcardValue::String(<&str>::deserialize(&mut *de)?),
+        SdmTy::ByteArray => PostcardValue::Bytes(<&[u8]>::deserialize(&mut *de)?),
+        SdmTy::Option(inner) => match u8::deserialize(&mut *de)? {
+            0 => PostcardValue::Option(None),
+            1 => PostcardValue::Option(Some(Box::new(decode(de, inner.ty)?))),
+            _ => return Err(Error::DeserializeBadOption),
+        },
+        SdmTy::Unit | SdmTy::UnitStruct | SdmTy::UnitVariant => PostcardValue::Unit,
+        SdmTy::NewtypeStruct(inner) | SdmTy::NewtypeVariant(inner) => decode(de, inner.ty)?,
+        // The schema derive describes newtypes as tuples with a single field
+        SdmTy::TupleStruct([inner]) | SdmTy::TupleVariant([inner]) => decode(de, inner.ty)?,
+        SdmTy::Seq(inner) => {
+            let len = de.try_take_length()?;
+            PostcardValue::Seq(
+                (0..len)
+                    .map(|_| decode(de, inner.ty))
+                    .collect::<Result<_>>()?,
+            )
+        }
+        SdmTy::Tuple(items) | SdmTy::TupleStruct(items) | SdmTy::TupleVariant(items) => {
+            PostcardValue::Tuple(decode_all(de, items)?)
+        }
+        SdmTy::Map { key, val } => {
+            let len = de.try_take_length()?;
+            let mut entries = Vec::new();
+            for _ in 0..len {
+                let k = decode(de, key.ty)?;
+                entries.push((k, decode(de, val.ty)?));
+            }
+            PostcardValue::Map(entries)
+        }
+        SdmTy::Struct(fields) | SdmTy::StructVariant(fields) => {
+            PostcardValue::Struct(decode_fields(de, fields)?)
+        }
+        SdmTy::Enum(variants) => {
+            let index = u32::deserialize(&mut *de)?;
+            let variant = variants
+                .get(index as usize)
+                .ok_or(Error::DeserializeBadEnum)?;
+            PostcardValue::Enum {
+                variant: variant.name,
+                value: Box::new(decode(de, variant.ty)?),
+            }
+        }
+    })
+}
+
+fn decode_all<'a>(
+    de: &mut Deserializer<'a, crate::de_flavors::Slice<'a>>,
+    items: &[&NamedType],
+) -> Result<Vec<PostcardValue<'a>>> {
+    items.iter().map(|item| decode(de, item.ty)).collect()
+}
+
+fn decode_fields<'a>(
+    de: &mut Deserializer<'a, crate::de_flavors::Slice<'a>>,
+    fields: &[&'static NamedValue],
+) -> Result<Vec<(&'static str, PostcardValue<'a>)>> {
+    fields
+        .iter()
+        .map(|field| Ok((field.name, decode(de, field.ty.ty)?)))
+        .collect()
+}
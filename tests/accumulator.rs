@@ -37,10 +37,10 @@ fn reader() {
         }
 
         let buf = &raw_buf[..ct];
-        let mut window = &buf[..];
+        let mut window = buf;
 
         'cobs: while !window.is_empty() {
-            window = match cobs_buf.feed::<Huge>(&window) {
+            window = match cobs_buf.feed::<Huge>(window) {
                 FeedResult::Consumed => break 'cobs,
                 FeedResult::OverFull(new_wind) => new_wind,
                 FeedResult::DeserError(new_wind) => new_wind,
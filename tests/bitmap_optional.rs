@@ -0,0 +1,165 @@
+#![cfg(feature = "experimental-derive")]
+
+use postcard::experimental::packed::BitmapOptional;
+use postcard::{from_bytes, to_slice, Error};
+use serde::{Deserialize, Serialize};
+
+#[derive(BitmapOptional, Debug, PartialEq, Clone)]
+struct Settings {
+    id: u16,
+    baud: Option<u32>,
+    parity: Option<u8>,
+    name: Option<heapless::String<8>>,
+    timeout_ms: Option<u16>,
+    retries: Option<u8>,
+    enabled: bool,
+    offset: Option<i32>,
+    gain: Option<f32>,
+    channel: Option<u8>,
+}
+
+// The same fields, with serde's own derives
+#[derive(Serialize, Deserialize)]
+struct Plain {
+    id: u16,
+    baud: Option<u32>,
+    parity: Option<u8>,
+    name: Option<heapless::String<8>>,
+    timeout_ms: Option<u16>,
+    retries: Option<u8>,
+    enabled: bool,
+    offset: Option<i32>,
+    gain: Option<f32>,
+    channel: Option<u8>,
+}
+
+#[test]
+fn bitmap_optional() {
+    let mut buf = [0u8; 64];
+    let input = Settings {
+        id: 300,
+        baud: None,
+        parity: None,
+        name: None,
+        timeout_ms: Some(1000),
+        retries: None,
+        enabled: true,
+        offset: None,
+        gain: None,
+        channel: Some(3),
+    };
+    let used = to_slice(&input, &mut buf).unwrap();
+    // Eight `Option`s take one byte, followed by the other fields and present values
+    assert_eq!(used, &[0b1000_1000, 0xAC, 0x02, 0xE8, 0x07, 0x01, 0x03]);
+    assert_eq!(from_bytes(used), Ok(input.clone()));
+
+    let mut plain_buf = [0u8; 64];
+    let plain = Plain {
+        id: 300,
+        baud: None,
+        parity: None,
+        name: None,
+        timeout_ms: Some(1000),
+        retries: None,
+        enabled: true,
+        offset: None,
+        gain: None,
+        channel: Some(3),
+    };
+    let plain_used = to_slice(&plain, &mut plain_buf).unwrap();
+    assert_eq!(plain_used.len(), 14);
+    assert!(used.len() < plain_used.len());
+
+    // All present, and all absent
+    let full = Settings {
+        id: 1,
+        baud: Some(115_200),
+        parity: Some(2),
+        name: Some("uart0".into()),
+        timeout_ms: Some(0),
+        retries: Some(5),
+        enabled: false,
+        offset: Some(-1),
+        gain: Some(0.5),
+        channel: Some(0),
+    };
+    let used = to_slice(&full, &mut buf).unwrap();
+    assert_eq!(used[0], 0xFF);
+    assert_eq!(from_bytes(used), Ok(full));
+
+    let empty = Settings {
+        id: 1,
+        baud: None,
+        parity: None,
+        name: None,
+        timeout_ms: None,
+        retries: None,
+        enabled: false,
+        offset: None,
+        gain: None,
+        channel: None,
+    };
+    let used = to_slice(&empty, &mut buf).unwrap();
+    assert_eq!(used, &[0x00, 0x01, 0x00]);
+    assert_eq!(from_bytes(used), Ok(empty));
+
+    // A present value that is missing
+    let res = from_bytes::<Settings>(&[0b1000_0000, 0x01, 0x00]);
+    assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+}
+
+#[derive(BitmapOptional, Debug, PartialEq)]
+struct Interleaved {
+    first: u8,
+    a: Option<u8>,
+    second: u16,
+    b: Option<u16>,
+    c: Option<u8>,
+    third: bool,
+    d: Option<i8>,
+}
+
+#[derive(Serialize)]
+struct PlainInterleaved {
+    first: u8,
+    a: Option<u8>,
+    second: u16,
+    b: Option<u16>,
+    c: Option<u8>,
+    third: bool,
+    d: Option<i8>,
+}
+
+#[test]
+fn required_fields_stay_in_order() {
+    let mut buf = [0u8; 32];
+    let input = Interleaved {
+        first: 1,
+        a: None,
+        second: 2,
+        b: Some(3),
+        c: None,
+        third: true,
+        d: Some(-4),
+    };
+    let used = to_slice(&input, &mut buf).unwrap();
+    // Only the `Option` fields are in the bitmap, and the present values are in field order
+    assert_eq!(used, &[0b0000_1010, 0x01, 0x02, 0x03, 0x01, 0xFC]);
+    assert_eq!(from_bytes(used), Ok(input));
+
+    let mut plain_buf = [0u8; 32];
+    let plain = PlainInterleaved {
+        first: 1,
+        a: None,
+        second: 2,
+        b: Some(3),
+        c: None,
+        third: true,
+        d: Some(-4),
+    };
+    let plain_used = to_slice(&plain, &mut plain_buf).unwrap();
+    assert_eq!(
+        plain_used,
+        &[0x01, 0x00, 0x02, 0x01, 0x03, 0x00, 0x01, 0x01, 0xFC]
+    );
+}
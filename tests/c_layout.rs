@@ -0,0 +1,66 @@
+#![cfg(feature = "experimental-derive")]
+
+use postcard::experimental::c_layout::CLayout;
+use postcard::{from_bytes, to_slice, Error};
+
+#[repr(C)]
+#[derive(CLayout, Debug, PartialEq, Clone, Copy)]
+struct Pair {
+    tag: u8,
+    value: u32,
+}
+
+#[repr(C)]
+#[derive(CLayout, Debug, PartialEq, Clone, Copy)]
+struct Record {
+    flags: u16,
+    pair: Pair,
+    valid: bool,
+    samples: [i16; 3],
+    scale: f64,
+}
+
+/// The memory of a value, as C code would see it
+fn memory_of<T>(val: &T) -> &[u8] {
+    unsafe {
+        core::slice::from_raw_parts((val as *const T).cast::<u8>(), core::mem::size_of::<T>())
+    }
+}
+
+#[test]
+fn c_layout_padding() {
+    let mut buf = [0u8; 64];
+    let input = Pair {
+        tag: 0x42,
+        value: 0x1234_5678,
+    };
+    let used = to_slice(&input, &mut buf).unwrap();
+    // Three bytes of padding after the `u8`, so the `u32` is aligned
+    assert_eq!(used, &[0x42, 0x00, 0x00, 0x00, 0x78, 0x56, 0x34, 0x12]);
+    assert_eq!(from_bytes(used), Ok(input));
+
+    // The contents of padding bytes are ignored
+    let out: Pair = from_bytes(&[0x42, 0xFF, 0xFF, 0xFF, 0x78, 0x56, 0x34, 0x12]).unwrap();
+    assert_eq!(out, input);
+    let res = from_bytes::<Pair>(&[0x42, 0x00, 0x00, 0x00, 0x78]);
+    assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+}
+
+#[cfg(target_endian = "little")]
+#[test]
+fn c_layout_matches_memory() {
+    let mut buf = [0u8; 64];
+
+    // Zero the padding of the in-memory value, so that it can be compared
+    let mut input: Record = unsafe { core::mem::zeroed() };
+    input.flags = 0xBEEF;
+    input.pair = Pair { tag: 7, value: 300 };
+    input.valid = true;
+    input.samples = [-1, 0, i16::MAX];
+    input.scale = 0.5;
+
+    let used = to_slice(&input, &mut buf).unwrap();
+    assert_eq!(used.len(), core::mem::size_of::<Record>());
+    assert_eq!(used, memory_of(&input));
+    assert_eq!(from_bytes(used), Ok(input));
+}
@@ -50,6 +50,7 @@ struct NewTypeStruct(u32);
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
 struct TupleStruct((u8, u16));
 
+#[allow(dead_code)]
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
 struct RefStruct<'a> {
     bytes: &'a [u8],
@@ -98,13 +99,13 @@ fn loopback() {
     // Enums!
     test_one(BasicEnum::Bim, &[0x01]);
     test_one(
-        DataEnum::Bim(u64::max_value()),
+        DataEnum::Bim(u64::MAX),
         &[
             0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01,
         ],
     );
-    test_one(DataEnum::Bib(u16::max_value()), &[0x00, 0xFF, 0xFF, 0x03]);
-    test_one(DataEnum::Bap(u8::max_value()), &[0x02, 0xFF]);
+    test_one(DataEnum::Bib(u16::MAX), &[0x00, 0xFF, 0xFF, 0x03]);
+    test_one(DataEnum::Bap(u8::MAX), &[0x02, 0xFF]);
     test_one(
         DataEnum::Kim(EnumStruct {
             eight: 0xF0,
@@ -121,6 +122,11 @@ fn loopback() {
     );
     test_one(DataEnum::Sho(0x6969, 0x07), &[0x05, 0xE9, 0xD2, 0x01, 0x07]);
 
+    // `Result` is an enum with `Ok` and `Err` variants
+    test_one(Ok::<u32, DataEnum>(5), &[0x00, 0x05]);
+    test_one(Err::<u32, DataEnum>(DataEnum::Bap(7)), &[0x01, 0x02, 0x07]);
+    test_one(Ok::<Result<u8, u8>, ()>(Err(0xFF)), &[0x00, 0x01, 0xFF]);
+
     // Tuples!
     test_one((0x12u8, 0xC7A5u16), &[0x12, 0xA5, 0x8F, 0x03]);
 
@@ -156,7 +162,7 @@ fn loopback() {
 
 #[cfg(feature = "heapless")]
 #[track_caller]
-fn test_one<'a, 'de, T>(data: T, ser_rep: &'a [u8])
+fn test_one<T>(data: T, ser_rep: &[u8])
 where
     T: Serialize + DeserializeOwned + Eq + PartialEq + Debug,
 {
@@ -173,13 +179,237 @@ where
     }
 }
 
+#[cfg(feature = "heapless")]
+#[test]
+fn configs() {
+    use postcard::{
+        from_bytes_with_config, to_vec_with_config, Config, IntEncoding, LengthEncoding,
+    };
+
+    #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+    struct Readings {
+        id: u16,
+        vals: Vec<u32, 4>,
+    }
+
+    let data = Readings {
+        id: 0x0102,
+        vals: Vec::from_slice(&[1, 300]).unwrap(),
+    };
+
+    let cases: [(LengthEncoding, IntEncoding, &[u8]); 4] = [
+        (
+            LengthEncoding::Varint,
+            IntEncoding::Varint,
+            &[0x82, 0x02, 0x02, 0x01, 0xAC, 0x02],
+        ),
+        (
+            LengthEncoding::Varint,
+            IntEncoding::Fixed,
+            &[
+                0x02, 0x01, 0x02, 0x01, 0x00, 0x00, 0x00, 0x2C, 0x01, 0x00, 0x00,
+            ],
+        ),
+        (
+            LengthEncoding::Fixed,
+            IntEncoding::Varint,
+            &[
+                0x82, 0x02, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0xAC, 0x02,
+            ],
+        ),
+        (
+            LengthEncoding::Fixed,
+            IntEncoding::Fixed,
+            &[
+                0x02, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+                0x2C, 0x01, 0x00, 0x00,
+            ],
+        ),
+    ];
+
+    for (length_encoding, int_encoding, ser_rep) in cases {
+        let config = Config::new()
+            .length_encoding(length_encoding)
+            .int_encoding(int_encoding);
+        let output: Vec<u8, 32> = to_vec_with_config(&data, config).unwrap();
+        assert_eq!(output.deref(), ser_rep);
+
+        let deserialized: Readings = from_bytes_with_config(output.deref(), config).unwrap();
+        assert_eq!(data, deserialized);
+    }
+
+    // The default configuration is the standard wire format
+    let output: Vec<u8, 32> = to_vec(&data).unwrap();
+    assert_eq!(output.deref(), cases[0].2);
+}
+
+#[test]
+fn char_varint() {
+    use postcard::{from_bytes_with_config, to_slice_with_config, CharEncoding, Config, Error};
+
+    let config = Config::new().char_encoding(CharEncoding::Varint);
+    let mut buf = [0u8; 8];
+
+    let cases: [(char, &[u8]); 3] = [
+        ('a', &[0x61]),
+        ('€', &[0xAC, 0x41]),
+        ('😀', &[0x80, 0xEC, 0x07]),
+    ];
+    for (data, ser_rep) in cases {
+        let used = to_slice_with_config(&data, &mut buf, config).unwrap();
+        assert_eq!(used, ser_rep);
+        let deserialized: char = from_bytes_with_config(used, config).unwrap();
+        assert_eq!(data, deserialized);
+    }
+
+    // A surrogate is not a Unicode scalar value
+    let res = from_bytes_with_config::<char>(&[0x80, 0xB0, 0x03], config);
+    assert_eq!(res, Err(Error::DeserializeBadChar));
+}
+
+#[test]
+fn fixed_128() {
+    use postcard::{from_bytes_with_config, to_slice_with_config, Config, IntEncoding};
+
+    #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+    struct Fixed128 {
+        #[serde(with = "postcard::fixint::le")]
+        x: u128,
+    }
+
+    let config = Config::new().int_encoding(IntEncoding::Fixed);
+    let mut buf = [0u8; 32];
+
+    for data in [u128::MAX, 0, 0x0102_0304_0506_0708_090A_0B0C_0D0E_0F10] {
+        let used = to_slice_with_config(&data, &mut buf, config).unwrap();
+        assert_eq!(used, &data.to_le_bytes());
+        assert_eq!(from_bytes_with_config::<u128>(used, config).unwrap(), data);
+
+        // The same layout is available per field, regardless of configuration
+        let used = postcard::to_slice(&Fixed128 { x: data }, &mut buf).unwrap();
+        assert_eq!(used, &data.to_le_bytes());
+        assert_eq!(from_bytes::<Fixed128>(used).unwrap(), Fixed128 { x: data });
+    }
+
+    for data in [
+        i128::MIN,
+        -0x0102_0304_0506_0708_090A_0B0C_0D0E_0F10,
+        0,
+        i128::MAX,
+    ] {
+        let used = to_slice_with_config(&data, &mut buf, config).unwrap();
+        assert_eq!(used.len(), 16);
+        assert_eq!(used, &data.to_le_bytes());
+        assert_eq!(from_bytes_with_config::<i128>(used, config).unwrap(), data);
+    }
+
+    // By default, 128-bit integers are still varint encoded
+    let used = postcard::to_slice(&u128::MAX, &mut buf).unwrap();
+    assert_eq!(used.len(), 19);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_containers() {
+    extern crate alloc;
+
+    use alloc::{boxed::Box, rc::Rc, sync::Arc};
+    use postcard::to_allocvec;
+
+    fn round_trip<T>(data: T, ser_rep: &[u8])
+    where
+        T: Serialize + DeserializeOwned + PartialEq + Debug,
+    {
+        let serialized = to_allocvec(&data).unwrap();
+        assert_eq!(serialized, ser_rep);
+        let deserialized: T = from_bytes(&serialized).unwrap();
+        assert_eq!(data, deserialized);
+    }
+
+    // Smart pointers are transparent on the wire
+    round_trip(Box::new(0xA5C7u32), &[0xC7, 0xCB, 0x02]);
+    round_trip(
+        Box::new(BasicU8S {
+            st: 0xABCD,
+            ei: 0xFE,
+            sf: 0x1234_4321_ABCD_DCBA,
+            tt: 0xACAC_ACAC,
+        }),
+        &[
+            0xCD, 0xD7, 0x02, 0xFE, 0xBA, 0xB9, 0xB7, 0xDE, 0x9A, 0xE4, 0x90, 0x9A, 0x12, 0xAC,
+            0xD9, 0xB2, 0xE5, 0x0A,
+        ],
+    );
+    round_trip(
+        Rc::new(alloc::string::String::from("hi")),
+        &[0x02, b'h', b'i'],
+    );
+    round_trip(Arc::new(alloc::vec![0x01u8, 0x02]), &[0x02, 0x01, 0x02]);
+
+    // Unsized contents can't borrow from the input, and are always copied into
+    // a new allocation
+    round_trip::<Rc<str>>(Rc::from("hi"), &[0x02, b'h', b'i']);
+    round_trip::<Arc<[u8]>>(Arc::from(&[0x01u8, 0x02][..]), &[0x02, 0x01, 0x02]);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_maps() {
+    extern crate alloc;
+
+    use alloc::{collections::BTreeMap, string::String};
+    use postcard::to_allocvec;
+
+    let mut input: BTreeMap<String, u32> = BTreeMap::new();
+    input.insert(String::from("a"), 1);
+    input.insert(String::from("bc"), 0x80);
+
+    let serialized = to_allocvec(&input).unwrap();
+    assert_eq!(
+        serialized,
+        &[0x02, 0x01, b'a', 0x01, 0x02, b'b', b'c', 0x80, 0x01]
+    );
+    let deserialized: BTreeMap<String, u32> = from_bytes(&serialized).unwrap();
+    assert_eq!(input, deserialized);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn alloc_maps_binary_keys() {
+    extern crate alloc;
+
+    use alloc::{collections::BTreeMap, vec::Vec};
+    use postcard::to_allocvec;
+
+    // Keys that are not valid UTF-8
+    let mut input: BTreeMap<Vec<u8>, u32> = BTreeMap::new();
+    input.insert(vec![0xFF, 0xFE], 1);
+    input.insert(vec![0x80], 300);
+    input.insert(vec![], 2);
+
+    let serialized = to_allocvec(&input).unwrap();
+    assert_eq!(
+        serialized,
+        &[0x03, 0x00, 0x02, 0x01, 0x80, 0xAC, 0x02, 0x02, 0xFF, 0xFE, 0x01]
+    );
+    let deserialized: BTreeMap<Vec<u8>, u32> = from_bytes(&serialized).unwrap();
+    assert_eq!(input, deserialized);
+
+    // Borrowed keys go through `deserialize_bytes`, with the same encoding
+    let borrowed: BTreeMap<&[u8], u32> = from_bytes(&serialized).unwrap();
+    assert_eq!(borrowed.get(&[0xFF, 0xFE][..]), Some(&1));
+    assert_eq!(borrowed.get(&[0x80][..]), Some(&300));
+    assert_eq!(borrowed.get(&[][..]), Some(&2));
+    assert_eq!(to_allocvec(&borrowed).unwrap(), serialized);
+}
+
 #[cfg(feature = "use-std")]
 #[test]
 fn std_io_loopback() {
     use postcard::from_io;
     use postcard::to_io;
 
-    fn test_io<'a, 'de, T>(data: T, ser_rep: &'a [u8])
+    fn test_io<T>(data: T, ser_rep: &[u8])
     where
         T: Serialize + DeserializeOwned + Eq + PartialEq + Debug,
     {
@@ -216,7 +446,7 @@ fn std_eio_loopback() {
     use postcard::from_eio;
     use postcard::to_eio;
 
-    fn test_io<'a, 'de, T>(data: T, ser_rep: &'a [u8])
+    fn test_io<T>(data: T, ser_rep: &[u8])
     where
         T: Serialize + DeserializeOwned + Eq + PartialEq + Debug,
     {
@@ -246,3 +476,479 @@ fn std_eio_loopback() {
         ],
     );
 }
+
+#[test]
+fn enum_representations() {
+    use postcard::{to_slice, Error};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum External {
+        Start { at: u8 },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(tag = "type")]
+    enum Internal {
+        Start { at: u8 },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(tag = "t", content = "c")]
+    enum Adjacent {
+        Start { at: u8 },
+        Level(u16),
+        Move(u8, u8),
+        Stop,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    #[serde(untagged)]
+    enum Untagged {
+        Start { at: u8 },
+    }
+
+    let mut buf = [0u8; 32];
+
+    // Externally tagged enums (the default) round trip
+    let used = to_slice(&External::Start { at: 5 }, &mut buf).unwrap();
+    assert_eq!(used, &[0x00, 0x05]);
+    assert_eq!(from_bytes(used), Ok(External::Start { at: 5 }));
+
+    // Adjacently tagged enums encode their tag like a unit variant, followed by the
+    // content, which is the same as the externally tagged representation. Newtype and
+    // tuple variants round trip, struct and unit variants need `deserialize_any`.
+    let used = to_slice(&Adjacent::Level(300), &mut buf).unwrap();
+    assert_eq!(used, &[0x01, 0xAC, 0x02]);
+    assert_eq!(from_bytes(used), Ok(Adjacent::Level(300)));
+
+    let used = to_slice(&Adjacent::Move(1, 2), &mut buf).unwrap();
+    assert_eq!(used, &[0x02, 0x01, 0x02]);
+    assert_eq!(from_bytes(used), Ok(Adjacent::Move(1, 2)));
+    let res = from_bytes::<Adjacent>(&[0x04, 0x05]);
+    assert_eq!(res, Err(Error::SerdeDeCustom));
+
+    let used = to_slice(&Adjacent::Start { at: 5 }, &mut buf).unwrap();
+    assert_eq!(used, &[0x00, 0x05]);
+    assert_eq!(from_bytes::<Adjacent>(used), Err(Error::WontImplement));
+
+    let used = to_slice(&Adjacent::Stop, &mut buf).unwrap();
+    assert_eq!(used, &[0x03]);
+    assert_eq!(from_bytes::<Adjacent>(used), Err(Error::WontImplement));
+
+    // The other representations serialize, but need a self-describing format
+    // (and `deserialize_any`) to deserialize
+    let used = to_slice(&Internal::Start { at: 5 }, &mut buf).unwrap();
+    assert_eq!(used, &[0x05, b'S', b't', b'a', b'r', b't', 0x05]);
+    assert_eq!(from_bytes::<Internal>(used), Err(Error::WontImplement));
+
+    let used = to_slice(&Untagged::Start { at: 5 }, &mut buf).unwrap();
+    assert_eq!(used, &[0x05]);
+    assert_eq!(from_bytes::<Untagged>(used), Err(Error::WontImplement));
+}
+
+#[test]
+fn ranges() {
+    use core::ops::{Range, RangeInclusive};
+    use postcard::to_slice;
+
+    let mut buf = [0u8; 32];
+
+    // Both ranges are encoded as their two bounds, back to back
+    let used = to_slice(&(0u32..100), &mut buf).unwrap();
+    assert_eq!(used, &[0x00, 0x64]);
+    assert_eq!(from_bytes::<Range<u32>>(used), Ok(0..100));
+
+    let used = to_slice(&(300u32..=70000), &mut buf).unwrap();
+    assert_eq!(used, &[0xAC, 0x02, 0xF0, 0xA2, 0x04]);
+    assert_eq!(from_bytes::<RangeInclusive<u32>>(used), Ok(300..=70000));
+
+    // An exhausted `RangeInclusive` is not distinguished on the wire
+    let mut exhausted = 5u32..=5;
+    assert_eq!(exhausted.next(), Some(5));
+    let used = to_slice(&exhausted, &mut buf).unwrap();
+    assert_eq!(used, &[0x05, 0x05]);
+
+    // Reversed (empty) ranges round trip unchanged
+    let reversed = Range {
+        start: 7u32,
+        end: 3,
+    };
+    let used = to_slice(&reversed, &mut buf).unwrap();
+    assert_eq!(from_bytes::<Range<u32>>(used), Ok(reversed));
+}
+
+#[cfg(feature = "use-std")]
+#[test]
+fn c_strings() {
+    use postcard::{to_stdvec, Error};
+    use std::ffi::{CStr, CString};
+
+    // C strings are encoded like a byte slice, without the terminating nul
+    let input = CString::new("hi").unwrap();
+    let ser = to_stdvec(&input).unwrap();
+    assert_eq!(ser, &[0x02, b'h', b'i']);
+    assert_eq!(from_bytes::<CString>(&ser), Ok(input.clone()));
+    assert_eq!(to_stdvec(input.as_c_str()).unwrap(), ser);
+
+    let boxed: Box<CStr> = from_bytes(&ser).unwrap();
+    assert_eq!(&*boxed, input.as_c_str());
+
+    let empty = to_stdvec(&CString::default()).unwrap();
+    assert_eq!(empty, &[0x00]);
+    assert_eq!(from_bytes::<CString>(&empty), Ok(CString::default()));
+
+    // Interior (and trailing) nul bytes are rejected
+    let res = from_bytes::<CString>(&[0x03, b'h', 0x00, b'i']);
+    assert_eq!(res, Err(Error::SerdeDeCustom));
+    let res = from_bytes::<CString>(&[0x03, b'h', b'i', 0x00]);
+    assert_eq!(res, Err(Error::SerdeDeCustom));
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn spsc_producer() {
+    use heapless::spsc::Queue;
+    use postcard::ser_flavors::SpscProducer;
+    use postcard::{serialize_with_flavor, to_spsc, Error};
+
+    let mut queue: Queue<u8, 16> = Queue::new();
+    let (mut producer, mut consumer) = queue.split();
+    let input = BasicU8S {
+        st: 0xFFFF,
+        ei: 0x42,
+        sf: 0x1234_5678,
+        tt: 7,
+    };
+
+    // Start off-center, so that the message wraps around the end of the queue
+    for _ in 0..10 {
+        producer.enqueue(0xEE).unwrap();
+        consumer.dequeue().unwrap();
+    }
+
+    let used = to_spsc(&input, &mut producer).unwrap();
+    assert_eq!(used, 10);
+    let mut buf: Vec<u8, 16> = Vec::new();
+    while let Some(byte) = consumer.dequeue() {
+        buf.push(byte).unwrap();
+    }
+    assert_eq!(buf.len(), used);
+    assert_eq!(from_bytes::<BasicU8S>(&buf), Ok(input));
+
+    // A message that doesn't fit is rejected before anything is enqueued
+    let big = [0xAAu8; 15];
+    assert_eq!(to_spsc(&big, &mut producer), Ok(15));
+    assert_eq!(
+        to_spsc(&0u8, &mut producer),
+        Err(Error::SerializeBufferFull)
+    );
+    assert_eq!(producer.len(), 15);
+    for _ in 0..15 {
+        consumer.dequeue().unwrap();
+    }
+
+    // The flavor on its own stops when the queue fills up, keeping the bytes so far
+    let big = [0xAAu8; 16];
+    let res = serialize_with_flavor::<_, _, usize>(&big, SpscProducer::new(&mut producer));
+    assert_eq!(res, Err(Error::SerializeBufferFull));
+    assert_eq!(consumer.len(), 15);
+}
+
+#[test]
+fn single_variant_enums() {
+    use postcard::to_slice;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum OnlyUnit {
+        Only,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum OnlyNewtype {
+        Only(u8),
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Extended {
+        Only,
+        Added,
+    }
+
+    let mut buf = [0u8; 8];
+
+    // The discriminant is always written, even when there is only one variant,
+    // so that variants can be added later on without breaking the wire format
+    let used = to_slice(&OnlyUnit::Only, &mut buf).unwrap();
+    assert_eq!(used, &[0x00]);
+    assert_eq!(from_bytes(used), Ok(OnlyUnit::Only));
+    assert_eq!(from_bytes(used), Ok(Extended::Only));
+
+    let used = to_slice(&OnlyNewtype::Only(7), &mut buf).unwrap();
+    assert_eq!(used, &[0x00, 0x07]);
+    assert_eq!(from_bytes(used), Ok(OnlyNewtype::Only(7)));
+
+    // ...and checked when deserializing
+    assert!(from_bytes::<OnlyUnit>(&[0x01]).is_err());
+}
+
+#[test]
+fn wrapping_integers() {
+    use core::num::Wrapping;
+    use postcard::to_slice;
+
+    let mut buf = [0u8; 16];
+    let mut bare = [0u8; 16];
+
+    // `Wrapping<T>` is encoded exactly like the bare integer
+    let used = to_slice(&Wrapping(0xFFu8), &mut buf).unwrap();
+    assert_eq!(used, &[0xFF]);
+    assert_eq!(from_bytes(used), Ok(Wrapping(0xFFu8)));
+
+    for val in [0, 127, 128, 300, u32::MAX] {
+        let used = to_slice(&Wrapping(val), &mut buf).unwrap();
+        assert_eq!(used, to_slice(&val, &mut bare).unwrap());
+        assert_eq!(from_bytes(used), Ok(Wrapping(val)));
+    }
+
+    for val in [0, 1 << 35, u64::MAX] {
+        let used = to_slice(&Wrapping(val), &mut buf).unwrap();
+        assert_eq!(used, to_slice(&val, &mut bare).unwrap());
+        assert_eq!(from_bytes(used), Ok(Wrapping(val)));
+    }
+
+    // A counter that overflowed
+    let counter = Wrapping(u32::MAX) + Wrapping(2);
+    let used = to_slice(&counter, &mut buf).unwrap();
+    assert_eq!(used, &[0x01]);
+    assert_eq!(from_bytes(used), Ok(Wrapping(1u32)));
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn marker_fields() {
+    use core::marker::{PhantomData, PhantomPinned};
+    use postcard::to_slice;
+
+    // serde has no impls for `PhantomPinned`, so it is skipped, and recreated with
+    // `Default` when deserializing
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Pinned<T> {
+        id: u16,
+        #[serde(skip)]
+        _pin: PhantomPinned,
+        _kind: PhantomData<T>,
+        _fn: PhantomData<fn() -> T>,
+        name: heapless::String<8>,
+        _unit: (),
+    }
+
+    #[derive(Serialize)]
+    struct Bare<'a> {
+        id: u16,
+        name: &'a str,
+    }
+
+    let mut buf = [0u8; 16];
+    let mut bare_buf = [0u8; 16];
+    let input = Pinned::<u64> {
+        id: 300,
+        _pin: PhantomPinned,
+        _kind: PhantomData,
+        _fn: PhantomData,
+        name: "pin".into(),
+        _unit: (),
+    };
+    let used = to_slice(&input, &mut buf).unwrap();
+    let bare = to_slice(
+        &Bare {
+            id: 300,
+            name: "pin",
+        },
+        &mut bare_buf,
+    )
+    .unwrap();
+    assert_eq!(used, bare);
+    assert_eq!(used, &[0xAC, 0x02, 0x03, b'p', b'i', b'n']);
+    assert_eq!(from_bytes::<Pinned<u64>>(used), Ok(input));
+
+    // Markers on their own take no bytes at all
+    assert!(to_slice(&PhantomData::<u32>, &mut buf).unwrap().is_empty());
+    assert_eq!(from_bytes::<PhantomData<u32>>(&[]), Ok(PhantomData));
+}
+
+#[test]
+fn math_vectors() {
+    use postcard::to_slice;
+
+    let mut buf = [0u8; 32];
+
+    // Fixed size vectors are tuples of floats, without a length prefix
+    let v = glam::Vec3::new(1.0, -2.0, 0.5);
+    let used = to_slice(&v, &mut buf).unwrap();
+    assert_eq!(used.len(), 12);
+    assert_eq!(&used[..4], &1.0f32.to_le_bytes());
+    assert_eq!(&used[4..8], &(-2.0f32).to_le_bytes());
+    assert_eq!(&used[8..], &0.5f32.to_le_bytes());
+    assert_eq!(from_bytes::<glam::Vec3>(used), Ok(v));
+
+    let q = glam::Quat::from_xyzw(0.0, 0.0, 0.5, 0.75);
+    let used = to_slice(&q, &mut buf).unwrap();
+    assert_eq!(used.len(), 16);
+    assert_eq!(&used[8..12], &0.5f32.to_le_bytes());
+    assert_eq!(from_bytes::<glam::Quat>(used), Ok(q));
+
+    let n = nalgebra::Vector3::new(1.0f32, -2.0, 0.5);
+    let used = to_slice(&n, &mut buf).unwrap();
+    assert_eq!(used.len(), 12);
+    assert_eq!(
+        used,
+        to_slice(&[1.0f32, -2.0, 0.5], &mut [0u8; 12]).unwrap()
+    );
+    assert_eq!(from_bytes::<nalgebra::Vector3<f32>>(used), Ok(n));
+}
+
+#[test]
+fn serde_bytes_arrays() {
+    use postcard::to_slice;
+    use serde_bytes::{ByteArray, Bytes};
+
+    let mut buf = [0u8; 64];
+    let key: [u8; 32] = core::array::from_fn(|i| i as u8);
+
+    // `ByteArray` is a serde byte array, which always carries its length
+    let input = ByteArray::new(key);
+    let used = to_slice(&input, &mut buf).unwrap();
+    assert_eq!(used.len(), 1 + 32);
+    assert_eq!(used[0], 32);
+    assert_eq!(&used[1..], &key);
+    assert_eq!(from_bytes::<ByteArray<32>>(used), Ok(input));
+    assert!(from_bytes::<ByteArray<16>>(used).is_err());
+
+    // A plain array is a tuple, without a length
+    let used = to_slice(&key, &mut buf).unwrap();
+    assert_eq!(used, &key);
+
+    // `&Bytes` is borrowed from the input
+    let used = to_slice(Bytes::new(&key), &mut buf).unwrap();
+    let out: &Bytes = from_bytes(used).unwrap();
+    assert_eq!(&**out, &key);
+    assert!(core::ptr::eq(out.as_ptr(), used[1..].as_ptr()));
+}
+
+#[cfg(feature = "use-std")]
+#[test]
+fn std_paths() {
+    use postcard::to_stdvec;
+    use std::path::{Path, PathBuf};
+
+    // Paths are length prefixed strings
+    let path = PathBuf::from("/tmp/x");
+    let serialized = to_stdvec(&path).unwrap();
+    assert_eq!(serialized, to_stdvec("/tmp/x").unwrap());
+    assert_eq!(serialized, &[0x06, b'/', b't', b'm', b'p', b'/', b'x']);
+    assert_eq!(from_bytes::<PathBuf>(&serialized), Ok(path));
+
+    // ...which `&Path` borrows from the input
+    let borrowed: &Path = from_bytes(&serialized).unwrap();
+    assert_eq!(borrowed, Path::new("/tmp/x"));
+    assert_eq!(
+        borrowed.as_os_str().as_encoded_bytes().as_ptr(),
+        serialized[1..].as_ptr()
+    );
+    assert_eq!(to_stdvec(borrowed).unwrap(), serialized);
+
+    // Invalid UTF-8 is rejected, as for any string
+    assert_eq!(
+        from_bytes::<PathBuf>(&[0x02, 0xFF, 0xFE]),
+        Err(postcard::Error::DeserializeBadUtf8)
+    );
+
+    // serde can't serialize paths which are not valid UTF-8
+    #[cfg(unix)]
+    {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let path = Path::new(OsStr::from_bytes(&[b'/', 0xFF]));
+        assert_eq!(to_stdvec(path), Err(postcard::Error::SerdeSerCustom));
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn field_sorted() {
+    extern crate alloc;
+
+    use alloc::{string::String, vec::Vec};
+    use postcard::{from_bytes_field_sorted, to_allocvec, to_allocvec_field_sorted};
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Unit {
+        Celsius,
+        Scaled { offset: i8, factor: u8 },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Before {
+        name: String,
+        id: u16,
+        readings: Vec<(u8, Option<Unit>)>,
+        active: bool,
+    }
+
+    // The same fields, declared in a different order
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct After {
+        readings: Vec<(u8, Option<Unit>)>,
+        active: bool,
+        id: u16,
+        name: String,
+    }
+
+    let before = Before {
+        name: "t1".into(),
+        id: 300,
+        readings: alloc::vec![
+            (1, None),
+            (
+                2,
+                Some(Unit::Scaled {
+                    offset: -1,
+                    factor: 3
+                })
+            )
+        ],
+        active: true,
+    };
+    let after = After {
+        readings: alloc::vec![
+            (1, None),
+            (
+                2,
+                Some(Unit::Scaled {
+                    offset: -1,
+                    factor: 3
+                })
+            )
+        ],
+        active: true,
+        id: 300,
+        name: "t1".into(),
+    };
+
+    let sorted = to_allocvec_field_sorted(&before).unwrap();
+    assert_eq!(sorted, to_allocvec_field_sorted(&after).unwrap());
+    assert_eq!(
+        sorted,
+        &[
+            0x01, // active
+            0xAC, 0x02, // id
+            0x02, b't', b'1', // name
+            0x02, 0x01, 0x00, 0x02, 0x01, 0x01, 0x03, 0xFF, // readings, with `factor` first
+        ]
+    );
+    assert_ne!(to_allocvec(&before).unwrap(), to_allocvec(&after).unwrap());
+
+    assert_eq!(from_bytes_field_sorted::<Before>(&sorted), Ok(before));
+    assert_eq!(from_bytes_field_sorted::<After>(&sorted), Ok(after));
+}
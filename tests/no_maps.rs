@@ -0,0 +1,58 @@
+#![allow(unused_imports)]
+
+#[cfg(feature = "experimental-derive")]
+mod tests {
+    use core::marker::PhantomData;
+    use postcard::experimental::no_maps::NoMaps;
+    use std::collections::BTreeMap;
+
+    // Answers whether `T: NoMaps` for a concrete type, without failing to compile
+    trait Fallback {
+        const IMPLS_NO_MAPS: bool = false;
+    }
+
+    impl<T: ?Sized> Fallback for Probe<T> {}
+
+    struct Probe<T: ?Sized>(PhantomData<T>);
+
+    #[allow(dead_code)]
+    impl<T: ?Sized + NoMaps> Probe<T> {
+        const IMPLS_NO_MAPS: bool = true;
+    }
+
+    #[allow(dead_code)]
+    #[derive(NoMaps)]
+    struct Reading<'a> {
+        id: u16,
+        label: &'a str,
+        samples: [Option<i32>; 4],
+    }
+
+    #[allow(dead_code)]
+    #[derive(NoMaps)]
+    enum Message<T> {
+        Ping,
+        Reading(T),
+        Batch { items: heapless::Vec<T, 8> },
+    }
+
+    #[allow(dead_code)]
+    struct WithMap {
+        values: BTreeMap<u8, u16>,
+    }
+
+    #[test]
+    fn no_maps() {
+        // Checked at compile time, the test only has to build
+        const { assert!(Probe::<u32>::IMPLS_NO_MAPS) };
+        const { assert!(Probe::<(bool, &[u8])>::IMPLS_NO_MAPS) };
+        const { assert!(Probe::<Reading<'static>>::IMPLS_NO_MAPS) };
+        const { assert!(Probe::<Message<Reading<'static>>>::IMPLS_NO_MAPS) };
+
+        // Maps, and types containing maps, don't implement `NoMaps`
+        const { assert!(!Probe::<BTreeMap<u8, u16>>::IMPLS_NO_MAPS) };
+        const { assert!(!Probe::<WithMap>::IMPLS_NO_MAPS) };
+        const { assert!(!Probe::<Message<BTreeMap<u8, u16>>>::IMPLS_NO_MAPS) };
+        const { assert!(!Probe::<Option<heapless::FnvIndexMap<u8, u8, 4>>>::IMPLS_NO_MAPS) };
+    }
+}
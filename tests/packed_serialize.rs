@@ -0,0 +1,109 @@
+#![cfg(feature = "experimental-derive")]
+
+use postcard::experimental::packed::PackedSerialize;
+use postcard::{from_bytes, to_slice, Error};
+use serde::{Deserialize, Serialize};
+
+#[derive(PackedSerialize, Debug, PartialEq, Clone)]
+struct Status {
+    powered: bool,
+    id: u16,
+    charging: bool,
+    fault: bool,
+    door_open: bool,
+    name: heapless::String<8>,
+    armed: bool,
+    muted: bool,
+    night: bool,
+    eco: bool,
+    locked: bool,
+    synced: bool,
+}
+
+// The same fields, with serde's own derives
+#[derive(Serialize, Deserialize)]
+struct Unpacked {
+    powered: bool,
+    id: u16,
+    charging: bool,
+    fault: bool,
+    door_open: bool,
+    name: heapless::String<8>,
+    armed: bool,
+    muted: bool,
+    night: bool,
+    eco: bool,
+    locked: bool,
+    synced: bool,
+}
+
+#[test]
+fn packed_serialize() {
+    let mut buf = [0u8; 32];
+    let input = Status {
+        powered: true,
+        id: 300,
+        charging: false,
+        fault: false,
+        door_open: true,
+        name: "hall".into(),
+        armed: false,
+        muted: false,
+        night: false,
+        eco: false,
+        locked: false,
+        synced: true,
+    };
+    let used = to_slice(&input, &mut buf).unwrap();
+    // Ten bools take two bytes, followed by the other fields in order
+    assert_eq!(
+        used,
+        &[
+            0b0000_1001,
+            0b0000_0010,
+            0xAC,
+            0x02,
+            0x04,
+            b'h',
+            b'a',
+            b'l',
+            b'l'
+        ]
+    );
+    assert_eq!(from_bytes(used), Ok(input.clone()));
+
+    let unpacked = Unpacked {
+        powered: input.powered,
+        id: input.id,
+        charging: input.charging,
+        fault: input.fault,
+        door_open: input.door_open,
+        name: input.name.clone(),
+        armed: input.armed,
+        muted: input.muted,
+        night: input.night,
+        eco: input.eco,
+        locked: input.locked,
+        synced: input.synced,
+    };
+    let packed_len = used.len();
+    assert_eq!(to_slice(&unpacked, &mut buf).unwrap().len(), packed_len + 8);
+
+    // Unused bits of the bitmap are rejected
+    let res = from_bytes::<Status>(&[0x00, 0x04, 0x00, 0x00]);
+    assert_eq!(res, Err(Error::SerdeDeCustom));
+}
+
+#[derive(PackedSerialize, Debug, PartialEq)]
+struct NoBools {
+    a: u8,
+    b: u8,
+}
+
+#[test]
+fn packed_serialize_without_bools() {
+    let mut buf = [0u8; 8];
+    let used = to_slice(&NoBools { a: 1, b: 2 }, &mut buf).unwrap();
+    assert_eq!(used, &[0x01, 0x02]);
+    assert_eq!(from_bytes(used), Ok(NoBools { a: 1, b: 2 }));
+}
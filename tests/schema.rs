@@ -1,6 +1,9 @@
-#![cfg(feature = "derive")]
+#![cfg(feature = "experimental-derive")]
 
-use postcard::schema::{NamedType, NamedValue, NamedVariant, Schema, SdmTy, Varint};
+use postcard::experimental::schema::{
+    assert_same_wire_format, wire_compatible, NamedType, NamedValue, NamedVariant, Schema, SdmTy,
+    Varint,
+};
 
 const U8_SCHEMA: NamedType = NamedType {
     name: "u8",
@@ -97,7 +100,7 @@ fn test_struct_serialize() {
                     name: "e",
                     ty: &NamedType {
                         name: "[T; N]",
-                        ty: &SdmTy::Seq(&U8_SCHEMA),
+                        ty: &SdmTy::Tuple(&[&U8_SCHEMA; 10]),
                     }
                 }
             ]),
@@ -105,3 +108,402 @@ fn test_struct_serialize() {
         Outer::SCHEMA
     );
 }
+
+#[allow(unused)]
+#[derive(Schema)]
+struct OuterRenamed {
+    alpha: u32,
+    beta: u64,
+    gamma: u8,
+    delta: InnerRenamed,
+    epsilon: [u8; 10],
+}
+
+#[allow(unused)]
+#[derive(Schema)]
+enum InnerRenamed {
+    A,
+    B,
+    C,
+    D { x: i32, y: i16 },
+}
+
+#[allow(unused)]
+#[derive(Schema)]
+struct OuterReordered {
+    b: u64,
+    a: u32,
+    c: u8,
+    d: Inner,
+    e: [u8; 10],
+}
+
+// Checked at compile time
+const _: () = assert_same_wire_format::<Outer, OuterRenamed>();
+
+#[test]
+fn test_wire_compatible() {
+    assert_same_wire_format::<Outer, OuterRenamed>();
+    assert_same_wire_format::<(u32, u32), [u32; 2]>();
+    assert!(wire_compatible(Inner::SCHEMA, InnerRenamed::SCHEMA));
+    assert!(!wire_compatible(Outer::SCHEMA, OuterReordered::SCHEMA));
+    assert!(!wire_compatible(u32::SCHEMA, u64::SCHEMA));
+}
+
+#[test]
+#[should_panic]
+fn test_wire_incompatible() {
+    assert_same_wire_format::<Outer, OuterReordered>();
+}
+
+#[test]
+fn schema_hash() {
+    use postcard::experimental::schema::SchemaHash;
+
+    #[allow(unused)]
+    #[derive(Schema)]
+    struct Renamed {
+        first: u8,
+        second: u16,
+    }
+
+    #[allow(unused)]
+    #[derive(Schema)]
+    struct Wider {
+        first: u8,
+        second: u32,
+    }
+
+    // Names never appear on the wire, and don't change the hash
+    assert_eq!(Renamed::SCHEMA_HASH, <(u8, u16)>::SCHEMA_HASH);
+    assert_ne!(Renamed::SCHEMA_HASH, Wider::SCHEMA_HASH);
+    assert_eq!(Outer::SCHEMA_HASH, OuterRenamed::SCHEMA_HASH);
+    assert_ne!(Outer::SCHEMA_HASH, OuterReordered::SCHEMA_HASH);
+    assert_ne!(u8::SCHEMA_HASH, Option::<u8>::SCHEMA_HASH);
+    assert_ne!(<(u8, u8)>::SCHEMA_HASH, <((u8, u8), ())>::SCHEMA_HASH);
+
+    // Products of a single field are encoded as that field, so they are compatible,
+    // and have the same hash
+    #[allow(unused)]
+    #[derive(Schema)]
+    struct Single {
+        a: u8,
+    }
+
+    assert!(wire_compatible(u32::SCHEMA, <(u32,)>::SCHEMA));
+    assert_eq!(u32::SCHEMA_HASH, <(u32,)>::SCHEMA_HASH);
+    assert!(wire_compatible(u8::SCHEMA, Single::SCHEMA));
+    assert_eq!(u8::SCHEMA_HASH, Single::SCHEMA_HASH);
+    assert!(wire_compatible(
+        <(u8, u16)>::SCHEMA,
+        <((u8,), (u16,))>::SCHEMA
+    ));
+    assert_eq!(<(u8, u16)>::SCHEMA_HASH, <((u8,), (u16,))>::SCHEMA_HASH);
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn versioned() {
+    use postcard::experimental::schema::{from_bytes_versioned, to_vec_versioned};
+    use postcard::Error;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Schema, Debug, PartialEq)]
+    struct ConfigV1 {
+        rate: u16,
+        enabled: bool,
+    }
+
+    #[derive(Serialize, Deserialize, Schema, Debug, PartialEq)]
+    struct ConfigV2 {
+        rate: u16,
+        enabled: bool,
+        gain: i8,
+    }
+
+    #[derive(Serialize, Deserialize, Schema, Debug, PartialEq)]
+    struct ConfigV1Renamed {
+        interval: u16,
+        active: bool,
+    }
+
+    let v1 = ConfigV1 {
+        rate: 100,
+        enabled: true,
+    };
+    let ser = to_vec_versioned::<_, 32>(&v1).unwrap();
+    assert_eq!(&ser[8..], &[0x64, 0x01]);
+    assert_eq!(from_bytes_versioned::<ConfigV1>(&ser), Ok(v1));
+
+    // A structurally different version is rejected, even though its encoding would
+    // otherwise be a valid prefix
+    assert_eq!(
+        from_bytes_versioned::<ConfigV2>(&ser),
+        Err(Error::DeserializeSchemaMismatch)
+    );
+    // A matching structure is accepted
+    assert_eq!(
+        from_bytes_versioned::<ConfigV1Renamed>(&ser),
+        Ok(ConfigV1Renamed {
+            interval: 100,
+            active: true
+        })
+    );
+
+    assert_eq!(
+        from_bytes_versioned::<ConfigV1>(&ser[..7]),
+        Err(Error::DeserializeUnexpectedEnd)
+    );
+    assert_eq!(
+        to_vec_versioned::<_, 8>(&0u8),
+        Err(Error::SerializeBufferFull)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn as_value() {
+    use postcard::experimental::schema::{from_bytes_as_value, PostcardValue};
+    use postcard::Error;
+    use serde::Serialize;
+
+    #[derive(Serialize, Schema)]
+    struct BasicU8S {
+        st: u16,
+        ei: u8,
+        sf: u64,
+        tt: u32,
+    }
+
+    let mut buf = [0u8; 32];
+    let input = BasicU8S {
+        st: 0xABCD,
+        ei: 0xFE,
+        sf: 0x1234_4321_ABCD_DCBA,
+        tt: 0xACAC_ACAC,
+    };
+    let used = postcard::to_slice(&input, &mut buf).unwrap();
+    let value = from_bytes_as_value::<BasicU8S>(used).unwrap();
+    assert_eq!(
+        value,
+        PostcardValue::Struct(vec![
+            ("st", PostcardValue::U16(0xABCD)),
+            ("ei", PostcardValue::U8(0xFE)),
+            ("sf", PostcardValue::U64(0x1234_4321_ABCD_DCBA)),
+            ("tt", PostcardValue::U32(0xACAC_ACAC)),
+        ])
+    );
+
+    let res = from_bytes_as_value::<BasicU8S>(&used[..used.len() - 1]);
+    assert_eq!(res, Err(Error::DeserializeUnexpectedEnd));
+
+    // Enums, sequences, and options
+    #[derive(Serialize, Schema)]
+    enum Event {
+        Idle,
+        Log {
+            level: Option<u8>,
+            lines: Vec<String>,
+        },
+    }
+
+    let input = Event::Log {
+        level: Some(2),
+        lines: vec![String::from("a"), String::from("bc")],
+    };
+    let used = postcard::to_slice(&input, &mut buf).unwrap();
+    let value = from_bytes_as_value::<Event>(used).unwrap();
+    assert_eq!(
+        value,
+        PostcardValue::Enum {
+            variant: "Log",
+            value: Box::new(PostcardValue::Struct(vec![
+                (
+                    "level",
+                    PostcardValue::Option(Some(Box::new(PostcardValue::U8(2))))
+                ),
+                (
+                    "lines",
+                    PostcardValue::Seq(vec![
+                        PostcardValue::String("a"),
+                        PostcardValue::String("bc"),
+                    ])
+                ),
+            ])),
+        }
+    );
+    let used = postcard::to_slice(&Event::Idle, &mut buf).unwrap();
+    assert_eq!(
+        from_bytes_as_value::<Event>(used),
+        Ok(PostcardValue::Enum {
+            variant: "Idle",
+            value: Box::new(PostcardValue::Unit),
+        })
+    );
+    assert_eq!(
+        from_bytes_as_value::<Event>(&[0x02]),
+        Err(Error::DeserializeBadEnum)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn validate_before_decode() {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use postcard::experimental::schema::{from_bytes_validated, validate};
+    use postcard::Error;
+    use serde::{Deserialize, Deserializer, Serialize};
+
+    static BUILT: AtomicUsize = AtomicUsize::new(0);
+
+    // Counts how many times it has been deserialized
+    #[derive(Serialize, Schema, Debug, PartialEq)]
+    struct Tracked(String);
+
+    impl<'de> Deserialize<'de> for Tracked {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            BUILT.fetch_add(1, Ordering::SeqCst);
+            String::deserialize(deserializer).map(Tracked)
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Schema, Debug, PartialEq)]
+    struct Batch {
+        names: Vec<Tracked>,
+        done: bool,
+    }
+
+    let input = Batch {
+        names: vec![Tracked(String::from("a")), Tracked(String::from("bc"))],
+        done: true,
+    };
+    let mut buf = [0u8; 32];
+    let used = postcard::to_slice(&input, &mut buf).unwrap();
+    assert_eq!(validate::<Batch>(used), Ok(()));
+
+    // Each malformed message fails validation, without building any part of it
+    let mut bad_bool = used.to_vec();
+    *bad_bool.last_mut().unwrap() = 0x02;
+    let mut bad_utf8 = used.to_vec();
+    bad_utf8[2] = 0xFF;
+    let cases: [(&[u8], Error); 3] = [
+        (&bad_bool, Error::DeserializeBadBool),
+        (&bad_utf8, Error::DeserializeBadUtf8),
+        (&used[..used.len() - 1], Error::DeserializeUnexpectedEnd),
+    ];
+    for (bytes, err) in cases {
+        assert_eq!(validate::<Batch>(bytes), Err(err.clone()));
+        assert_eq!(from_bytes_validated::<Batch>(bytes), Err(err));
+    }
+    assert_eq!(BUILT.load(Ordering::SeqCst), 0);
+
+    // A valid message passes, then decodes
+    assert_eq!(from_bytes_validated::<Batch>(used), Ok(input));
+    assert_eq!(BUILT.load(Ordering::SeqCst), 2);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn cbor_bridge() {
+    use postcard::experimental::schema::to_cbor_via_postcard;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Schema, Debug, PartialEq)]
+    enum Mode {
+        Off,
+        Fixed(u16),
+        Ramp { from: i32, to: i32 },
+    }
+
+    #[derive(Serialize, Deserialize, Schema, Debug, PartialEq)]
+    struct Status {
+        id: u8,
+        temp: f32,
+        offset: i64,
+        big: u128,
+        name: String,
+        raw: Vec<u8>,
+        tag: Option<char>,
+        limits: (i8, u16),
+        modes: Vec<Mode>,
+    }
+
+    let input = Status {
+        id: 7,
+        temp: 21.5,
+        offset: -1_000_000_000_000,
+        big: u128::MAX - 1,
+        name: String::from("gateway"),
+        raw: vec![0xDE, 0xAD],
+        tag: Some('x'),
+        limits: (-128, 65535),
+        modes: vec![Mode::Off, Mode::Fixed(300), Mode::Ramp { from: -5, to: 5 }],
+    };
+    let bytes = postcard::to_allocvec(&input).unwrap();
+
+    // postcard -> CBOR -> postcard gives back the original bytes
+    let cbor = to_cbor_via_postcard::<Status>(&bytes).unwrap();
+    let bridged: Status = ciborium::from_reader(cbor.as_slice()).unwrap();
+    assert_eq!(bridged, input);
+    assert_eq!(postcard::to_allocvec(&bridged).unwrap(), bytes);
+
+    // Truncated messages are rejected
+    assert_eq!(
+        to_cbor_via_postcard::<Status>(&bytes[..bytes.len() - 1]),
+        Err(postcard::Error::DeserializeUnexpectedEnd)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn read_single_field() {
+    use postcard::experimental::schema::from_bytes_field;
+    use postcard::Error;
+    use serde::Serialize;
+
+    #[derive(Serialize, Schema)]
+    struct Record {
+        id: u32,
+        name: String,
+        samples: Vec<(i16, Option<u8>)>,
+        notes: String,
+        checksum: u64,
+    }
+
+    let record = Record {
+        id: 70_000,
+        name: String::from("probe"),
+        samples: vec![(-3, None), (400, Some(2))],
+        notes: String::from("calibrated"),
+        checksum: u64::MAX,
+    };
+    let mut buf = [0u8; 64];
+    let used = postcard::to_slice(&record, &mut buf).unwrap();
+
+    let samples: Vec<(i16, Option<u8>)> = from_bytes_field::<Record, _>(used, "samples").unwrap();
+    assert_eq!(samples, record.samples);
+
+    // The fields after it are never read, so they may be truncated or malformed
+    let end = used.len() - "calibrated".len() - 1 - 10;
+    let mut partial = used[..end].to_vec();
+    partial.extend_from_slice(&[0xFF; 3]);
+    assert_eq!(
+        from_bytes_field::<Record, Vec<(i16, Option<u8>)>>(&partial, "samples"),
+        Ok(record.samples)
+    );
+    assert_eq!(from_bytes_field::<Record, u32>(&partial, "id"), Ok(70_000));
+    assert_eq!(
+        from_bytes_field::<Record, String>(&partial, "notes"),
+        Err(Error::DeserializeUnexpectedEnd)
+    );
+
+    // Only named fields can be read
+    assert_eq!(
+        from_bytes_field::<Record, u8>(used, "missing"),
+        Err(Error::SerdeDeCustom)
+    );
+    assert_eq!(
+        from_bytes_field::<(u8, u8), u8>(&[1, 2], "0"),
+        Err(Error::SerdeDeCustom)
+    );
+}
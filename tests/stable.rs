@@ -0,0 +1,47 @@
+use postcard::{postcard_stable, stable::assert_layout};
+use serde::{Deserialize, Serialize};
+
+mod v1 {
+    use super::*;
+
+    postcard_stable! {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        pub struct Telemetry {
+            pub id: u16,
+            pub temp: i32,
+            #[serde(default)]
+            pub tags: Vec<String>,
+        }
+    }
+}
+
+mod v2 {
+    use super::*;
+
+    // The same struct, with two fields swapped during a refactor
+    postcard_stable! {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        pub struct Telemetry {
+            pub temp: i32,
+            pub id: u16,
+            #[serde(default)]
+            pub tags: Vec<String>,
+        }
+    }
+}
+
+const SNAPSHOT: &str = "id: u16; temp: i32; tags: Vec<String>;";
+
+#[test]
+fn layout_matches_snapshot() {
+    assert_layout::<v1::Telemetry>(SNAPSHOT);
+
+    // Whitespace does not matter
+    assert_layout::<v1::Telemetry>("id:u16;temp:i32;tags:Vec<String>;");
+}
+
+#[test]
+#[should_panic(expected = "wire format layout changed")]
+fn reordered_fields_fail() {
+    assert_layout::<v2::Telemetry>(SNAPSHOT);
+}
@@ -0,0 +1,73 @@
+//! Round trips of types from third party crates, using their own serde support.
+
+use bitflags::bitflags;
+use postcard::{from_bytes, to_slice};
+use serde::{Deserialize, Serialize};
+
+bitflags! {
+    #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+    struct Status: u32 {
+        const READY = 1 << 0;
+        const BUSY = 1 << 1;
+        const OVERRUN = 1 << 7;
+        const FAULT = 1 << 31;
+    }
+}
+
+#[test]
+fn bitflags() {
+    let mut buf = [0u8; 32];
+
+    // Postcard is not human readable, so the flags are encoded as their
+    // underlying integer, which is a varint like any other `u32`.
+    let cases: [(Status, &[u8]); 4] = [
+        (Status::empty(), &[0x00]),
+        (Status::READY | Status::BUSY, &[0x03]),
+        (Status::OVERRUN, &[0x80, 0x01]),
+        (Status::all(), &[0x83, 0x81, 0x80, 0x80, 0x08]),
+    ];
+
+    for (data, ser_rep) in cases {
+        let used = to_slice(&data, &mut buf).unwrap();
+        assert_eq!(used, ser_rep);
+        assert_eq!(from_bytes::<Status>(used).unwrap(), data);
+    }
+
+    // Unknown bits are retained, matching `Status::from_bits_retain`
+    let out: Status = from_bytes(&[0x84, 0x01]).unwrap();
+    assert_eq!(out, Status::from_bits_retain(0x84));
+}
+
+#[cfg(feature = "use-smallvec")]
+#[test]
+fn smallvec() {
+    use postcard::{from_bytes_into, to_allocvec};
+    use smallvec::SmallVec;
+
+    type Bytes = SmallVec<[u8; 16]>;
+
+    // Stays inline
+    let data: Bytes = (0..16).collect();
+    let ser = to_allocvec(&data).unwrap();
+    assert_eq!(ser[0], 16);
+    assert_eq!(&ser[1..], data.as_slice());
+    let out: Bytes = from_bytes(&ser).unwrap();
+    assert_eq!(out, data);
+    assert!(!out.spilled());
+
+    // Spills to the heap. The length prefix is passed on as an exact size hint,
+    // which `SmallVec` uses to reserve its heap buffer once, up front.
+    let data: Bytes = (0..40).collect();
+    let ser = to_allocvec(&data).unwrap();
+    let out: Bytes = from_bytes(&ser).unwrap();
+    assert_eq!(out, data);
+    assert!(out.spilled());
+    assert_eq!(out.capacity(), 40usize.next_power_of_two());
+
+    // Refilling an existing `SmallVec` keeps its storage
+    let mut target = out;
+    from_bytes_into(&[0x02, 0x0A, 0x0B], &mut target).unwrap();
+    assert_eq!(target.as_slice(), &[0x0A, 0x0B]);
+    assert!(target.spilled());
+    assert_eq!(target.capacity(), 64);
+}